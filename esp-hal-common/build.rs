@@ -15,16 +15,6 @@ fn main() {
         n => panic!("Exactly 1 chip must be enabled via its Cargo feature, {n} provided"),
     }
 
-    if cfg!(feature = "esp32") && cfg!(feature = "esp32_40mhz") && cfg!(feature = "esp32_26mhz") {
-        panic!("Only one xtal speed feature can be selected");
-    }
-    if cfg!(feature = "esp32c2")
-        && cfg!(feature = "esp32c2_40mhz")
-        && cfg!(feature = "esp32c2_26mhz")
-    {
-        panic!("Only one xtal speed feature can be selected");
-    }
-
     // Define all required configuration symbols for the enabled chip.
     //
     // When adding a new device, at the bare minimum the following symbols MUST be
@@ -36,6 +26,7 @@ fn main() {
     // Additionally, the following symbols MAY be defined if present:
     //   - 'aes'
     //   - 'dac'
+    //   - 'etm'
     //   - 'gdma'
     //   - 'i2c1'
     //   - 'i2s'
@@ -108,6 +99,7 @@ fn main() {
             "riscv",
             "single_core",
             "aes",
+            "etm",
             "gdma",
             "i2s",
             "mcpwm",
@@ -4,10 +4,14 @@ use embedded_hal::adc::{Channel, OneShot};
 
 use crate::{
     analog::{ADC1, ADC2},
+    efuse::Efuse,
     peripheral::PeripheralRef,
-    peripherals::{RTCIO, SENS},
+    peripherals::{RTCIO, RTC_CNTL, SENS},
 };
 
+/// Nominal reference voltage of the ADC in millivolts
+const NOMINAL_VREF: u16 = 1100;
+
 /// The sampling/readout resolution of the ADC
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Resolution {
@@ -25,6 +29,43 @@ pub enum Attenuation {
     Attenuation11dB  = 0b11,
 }
 
+/// Reference voltage used to convert readings into millivolts
+///
+/// The internal reference of the ADC nominally is 1100 mV, but varies from
+/// chip to chip between roughly 1000 mV and 1200 mV, which directly scales all
+/// readings. Most chips have the actual value burned into eFuse, see
+/// [`AdcCalibration::from_efuse`]. For other chips it can be routed to a pin
+/// with [`ADC::enable_vref_output`], measured with a voltmeter and entered
+/// with [`AdcCalibration::from_vref`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AdcCalibration {
+    vref: u16,
+}
+
+impl AdcCalibration {
+    /// Calibration from the reference voltage burned into eFuse, if any
+    pub fn from_efuse() -> Option<Self> {
+        Efuse::get_adc_vref().map(Self::from_vref)
+    }
+
+    /// Calibration from a measured reference voltage, in millivolts
+    pub fn from_vref(vref: u16) -> Self {
+        Self { vref }
+    }
+
+    /// The reference voltage, in millivolts
+    pub fn vref(&self) -> u16 {
+        self.vref
+    }
+}
+
+impl Default for AdcCalibration {
+    /// The eFuse calibration, or the nominal reference voltage if there is none
+    fn default() -> Self {
+        Self::from_efuse().unwrap_or(Self::from_vref(NOMINAL_VREF))
+    }
+}
+
 pub struct AdcPin<PIN, ADCI> {
     pub pin: PIN,
     _phantom: PhantomData<ADCI>,
@@ -78,6 +119,13 @@ impl<ADCI> Default for AdcConfig<ADCI> {
 }
 
 pub trait RegisterAccess {
+    /// Gain of the attenuations relative to the reference voltage, scaled by
+    /// 65536, from `esp_adc_cal`
+    const ATTENUATION_SCALE: [u32; 4];
+
+    /// Offset of the attenuations in millivolts, from `esp_adc_cal`
+    const ATTENUATION_OFFSET: [u32; 4];
+
     fn set_bit_width(resolution: u8);
 
     fn set_sample_bit(resolution: u8);
@@ -103,6 +151,9 @@ pub trait RegisterAccess {
 
 #[doc(hidden)]
 impl RegisterAccess for ADC1 {
+    const ATTENUATION_SCALE: [u32; 4] = [57431, 76236, 105481, 196602];
+    const ATTENUATION_OFFSET: [u32; 4] = [75, 78, 88, 129];
+
     fn set_bit_width(resolution: u8) {
         let sensors = unsafe { &*SENS::ptr() };
         sensors
@@ -181,6 +232,9 @@ impl RegisterAccess for ADC1 {
 }
 
 impl RegisterAccess for ADC2 {
+    const ATTENUATION_SCALE: [u32; 4] = [57236, 76175, 105678, 197694];
+    const ATTENUATION_OFFSET: [u32; 4] = [78, 79, 77, 167];
+
     fn set_bit_width(resolution: u8) {
         let sensors = unsafe { &*SENS::ptr() };
         sensors
@@ -261,7 +315,12 @@ impl RegisterAccess for ADC2 {
 pub struct ADC<'d, ADC> {
     _adc: PeripheralRef<'d, ADC>,
     attenuations: [Option<Attenuation>; 10],
+    resolution: Resolution,
+    calibration: AdcCalibration,
     active_channel: Option<u8>,
+    oversampling: u16,
+    sum: u32,
+    samples: u16,
 }
 
 impl<'d, ADCI> ADC<'d, ADCI>
@@ -285,7 +344,7 @@ where
 
         for channel in 0..attenuations.len() {
             if let Some(attenuation) = attenuations[channel] {
-                ADC1::set_attenuation(channel, attenuation as u8);
+                ADCI::set_attenuation(channel, attenuation as u8);
             }
         }
 
@@ -331,11 +390,146 @@ where
         let adc = ADC {
             _adc: adc_instance.into_ref(),
             attenuations: config.attenuations,
+            resolution: config.resolution,
+            calibration: AdcCalibration::default(),
             active_channel: None,
+            oversampling: 1,
+            sum: 0,
+            samples: 0,
         };
 
         Ok(adc)
     }
+
+    /// Average `samples` conversions for every value returned by `read`
+    ///
+    /// Averaging reduces uncorrelated noise by a factor of `sqrt(samples)`,
+    /// i.e. every quadrupling of the number of samples gains about one bit of
+    /// effective resolution, up to the limits set by the non-linearity of the
+    /// ADC. The time a reading takes grows linearly with `samples`. The
+    /// conversions are done back to back, so this helps against noise, not
+    /// against slowly drifting inputs.
+    ///
+    /// The default of 1 disables averaging.
+    pub fn set_oversampling(&mut self, samples: u16) {
+        assert!(samples > 0, "At least one sample is needed");
+
+        self.oversampling = samples;
+        self.sum = 0;
+        self.samples = 0;
+    }
+
+    /// Replace the calibration used by [`ADC::read_millivolts`]
+    ///
+    /// Defaults to [`AdcCalibration::default`].
+    pub fn set_calibration(&mut self, calibration: AdcCalibration) {
+        self.calibration = calibration;
+    }
+
+    /// The calibration used by [`ADC::read_millivolts`]
+    pub fn calibration(&self) -> AdcCalibration {
+        self.calibration
+    }
+
+    /// Read the voltage at `pin` in millivolts
+    ///
+    /// The raw reading is converted using the reference voltage of the
+    /// calibration and the characteristics of the configured attenuation, like
+    /// `esp_adc_cal` in ESP-IDF does. The result is accurate to a few percent,
+    /// less so towards the ends of the range of the attenuation.
+    pub fn read_millivolts<PIN>(&mut self, pin: &mut AdcPin<PIN, ADCI>) -> nb::Result<u16, ()>
+    where
+        PIN: Channel<ADCI, ID = u8>,
+    {
+        let raw: u16 = self.read(pin)?;
+
+        // the characteristics are given for 12 bit readings
+        let raw = (raw as u32) << (Resolution::Resolution12Bit as u32 - self.resolution as u32);
+
+        let attenuation = self.attenuations[PIN::channel() as usize].unwrap() as usize;
+        let scale = self.calibration.vref as u32 * ADCI::ATTENUATION_SCALE[attenuation] / 4096;
+        let millivolts = (scale * raw + 32768) / 65536 + ADCI::ATTENUATION_OFFSET[attenuation];
+
+        Ok(millivolts as u16)
+    }
+
+    /// Read the difference between two channels, `positive - negative`
+    ///
+    /// The SAR ADCs of the ESP32 have no differential input mode, so any
+    /// two channels of this ADC can be used as a pair: `positive` is converted
+    /// first and `negative` right after it. The two conversions are a few
+    /// microseconds apart, this suits slowly changing signals like the voltage
+    /// across a current-sense shunt, not fast ones. Oversampling applies to
+    /// both conversions.
+    ///
+    /// Both channels should be configured with the same attenuation, else the
+    /// raw values don't share a scale. The result has the same resolution as a
+    /// single reading, with 12 bit readings it ranges from -4095 to 4095. Each
+    /// input on its own still has to be within the range of the attenuation,
+    /// negative voltages can't be measured.
+    pub fn read_differential<P, N>(
+        &mut self,
+        positive: &mut AdcPin<P, ADCI>,
+        negative: &mut AdcPin<N, ADCI>,
+    ) -> Result<i16, ()>
+    where
+        P: Channel<ADCI, ID = u8>,
+        N: Channel<ADCI, ID = u8>,
+    {
+        let positive: u16 = nb::block!(OneShot::<ADCI, u16, _>::read(self, positive))?;
+        let negative: u16 = nb::block!(OneShot::<ADCI, u16, _>::read(self, negative))?;
+
+        Ok(positive as i16 - negative as i16)
+    }
+}
+
+impl<'d> ADC<'d, ADC2> {
+    /// Output the internal reference voltage on `pin` for measuring it
+    ///
+    /// Only GPIO25, GPIO26 and GPIO27 (channels 7 to 9 of ADC2) are
+    /// supported. The measured value can be used with
+    /// [`AdcCalibration::from_vref`]. Reading from ADC2 is not possible while
+    /// the output is enabled.
+    pub fn enable_vref_output<PIN>(&mut self, _pin: &mut AdcPin<PIN, ADC2>)
+    where
+        PIN: Channel<ADC2, ID = u8>,
+    {
+        let channel = PIN::channel();
+        assert!(
+            (7..=9).contains(&channel),
+            "Vref can only be routed to GPIO25, GPIO26 or GPIO27"
+        );
+
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+        let sensors = unsafe { &*SENS::ptr() };
+
+        rtc_cntl
+            .bias_conf
+            .modify(|_, w| unsafe { w.dbg_atten().bits(0) });
+        // route Vref through the test mux to the ADC2 pads
+        rtc_cntl
+            .test_mux
+            .modify(|_, w| unsafe { w.dtest_rtc().bits(1).ent_rtc().set_bit() });
+        sensors
+            .sar_start_force
+            .modify(|_, w| w.sar2_en_test().set_bit());
+
+        ADC2::set_en_pad_force();
+        ADC2::set_en_pad(channel);
+    }
+
+    /// Stop outputting the internal reference voltage
+    pub fn disable_vref_output(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+        let sensors = unsafe { &*SENS::ptr() };
+
+        rtc_cntl
+            .test_mux
+            .modify(|_, w| unsafe { w.dtest_rtc().bits(0).ent_rtc().clear_bit() });
+        sensors
+            .sar_start_force
+            .modify(|_, w| w.sar2_en_test().clear_bit());
+    }
 }
 
 impl<'d, ADC1> ADC<'d, ADC1> {
@@ -394,10 +588,25 @@ where
         // Get converted value
         let converted_value = ADCI::read_data_sar();
 
+        self.sum += converted_value as u32;
+        self.samples += 1;
+        if self.samples < self.oversampling {
+            // Keep the channel active and start the next conversion right away
+            ADCI::clear_start_sar();
+            ADCI::set_start_sar();
+
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let samples = self.samples as u32;
+        let average = (self.sum + samples / 2) / samples;
+        self.sum = 0;
+        self.samples = 0;
+
         // Mark that no conversions are currently in progress
         self.active_channel = None;
 
-        Ok(converted_value.into())
+        Ok((average as u16).into())
     }
 }
 
@@ -440,6 +649,23 @@ pub mod implementation {
     //! | 7       | GPIO35 (VDET_2)      | GPIO27        |
     //! | 8       |                      | GPIO25        |
     //! | 9       |                      | GPIO26        |
+    //!
+    //! Readings can be converted to millivolts with `ADC::read_millivolts`,
+    //! calibrated with the reference voltage stored in eFuse, or with a
+    //! reference voltage measured at one of the pins GPIO25 to GPIO27, see
+    //! `AdcCalibration`.
+    //!
+    //! # Measuring the supply voltage
+    //!
+    //! The SAR ADCs have no internal channel connected to VDD3P3, and their
+    //! reference is the internal Vref, not the supply, so the supply voltage
+    //! can't be inferred from a reading of the reference either. To monitor
+    //! e.g. a battery, divide its voltage down to the range of the attenuation
+    //! with two resistors, read it on an ADC1 pin with `ADC::read_millivolts`
+    //! and scale it back up. With 11 dB of attenuation and a divider of two
+    //! equal resistors, supplies up to about 5 V can be measured. The result
+    //! has the accuracy of the calibration, a few percent, plus the tolerance
+    //! of the resistors.
 
     use embedded_hal::adc::Channel;
 
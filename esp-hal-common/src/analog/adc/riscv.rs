@@ -175,6 +175,15 @@ pub struct ADC<'d, ADCI> {
     _adc: PeripheralRef<'d, ADCI>,
     attenuations: [Option<Attenuation>; 5],
     active_channel: Option<u8>,
+    oversampling: u16,
+    sum: u32,
+    samples: u16,
+    // the next conversion of an oversampled reading is started by the next
+    // call to `read`, see the hardware limitation described there
+    start_pending: bool,
+    // `onetime_start` was reset by the last reading, the next conversion is
+    // delayed by one call to `read` for the same reason
+    settling: bool,
 }
 
 impl<'d, ADCI> ADC<'d, ADCI>
@@ -203,10 +212,62 @@ where
             _adc: adc_instance.into_ref(),
             attenuations: config.attenuations,
             active_channel: None,
+            oversampling: 1,
+            sum: 0,
+            samples: 0,
+            start_pending: false,
+            settling: false,
         };
 
         Ok(adc)
     }
+
+    /// Average `samples` conversions for every value returned by `read`
+    ///
+    /// Averaging reduces uncorrelated noise by a factor of `sqrt(samples)`,
+    /// i.e. every quadrupling of the number of samples gains about one bit of
+    /// effective resolution, up to the limits set by the non-linearity of the
+    /// ADC. The time a reading takes grows linearly with `samples`. The
+    /// conversions are done back to back, so this helps against noise, not
+    /// against slowly drifting inputs.
+    ///
+    /// The default of 1 disables averaging.
+    pub fn set_oversampling(&mut self, samples: u16) {
+        assert!(samples > 0, "At least one sample is needed");
+
+        self.oversampling = samples;
+        self.sum = 0;
+        self.samples = 0;
+    }
+
+    /// Read the difference between two channels, `positive - negative`
+    ///
+    /// The SAR ADCs of the ESP32-C2, ESP32-C3 and ESP32-C6 have no
+    /// differential input mode, so any two channels of this ADC can be used as
+    /// a pair: `positive` is converted first and `negative` right after it.
+    /// The two conversions are a few microseconds apart, this suits slowly
+    /// changing signals like the voltage across a current-sense shunt, not
+    /// fast ones. Oversampling applies to both conversions.
+    ///
+    /// Both channels should be configured with the same attenuation, else the
+    /// raw values don't share a scale. The result has the same 12 bit
+    /// resolution as a single reading and ranges from -4095 to 4095, but each
+    /// input on its own still has to be within the range of the attenuation,
+    /// e.g. 0 to about 750 mV with 0 dB. Negative voltages can't be measured.
+    pub fn read_differential<P, N>(
+        &mut self,
+        positive: &mut AdcPin<P, ADCI>,
+        negative: &mut AdcPin<N, ADCI>,
+    ) -> Result<i16, ()>
+    where
+        P: Channel<ADCI, ID = u8>,
+        N: Channel<ADCI, ID = u8>,
+    {
+        let positive: u16 = nb::block!(OneShot::<ADCI, u16, _>::read(self, positive))?;
+        let negative: u16 = nb::block!(OneShot::<ADCI, u16, _>::read(self, negative))?;
+
+        Ok(positive as i16 - negative as i16)
+    }
 }
 
 impl<'d, ADCI, WORD, PIN> OneShot<ADCI, WORD, AdcPin<PIN, ADCI>> for ADC<'d, ADCI>
@@ -232,10 +293,28 @@ where
             if active_channel != AdcPin::<PIN, ADCI>::channel() {
                 return Err(nb::Error::WouldBlock);
             }
+
+            if self.start_pending {
+                self.start_pending = false;
+
+                let attenuation = self.attenuations[active_channel as usize].unwrap() as u8;
+                ADCI::start_onetime_sample(active_channel, attenuation);
+
+                return Err(nb::Error::WouldBlock);
+            }
         } else {
             // If no conversions are in progress, start a new one for given channel
             self.active_channel = Some(AdcPin::<PIN, ADCI>::channel());
 
+            if self.settling {
+                // The previous reading just reset the ADC, start the conversion with the
+                // next call
+                self.settling = false;
+                self.start_pending = true;
+
+                return Err(nb::Error::WouldBlock);
+            }
+
             let channel = self.active_channel.unwrap();
             let attenuation = self.attenuations[channel as usize].unwrap() as u8;
             ADCI::start_onetime_sample(channel, attenuation);
@@ -258,13 +337,29 @@ where
         // clock cycle.
         //
         // This limitation will be removed in hardware future versions.
-        // We reset ``onetime_start`` in `reset` and assume enough time has passed until
-        // the next sample is requested.
+        // We reset ``onetime_start`` in `reset` and start the next conversion one call
+        // to `read` later, so the reset is visible to the ADC before the next start.
+
+        self.sum += converted_value as u32;
+        self.samples += 1;
+        if self.samples < self.oversampling {
+            // Keep the channel active. Starting the next conversion right after
+            // the reset could be missed, it's started by the next call instead.
+            self.start_pending = true;
+
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let samples = self.samples as u32;
+        let average = (self.sum + samples / 2) / samples;
+        self.sum = 0;
+        self.samples = 0;
 
         // Mark that no conversions are currently in progress
         self.active_channel = None;
+        self.settling = true;
 
-        Ok(converted_value.into())
+        Ok((average as u16).into())
     }
 }
 
@@ -252,6 +252,9 @@ pub struct ADC<'d, ADC> {
     _adc: PeripheralRef<'d, ADC>,
     attenuations: [Option<Attenuation>; 10],
     active_channel: Option<u8>,
+    oversampling: u16,
+    sum: u32,
+    samples: u16,
 }
 
 impl<'d, ADCI> ADC<'d, ADCI>
@@ -336,10 +339,60 @@ where
             _adc: adc_instance.into_ref(),
             attenuations: config.attenuations,
             active_channel: None,
+            oversampling: 1,
+            sum: 0,
+            samples: 0,
         };
 
         Ok(adc)
     }
+
+    /// Average `samples` conversions for every value returned by `read`
+    ///
+    /// Averaging reduces uncorrelated noise by a factor of `sqrt(samples)`,
+    /// i.e. every quadrupling of the number of samples gains about one bit of
+    /// effective resolution, up to the limits set by the non-linearity of the
+    /// ADC. The time a reading takes grows linearly with `samples`. The
+    /// conversions are done back to back, so this helps against noise, not
+    /// against slowly drifting inputs.
+    ///
+    /// The default of 1 disables averaging.
+    pub fn set_oversampling(&mut self, samples: u16) {
+        assert!(samples > 0, "At least one sample is needed");
+
+        self.oversampling = samples;
+        self.sum = 0;
+        self.samples = 0;
+    }
+
+    /// Read the difference between two channels, `positive - negative`
+    ///
+    /// The SAR ADCs of the ESP32-S2 and ESP32-S3 have no differential input
+    /// mode, so any two channels of this ADC can be used as a pair:
+    /// `positive` is converted first and `negative` right after it. The two
+    /// conversions are a few microseconds apart, this suits slowly changing
+    /// signals like the voltage across a current-sense shunt, not fast
+    /// ones. Oversampling applies to both conversions.
+    ///
+    /// Both channels should be configured with the same attenuation, else the
+    /// raw values don't share a scale. The result has the same resolution as a
+    /// single reading, with 13 bit readings it ranges from -8191 to 8191. Each
+    /// input on its own still has to be within the range of the attenuation,
+    /// negative voltages can't be measured.
+    pub fn read_differential<P, N>(
+        &mut self,
+        positive: &mut AdcPin<P, ADCI>,
+        negative: &mut AdcPin<N, ADCI>,
+    ) -> Result<i16, ()>
+    where
+        P: Channel<ADCI, ID = u8>,
+        N: Channel<ADCI, ID = u8>,
+    {
+        let positive: u16 = nb::block!(OneShot::<ADCI, u16, _>::read(self, positive))?;
+        let negative: u16 = nb::block!(OneShot::<ADCI, u16, _>::read(self, negative))?;
+
+        Ok(positive as i16 - negative as i16)
+    }
 }
 
 impl<'d, ADCI, WORD, PIN> OneShot<ADCI, WORD, AdcPin<PIN, ADCI>> for ADC<'d, ADCI>
@@ -384,10 +437,25 @@ where
         // Get converted value
         let converted_value = ADCI::read_data_sar();
 
+        self.sum += converted_value as u32;
+        self.samples += 1;
+        if self.samples < self.oversampling {
+            // Keep the channel active and start the next conversion right away
+            ADCI::clear_start_sar();
+            ADCI::set_start_sar();
+
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let samples = self.samples as u32;
+        let average = (self.sum + samples / 2) / samples;
+        self.sum = 0;
+        self.samples = 0;
+
         // Mark that no conversions are currently in progress
         self.active_channel = None;
 
-        Ok(converted_value.into())
+        Ok((average as u16).into())
     }
 }
 
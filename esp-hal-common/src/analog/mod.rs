@@ -1,3 +1,8 @@
+/// Analog to digital (ADC) conversion
+///
+/// Only the ESP32 converts readings to millivolts, see
+/// `ADC::read_millivolts`. On the other chips readings are raw values,
+/// conversion using the calibration data in eFuse is not supported yet.
 #[cfg_attr(esp32, path = "adc/esp32.rs")]
 #[cfg_attr(any(esp32c2, esp32c3, esp32c6), path = "adc/riscv.rs")]
 #[cfg_attr(any(esp32s2, esp32s3), path = "adc/xtensa.rs")]
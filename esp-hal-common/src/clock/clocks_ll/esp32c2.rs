@@ -41,6 +41,7 @@ const I2C_BBPLL_OC_DLREF_SEL_LSB: u32 = 6;
 const I2C_MST_ANA_CONF0_REG: u32 = 0x6004_E840;
 const I2C_MST_BBPLL_STOP_FORCE_HIGH: u32 = 1 << 2;
 const I2C_MST_BBPLL_STOP_FORCE_LOW: u32 = 1 << 3;
+const I2C_MST_BBPLL_CAL_DONE: u32 = 1 << 24;
 
 pub(crate) fn esp32c2_rtc_bbpll_configure(xtal_freq: XtalClock, _pll_freq: PllClock) {
     let system = unsafe { &*crate::peripherals::SYSTEM::ptr() };
@@ -115,6 +116,11 @@ pub(crate) fn esp32c2_rtc_bbpll_configure(xtal_freq: XtalClock, _pll_freq: PllCl
     }
 }
 
+// The calibration done flag is set once the BBPLL locked to the new frequency
+pub(crate) fn esp32c2_rtc_bbpll_locked() -> bool {
+    unsafe { (I2C_MST_ANA_CONF0_REG as *const u32).read_volatile() & I2C_MST_BBPLL_CAL_DONE != 0 }
+}
+
 pub(crate) fn esp32c2_rtc_bbpll_enable() {
     let rtc_cntl = unsafe { &*crate::peripherals::RTC_CNTL::ptr() };
 
@@ -131,6 +131,11 @@ pub(crate) fn esp32c6_rtc_bbpll_configure(_xtal_freq: XtalClock, _pll_freq: PllC
     }
 }
 
+// The calibration done flag is set once the BBPLL locked to the new frequency
+pub(crate) fn esp32c6_rtc_bbpll_locked() -> bool {
+    unsafe { (I2C_MST_ANA_CONF0_REG as *const u32).read_volatile() & I2C_MST_BBPLL_CAL_DONE != 0 }
+}
+
 pub(crate) fn esp32c6_rtc_bbpll_enable() {
     let pmu = unsafe { &*crate::peripherals::PMU::PTR };
 
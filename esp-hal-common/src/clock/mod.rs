@@ -1,8 +1,41 @@
 //! # Clock Control
+//!
+//! The frequency of the main XTAL is detected when the clocks are configured:
+//! the value stored by the bootloader is used if there is one, otherwise the
+//! XTAL is measured against the internal 8 MHz oscillator. The detected
+//! frequency ends up in [`Clocks::xtal_clock`], which all timing calculations
+//! are based on.
+//!
+//! Supported crystals are 26 MHz and 40 MHz on the ESP32 and ESP32-C2, and 40
+//! MHz on all other chips.
+//!
+//! # Changing the frequency at runtime
+//!
+//! There is no dynamic frequency scaling: the CPU and APB frequencies are set
+//! once by [`ClockControl`] and the resulting [`Clocks`] are frozen. Drivers
+//! derive their dividers (baud rates, bus clocks, timer ticks) from [`Clocks`]
+//! when they are constructed or reconfigured and don't notice later changes.
+//!
+//! Reconfiguring the clocks by passing a reborrowed
+//! [`SystemClockControl`] to [`ClockControl::configure`] again is possible, but
+//! changing the APB frequency that way corrupts whatever is in flight (e.g. a
+//! UART transmission or SPI transfer) and leaves all drivers running at wrong
+//! rates. All peripherals have to be idle before the change and reconfigured
+//! with the new [`Clocks`] afterwards, the HAL does not check this.
+//!
+//! [`Clocks::change_frequency_safe`] does both for the drivers passed to it,
+//! which implement [`ClockDependent`]: it refuses to change the clocks while
+//! one of them is busy and re-derives their dividers afterwards. Drivers that
+//! aren't passed still have to be taken care of by the application.
+//!
+//! ```rust,ignore
+//! clocks.change_frequency_safe(CpuClock::Clock80MHz, &mut [&mut uart0, &mut spi])?;
+//! ```
 use fugit::HertzU32;
 
 use crate::{
     peripheral::{Peripheral, PeripheralRef},
+    rtc_cntl::RtcClock,
     system::SystemClockControl,
 };
 
@@ -126,6 +159,151 @@ pub struct Clocks<'d> {
     // TODO chip specific additional ones as needed
 }
 
+impl<'d> Clocks<'d> {
+    /// Number of CPU cycles in `us` microseconds
+    ///
+    /// Rounded up, so a busy loop running for the returned number of cycles
+    /// takes at least `us`. Saturates at `u32::MAX`, which is reached after
+    /// about 17.9 s at 240 MHz.
+    pub fn cpu_cycles_for_us(&self, us: u32) -> u32 {
+        Self::cycles_for(us, self.cpu_clock, 1_000_000)
+    }
+
+    /// Number of CPU cycles in `ns` nanoseconds
+    ///
+    /// Rounded up like [`Clocks::cpu_cycles_for_us`].
+    pub fn cpu_cycles_for_ns(&self, ns: u32) -> u32 {
+        Self::cycles_for(ns, self.cpu_clock, 1_000_000_000)
+    }
+
+    /// Duration of `cycles` CPU cycles in microseconds
+    ///
+    /// Rounded down, the result can't overflow.
+    pub fn us_for_cpu_cycles(&self, cycles: u32) -> u32 {
+        (cycles as u64 * 1_000_000 / self.cpu_clock.raw() as u64) as u32
+    }
+
+    fn cycles_for(time: u32, clock: HertzU32, units_per_second: u64) -> u32 {
+        let cycles = (time as u64 * clock.raw() as u64 + units_per_second - 1) / units_per_second;
+
+        u32::try_from(cycles).unwrap_or(u32::MAX)
+    }
+}
+
+/// A driver whose timing is derived from [`Clocks`], see
+/// [`Clocks::change_frequency_safe`]
+pub trait ClockDependent {
+    /// Whether an operation is in flight that a clock change would corrupt
+    fn is_busy(&self) -> bool;
+
+    /// Derive the dividers again after the clocks changed
+    fn clocks_changed(&mut self, clocks: &Clocks);
+}
+
+/// A driver passed to [`Clocks::change_frequency_safe`] was busy, the clocks
+/// weren't changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeripheralBusy;
+
+impl<'d> Clocks<'d> {
+    /// Switch to another CPU clock speed, like [`ClockControl::configure`]
+    ///
+    /// Fails with [`PeripheralBusy`] if one of `drivers` is in the middle of
+    /// an operation. Otherwise the clocks are configured and `drivers` are
+    /// reconfigured for the new frequencies, which are also updated in
+    /// `self`. The check and the change happen in a critical section, so an
+    /// interrupt handler can't start a transfer in between. Drivers that
+    /// aren't passed here keep running with dividers for the old frequencies
+    /// if the APB clock changes.
+    pub fn change_frequency_safe(
+        &mut self,
+        cpu_clock_speed: CpuClock,
+        drivers: &mut [&mut dyn ClockDependent],
+    ) -> Result<(), PeripheralBusy> {
+        critical_section::with(|_| {
+            if drivers.iter().any(|driver| driver.is_busy()) {
+                return Err(PeripheralBusy);
+            }
+
+            // `self` holds the only reference to the clock control, which is
+            // only needed for the duration of the call
+            let clock_control = unsafe { (*self._private).clone_unchecked() };
+            let clocks = ClockControl::configure(clock_control, cpu_clock_speed).freeze();
+
+            self.cpu_clock = clocks.cpu_clock;
+            self.apb_clock = clocks.apb_clock;
+            self.xtal_clock = clocks.xtal_clock;
+            self.i2c_clock = clocks.i2c_clock;
+            #[cfg(esp32)]
+            {
+                self.pwm_clock = clocks.pwm_clock;
+            }
+            #[cfg(esp32s3)]
+            {
+                self.crypto_pwm_clock = clocks.crypto_pwm_clock;
+            }
+            #[cfg(esp32c6)]
+            {
+                self.crypto_clock = clocks.crypto_clock;
+            }
+
+            for driver in drivers.iter_mut() {
+                driver.clocks_changed(self);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// The PLL didn't lock within the timeout of [`Clocks::wait_pll_lock`]
+#[cfg(any(esp32c2, esp32c3, esp32c6))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PllLockTimeout;
+
+#[cfg(any(esp32c2, esp32c3, esp32c6))]
+impl<'d> Clocks<'d> {
+    /// Whether the PLL is locked
+    ///
+    /// Reads the calibration done flag of the BBPLL, which the hardware sets
+    /// once the PLL locked to the configured frequency. Until then the PLL
+    /// derived clocks (the CPU clock above the XTAL frequency, the APB clock
+    /// and most peripheral clocks) may run at wrong frequencies.
+    ///
+    /// [`ClockControl::configure`] already waits for the lock on the ESP32-C6,
+    /// on the ESP32-C2 and ESP32-C3 it returns right after starting the
+    /// calibration. The lock usually takes a few tens of microseconds. The
+    /// ESP32, ESP32-S2 and ESP32-S3 have no such flag, ESP-IDF only waits a
+    /// fixed time there.
+    pub fn pll_locked(&self) -> bool {
+        #[cfg(esp32c2)]
+        return clocks_ll::esp32c2_rtc_bbpll_locked();
+        #[cfg(esp32c3)]
+        return clocks_ll::esp32c3_rtc_bbpll_locked();
+        #[cfg(esp32c6)]
+        return clocks_ll::esp32c6_rtc_bbpll_locked();
+    }
+
+    /// Wait up to `timeout_us` microseconds for the PLL to lock, see
+    /// [`Clocks::pll_locked`]
+    ///
+    /// The timeout is measured with the SYSTIMER, which runs from the XTAL and
+    /// is accurate while the PLL isn't locked yet. Use this before setting up
+    /// high speed peripherals right after the clocks were configured.
+    pub fn wait_pll_lock(&self, timeout_us: u32) -> Result<(), PllLockTimeout> {
+        let delay = crate::Delay::new(self);
+
+        for _ in 0..=timeout_us {
+            if self.pll_locked() {
+                return Ok(());
+            }
+            delay.delay(1);
+        }
+
+        Err(PllLockTimeout)
+    }
+}
+
 #[doc(hidden)]
 impl<'d> Clocks<'d> {
     /// This should not be used in user code.
@@ -192,29 +370,18 @@ impl<'d> ClockControl<'d> {
     pub fn boot_defaults(
         clock_control: impl Peripheral<P = SystemClockControl> + 'd,
     ) -> ClockControl<'d> {
-        #[cfg(feature = "esp32_40mhz")]
-        return ClockControl {
-            _private: clock_control.into_ref(),
-            desired_rates: RawClocks {
-                cpu_clock: HertzU32::MHz(80),
-                apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
-                i2c_clock: HertzU32::MHz(80),
-                pwm_clock: HertzU32::MHz(160),
-            },
-        };
+        let xtal_freq = RtcClock::detect_xtal_freq();
 
-        #[cfg(feature = "esp32_26mhz")]
-        return ClockControl {
+        ClockControl {
             _private: clock_control.into_ref(),
             desired_rates: RawClocks {
                 cpu_clock: HertzU32::MHz(80),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(26),
+                xtal_clock: xtal_freq.frequency(),
                 i2c_clock: HertzU32::MHz(80),
                 pwm_clock: HertzU32::MHz(160),
             },
-        };
+        }
     }
 
     /// Configure the CPU clock speed.
@@ -223,12 +390,7 @@ impl<'d> ClockControl<'d> {
         clock_control: impl Peripheral<P = SystemClockControl> + 'd,
         cpu_clock_speed: CpuClock,
     ) -> ClockControl<'d> {
-        // like NuttX use 40M hardcoded - if it turns out to be a problem
-        // we will take care then
-        #[cfg(feature = "esp32_40mhz")]
-        let xtal_freq = XtalClock::RtcXtalFreq40M;
-        #[cfg(feature = "esp32_26mhz")]
-        let xtal_freq = XtalClock::RtcXtalFreq26M;
+        let xtal_freq = RtcClock::detect_xtal_freq();
         let pll_freq = match cpu_clock_speed {
             CpuClock::Clock80MHz => PllClock::Pll320MHz,
             CpuClock::Clock160MHz => PllClock::Pll320MHz,
@@ -245,7 +407,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: cpu_clock_speed.frequency(),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: xtal_freq.frequency(),
                 i2c_clock: HertzU32::MHz(40),
                 // The docs are unclear here. pwm_clock seems to be tied to clocks.apb_clock
                 // while simultaneously being fixed at 160 MHz.
@@ -263,27 +425,17 @@ impl<'d> ClockControl<'d> {
     pub fn boot_defaults(
         clock_control: impl Peripheral<P = SystemClockControl> + 'd,
     ) -> ClockControl<'d> {
-        #[cfg(feature = "esp32c2_40mhz")]
-        return ClockControl {
-            _private: clock_control.into_ref(),
-            desired_rates: RawClocks {
-                cpu_clock: HertzU32::MHz(80),
-                apb_clock: HertzU32::MHz(40),
-                xtal_clock: HertzU32::MHz(40),
-                i2c_clock: HertzU32::MHz(40),
-            },
-        };
+        let xtal_freq = RtcClock::detect_xtal_freq();
 
-        #[cfg(feature = "esp32c2_26mhz")]
-        return ClockControl {
+        ClockControl {
             _private: clock_control.into_ref(),
             desired_rates: RawClocks {
                 cpu_clock: HertzU32::MHz(80),
                 apb_clock: HertzU32::MHz(40),
-                xtal_clock: HertzU32::MHz(26),
-                i2c_clock: HertzU32::MHz(26),
+                xtal_clock: xtal_freq.frequency(),
+                i2c_clock: xtal_freq.frequency(),
             },
-        };
+        }
     }
 
     /// Configure the CPU clock speed.
@@ -293,10 +445,7 @@ impl<'d> ClockControl<'d> {
         cpu_clock_speed: CpuClock,
     ) -> ClockControl<'d> {
         let apb_freq;
-        #[cfg(feature = "esp32c2_40mhz")]
-        let xtal_freq = XtalClock::RtcXtalFreq40M;
-        #[cfg(feature = "esp32c2_26mhz")]
-        let xtal_freq = XtalClock::RtcXtalFreq26M;
+        let xtal_freq = RtcClock::detect_xtal_freq();
         let pll_freq = PllClock::Pll480MHz;
 
         if cpu_clock_speed.mhz() <= xtal_freq.mhz() {
@@ -317,7 +466,7 @@ impl<'d> ClockControl<'d> {
                 cpu_clock: cpu_clock_speed.frequency(),
                 apb_clock: apb_freq.frequency(),
                 xtal_clock: xtal_freq.frequency(),
-                i2c_clock: HertzU32::MHz(40),
+                i2c_clock: xtal_freq.frequency(),
             },
         }
     }
@@ -335,7 +484,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: HertzU32::MHz(80),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: RtcClock::detect_xtal_freq().frequency(),
                 i2c_clock: HertzU32::MHz(40),
             },
         }
@@ -348,7 +497,7 @@ impl<'d> ClockControl<'d> {
         cpu_clock_speed: CpuClock,
     ) -> ClockControl<'d> {
         let apb_freq;
-        let xtal_freq = XtalClock::RtcXtalFreq40M;
+        let xtal_freq = RtcClock::detect_xtal_freq();
         let pll_freq = PllClock::Pll480MHz;
 
         if cpu_clock_speed.mhz() <= xtal_freq.mhz() {
@@ -387,7 +536,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: HertzU32::MHz(80),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: RtcClock::detect_xtal_freq().frequency(),
                 i2c_clock: HertzU32::MHz(40),
                 crypto_clock: HertzU32::MHz(160),
             },
@@ -401,7 +550,7 @@ impl<'d> ClockControl<'d> {
         cpu_clock_speed: CpuClock,
     ) -> ClockControl<'d> {
         let apb_freq;
-        let xtal_freq = XtalClock::RtcXtalFreq40M;
+        let xtal_freq = RtcClock::detect_xtal_freq();
         let pll_freq = PllClock::Pll480MHz;
 
         if cpu_clock_speed.mhz() <= xtal_freq.mhz() {
@@ -441,7 +590,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: HertzU32::MHz(80),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: RtcClock::detect_xtal_freq().frequency(),
                 i2c_clock: HertzU32::MHz(80),
             },
         }
@@ -460,7 +609,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: cpu_clock_speed.frequency(),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: RtcClock::detect_xtal_freq().frequency(),
                 i2c_clock: HertzU32::MHz(40),
             },
         }
@@ -479,7 +628,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: HertzU32::MHz(80),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: RtcClock::detect_xtal_freq().frequency(),
                 i2c_clock: HertzU32::MHz(40),
                 crypto_pwm_clock: HertzU32::MHz(160),
             },
@@ -499,7 +648,7 @@ impl<'d> ClockControl<'d> {
             desired_rates: RawClocks {
                 cpu_clock: cpu_clock_speed.frequency(),
                 apb_clock: HertzU32::MHz(80),
-                xtal_clock: HertzU32::MHz(40),
+                xtal_clock: RtcClock::detect_xtal_freq().frequency(),
                 i2c_clock: HertzU32::MHz(40),
                 crypto_pwm_clock: HertzU32::MHz(160),
             },
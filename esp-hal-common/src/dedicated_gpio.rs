@@ -0,0 +1,143 @@
+//! Dedicated GPIO
+//!
+//! Dedicated GPIO connects up to 8 pins to CPU registers. Every access is a
+//! single CSR instruction executing in one CPU cycle, while the normal GPIO
+//! path is a store to the GPIO peripheral over the APB bus, which takes
+//! several cycles and is subject to bus arbitration. This allows
+//! cycle-accurate bit-banging of protocols well beyond what the GPIO
+//! registers can keep up with.
+//!
+//! The pins are routed through the GPIO matrix, so any pin can be used on
+//! any of the 8 channels. Writes only reach channels configured as outputs
+//! with [`DedicatedGpio::connect_output`].
+//!
+//! Availability: the ESP32-C3 is supported. The ESP32-S2, ESP32-S3 and
+//! ESP32-C6 also have dedicated GPIO, which is not implemented yet, the ESP32
+//! and ESP32-C2 don't have it.
+//!
+//! ```no_run
+//! let mut dedicated = DedicatedGpio::new(&mut system.peripheral_clock_control);
+//! dedicated.connect_output(0, io.pins.gpio5);
+//!
+//! dedicated.set(0b1);
+//! dedicated.clear(0b1);
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{
+    gpio::{InputPin, InputSignal, OutputPin, OutputSignal},
+    peripheral::Peripheral,
+    system::{self, PeripheralClockControl},
+};
+
+const OUTPUT_SIGNALS: [OutputSignal; 8] = [
+    OutputSignal::CPU_GPIO_0,
+    OutputSignal::CPU_GPIO_1,
+    OutputSignal::CPU_GPIO_2,
+    OutputSignal::CPU_GPIO_3,
+    OutputSignal::CPU_GPIO_4,
+    OutputSignal::CPU_GPIO_5,
+    OutputSignal::CPU_GPIO_6,
+    OutputSignal::CPU_GPIO_7,
+];
+
+const INPUT_SIGNALS: [InputSignal; 8] = [
+    InputSignal::CPU_GPIO_0,
+    InputSignal::CPU_GPIO_1,
+    InputSignal::CPU_GPIO_2,
+    InputSignal::CPU_GPIO_3,
+    InputSignal::CPU_GPIO_4,
+    InputSignal::CPU_GPIO_5,
+    InputSignal::CPU_GPIO_6,
+    InputSignal::CPU_GPIO_7,
+];
+
+/// Bundle of up to 8 pins driven directly by the CPU
+///
+/// Bit `n` of the values read and written corresponds to channel `n`.
+pub struct DedicatedGpio<'d> {
+    outputs: u8,
+    _pins: PhantomData<&'d ()>,
+}
+
+impl<'d> DedicatedGpio<'d> {
+    /// Enable dedicated GPIO, with no pins connected
+    pub fn new(peripheral_clock_control: &mut PeripheralClockControl) -> Self {
+        peripheral_clock_control.enable(system::Peripheral::DedicatedGpio);
+
+        let mut dedicated = Self {
+            outputs: 0,
+            _pins: PhantomData,
+        };
+        dedicated.write(0);
+        set_output_enable(0);
+
+        dedicated
+    }
+
+    /// Drive `pin` from `channel` (0 to 7)
+    pub fn connect_output<P: OutputPin>(
+        &mut self,
+        channel: u8,
+        pin: impl Peripheral<P = P> + 'd,
+    ) -> &mut Self {
+        assert!(channel < 8, "Dedicated GPIO only has 8 channels");
+        crate::into_ref!(pin);
+
+        pin.set_to_push_pull_output()
+            .connect_peripheral_to_output(OUTPUT_SIGNALS[channel as usize]);
+
+        self.outputs |= 1 << channel;
+        set_output_enable(self.outputs);
+
+        self
+    }
+
+    /// Read `pin` through `channel` (0 to 7)
+    pub fn connect_input<P: InputPin>(
+        &mut self,
+        channel: u8,
+        pin: impl Peripheral<P = P> + 'd,
+    ) -> &mut Self {
+        assert!(channel < 8, "Dedicated GPIO only has 8 channels");
+        crate::into_ref!(pin);
+
+        pin.set_to_input()
+            .connect_input_to_peripheral(INPUT_SIGNALS[channel as usize]);
+
+        self
+    }
+
+    /// Set the level of all output channels
+    #[inline(always)]
+    pub fn write(&mut self, value: u8) {
+        unsafe { core::arch::asm!("csrw 0x805, {0}", in(reg) value as u32) };
+    }
+
+    /// Drive the channels in `mask` high, leaving the others unchanged
+    #[inline(always)]
+    pub fn set(&mut self, mask: u8) {
+        unsafe { core::arch::asm!("csrs 0x805, {0}", in(reg) mask as u32) };
+    }
+
+    /// Drive the channels in `mask` low, leaving the others unchanged
+    #[inline(always)]
+    pub fn clear(&mut self, mask: u8) {
+        unsafe { core::arch::asm!("csrc 0x805, {0}", in(reg) mask as u32) };
+    }
+
+    /// Read the level of all input channels
+    #[inline(always)]
+    pub fn read(&self) -> u8 {
+        let value: u32;
+        unsafe { core::arch::asm!("csrr {0}, 0x804", out(reg) value) };
+
+        value as u8
+    }
+}
+
+#[inline(always)]
+fn set_output_enable(mask: u8) {
+    unsafe { core::arch::asm!("csrw 0x803, {0}", in(reg) mask as u32) };
+}
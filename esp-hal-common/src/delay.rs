@@ -2,6 +2,40 @@
 //!
 //! Implement the `DelayMs` and `DelayUs` traits from [embedded-hal].
 //!
+//! [Delay] has the same name and constructor, `Delay::new(&clocks)`, on all
+//! chips, code using it is portable between RISC-V and Xtensa. The options
+//! that only exist on RISC-V are methods, not part of the type. It also isn't
+//! generic, so it can be passed to drivers which take the delay as a trait
+//! object to avoid monomorphization:
+//!
+//! ```rust,ignore
+//! use embedded_hal::blocking::delay::DelayUs;
+//!
+//! fn reset_sensor(delay: &mut dyn DelayUs<u32>) {
+//!     // ...
+//!     delay.delay_us(500);
+//! }
+//!
+//! let mut delay = Delay::new(&clocks);
+//! reset_sensor(&mut delay);
+//! ```
+//!
+//! Shorter delays, e.g. for bit-banged protocols, are possible with
+//! `Delay::delay_ns`. There is no embedded-hal trait for them, so drivers have
+//! to take a [Delay] to use it. Its resolution is limited by the counter the
+//! delay is based on.
+//!
+//! On the RISC-V chips the delay can sleep until a `SYSTIMER` alarm fires
+//! instead of busy-waiting, see `Delay::set_low_power`. A busy-waiting
+//! [Delay] only reads the counter and doesn't need an alarm at all, and
+//! `Delay::delay_with_alarm` sleeps on an alarm that is only borrowed for the
+//! call.
+//!
+//! Either way [Delay] blocks the core, inside an embassy executor no other
+//! task runs in the meantime. Tasks should use `embassy::Delay` instead, which
+//! implements the `DelayUs` trait of `embedded-hal-async` on top of the alarm
+//! of the embassy time driver.
+//!
 //! [embedded-hal]: https://docs.rs/embedded-hal/latest/embedded_hal/
 
 pub use self::delay::Delay;
@@ -41,35 +75,146 @@ impl embedded_hal_1::delay::DelayUs for Delay {
 mod delay {
     use fugit::HertzU64;
 
-    use crate::{clock::Clocks, systimer::SystemTimer};
+    use crate::{
+        clock::Clocks,
+        systimer::{Alarm, SystemTimer, Target},
+    };
 
     /// Uses the `SYSTIMER` peripheral for counting clock cycles, as
     /// unfortunately the ESP32-C3 does NOT implement the `mcycle` CSR, which is
     /// how we would normally do this.
+    ///
+    /// By default the delay busy-waits on the counter, which returns within a
+    /// few ticks of the requested time but keeps the CPU running. With
+    /// [`Delay::set_low_power`] the delay arms a `SYSTIMER` alarm instead and
+    /// sleeps with `wfi` until it fires, which reduces the current drawn during
+    /// long delays. Waking up takes a few microseconds, so delays shorter than
+    /// about 10 µs gain nothing from it and end up slightly longer than
+    /// requested.
     pub struct Delay {
         freq: HertzU64,
+        // channel of the alarm handed over with `set_low_power`
+        alarm: Option<u8>,
     }
 
     impl Delay {
         /// Create a new Delay instance
         pub fn new(clocks: &Clocks) -> Self {
             // The counters and comparators are driven using `XTAL_CLK`. The average clock
-            // frequency is fXTAL_CLK/2.5, which is 16 MHz. The timer counting is
-            // incremented by 1/16 μs on each `CNT_CLK` cycle.
+            // frequency is fXTAL_CLK/2.5, which is 16 MHz with a 40 MHz XTAL. The timer
+            // counting is incremented by 1/16 μs on each `CNT_CLK` cycle.
+            // The calculation is done in Hz, as a 26 MHz XTAL results in 10.4 MHz.
 
             Self {
-                freq: HertzU64::MHz((clocks.xtal_clock.to_MHz() * 10 / 25) as u64),
+                freq: HertzU64::Hz(clocks.xtal_clock.to_Hz() as u64 * 10 / 25),
+                alarm: None,
             }
         }
 
+        /// Sleep during delays instead of busy-waiting
+        ///
+        /// `alarm` is used to wake the CPU at the end of every delay. Its
+        /// interrupt (`SYSTIMER_TARGET0` to `SYSTIMER_TARGET2`) has to be
+        /// enabled with [`crate::interrupt::enable`], otherwise `wfi` never
+        /// returns. No handler runs for it, the delay clears the interrupt
+        /// before interrupts are unmasked again. Other interrupts are still
+        /// handled while sleeping.
+        pub fn set_low_power<const CHANNEL: u8>(&mut self, alarm: Alarm<Target, CHANNEL>) {
+            alarm.interrupt_enable(false);
+            alarm.clear_interrupt();
+
+            self.alarm = Some(CHANNEL);
+        }
+
+        /// Busy-wait during delays again
+        ///
+        /// The alarm passed to [`Self::set_low_power`] stays unused.
+        pub fn set_busy_wait(&mut self) {
+            self.alarm = None;
+        }
+
         /// Delay for the specified number of microseconds
+        ///
+        /// The longest possible delay, `u32::MAX` µs or about 71 minutes, is
+        /// less than 2^37 ticks. Neither the conversion to ticks nor the
+        /// elapsed time, taken modulo [`SystemTimer::BIT_MASK`], can overflow.
         pub fn delay(&self, us: u32) {
             let t0 = SystemTimer::now();
             let clocks = (us as u64 * self.freq.raw()) / HertzU64::MHz(1).raw();
 
+            match self.alarm {
+                None => {
+                    while SystemTimer::now().wrapping_sub(t0) & SystemTimer::BIT_MASK <= clocks {}
+                }
+                // safety: the alarm was handed over in `set_low_power`
+                Some(0) => sleep_until(&unsafe { Alarm::<Target, 0>::conjure() }, t0 + clocks),
+                Some(1) => sleep_until(&unsafe { Alarm::<Target, 1>::conjure() }, t0 + clocks),
+                Some(2) => sleep_until(&unsafe { Alarm::<Target, 2>::conjure() }, t0 + clocks),
+                Some(_) => unreachable!(),
+            }
+        }
+
+        /// Delay for the specified number of microseconds, sleeping on `alarm`
+        ///
+        /// Like [`Self::delay`] in low-power mode, but the alarm is only
+        /// borrowed for this call and can be used for something else in
+        /// between. The same requirements apply: its interrupt has to be
+        /// enabled, and it must not be armed by anyone else while the delay
+        /// runs.
+        pub fn delay_with_alarm<const CHANNEL: u8>(&self, alarm: &Alarm<Target, CHANNEL>, us: u32) {
+            let t0 = SystemTimer::now();
+            let clocks = (us as u64 * self.freq.raw()) / HertzU64::MHz(1).raw();
+
+            alarm.interrupt_enable(false);
+            alarm.clear_interrupt();
+
+            sleep_until(alarm, t0 + clocks);
+        }
+
+        /// Delay for the specified number of nanoseconds
+        ///
+        /// Always busy-waits, also in low-power mode. The `SYSTIMER` counts at
+        /// 16 MHz (with a 40 MHz XTAL), so the delay is rounded down to a
+        /// multiple of 62.5 ns, at least one tick, and the start is only known
+        /// to within a tick. Expect up to two ticks more than requested, which
+        /// dominates for delays below about 1 µs: use it where a minimum
+        /// delay is required, not for exact timing.
+        pub fn delay_ns(&self, ns: u32) {
+            let t0 = SystemTimer::now();
+            let clocks = ((ns as u64 * self.freq.raw()) / HertzU64::Hz(1_000_000_000).raw()).max(1);
+
             while SystemTimer::now().wrapping_sub(t0) & SystemTimer::BIT_MASK <= clocks {}
         }
     }
+
+    fn sleep_until<const CHANNEL: u8>(alarm: &Alarm<Target, CHANNEL>, deadline: u64) {
+        let deadline = deadline & SystemTimer::BIT_MASK;
+
+        alarm.set_target(deadline);
+
+        loop {
+            // `wfi` returns as soon as an interrupt is pending, even while they are
+            // masked. The alarm interrupt is only enabled inside the critical section
+            // and cleared before leaving it, so its handler never runs, other
+            // interrupts are handled in between.
+            let done = critical_section::with(|_| {
+                alarm.interrupt_enable(true);
+                if !SystemTimer::has_elapsed(deadline) {
+                    unsafe { core::arch::asm!("wfi") };
+                }
+                alarm.interrupt_enable(false);
+                alarm.clear_interrupt();
+
+                SystemTimer::has_elapsed(deadline)
+            });
+
+            if done {
+                break;
+            }
+        }
+
+        alarm.clear_interrupt();
+    }
 }
 
 #[cfg(xtensa)]
@@ -98,5 +243,16 @@ mod delay {
             let clocks = (us as u64 * self.freq.raw()) / HertzU64::MHz(1).raw();
             xtensa_lx::timer::delay(clocks as u32);
         }
+
+        /// Delay for the specified number of nanoseconds
+        ///
+        /// The delay is counted in CPU clock cycles, rounded down but at least
+        /// one, so the resolution is a few nanoseconds. Reading the cycle
+        /// counter and the loop around it add some cycles on top: the delay is
+        /// a minimum, delays of a few cycles end up noticeably longer.
+        pub fn delay_ns(&self, ns: u32) {
+            let clocks = ((ns as u64 * self.freq.raw()) / HertzU64::Hz(1_000_000_000).raw()).max(1);
+            xtensa_lx::timer::delay(clocks as u32);
+        }
     }
 }
@@ -246,6 +246,12 @@ macro_rules! impl_channel {
                     });
                 }
 
+                fn set_mem2mem_mode(value: bool) {
+                    let dma = unsafe { &*crate::peripherals::DMA::PTR };
+
+                    dma.[<in_conf0_ch $num>].modify(|_, w| w.mem_trans_en().bit(value));
+                }
+
                 fn reset_in() {
                     let dma = unsafe { &*crate::peripherals::DMA::PTR };
 
@@ -445,6 +451,8 @@ macro_rules! impl_channel {
             impl I2sPeripheral for [<SuitablePeripheral $num>] {}
             impl I2s0Peripheral for [<SuitablePeripheral $num>] {}
             impl I2s1Peripheral for [<SuitablePeripheral $num>] {}
+            #[cfg(esp32c3)]
+            impl UhciPeripheral for [<SuitablePeripheral $num>] {}
         }
     };
 }
@@ -502,3 +510,131 @@ impl<'d> Gdma<'d> {
         }
     }
 }
+
+/// Memory-to-memory copies
+///
+/// A GDMA channel in memory-to-memory mode copies a buffer without involving
+/// the CPU, which is free to do other work in the meantime (see
+/// [`Mem2Mem::mem_copy_async`]). That is the only reason to use it: the DMA
+/// doesn't copy faster than the CPU, so a blocking [`Mem2Mem::mem_copy`] is
+/// never quicker than `copy_from_slice`. Setting up a transfer costs about as
+/// much as the CPU copying 1 KiB, below that size offloading the copy doesn't
+/// free up any time either. The `dma_mem2mem` example prints the DMA and CPU
+/// durations for sizes from 64 bytes to 16 KiB and the size from which the
+/// DMA keeps up, run it to get the threshold for a given chip and CPU clock.
+///
+/// Both buffers need to be in internal RAM. With burst mode enabled on the
+/// channel, they also need to be word aligned and the copy needs to be a
+/// multiple of 4 bytes long, otherwise [`DmaError::InvalidAlignment`] is
+/// returned.
+/// The descriptors of the channel limit the size of a copy, each of the TX and
+/// RX descriptor lists needs 3 words per 4092 bytes.
+///
+/// The channel is still connected to a peripheral, which is only used to
+/// pair its TX and RX side. Pass one that isn't used with DMA by anything else.
+///
+/// ```rust,ignore
+/// let mut mem2mem = Mem2Mem::new(
+///     dma_channel.configure(false, &mut tx_descriptors, &mut rx_descriptors, DmaPriority::Priority0),
+///     DmaPeripheral::Adc,
+/// );
+///
+/// mem2mem.mem_copy(&mut dst, &src)?;
+/// ```
+pub struct Mem2Mem<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    channel: Channel<TX, RX, P>,
+    peripheral: DmaPeripheral,
+}
+
+impl<TX, RX, P> Mem2Mem<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    /// Put the channel into memory-to-memory mode
+    pub fn new(mut channel: Channel<TX, RX, P>, peripheral: DmaPeripheral) -> Self {
+        channel.rx.set_mem2mem_mode(true);
+
+        Self {
+            channel,
+            peripheral,
+        }
+    }
+
+    /// Copy `src` to the beginning of `dst` and wait for the copy to finish
+    pub fn mem_copy(&mut self, dst: &mut [u8], src: &[u8]) -> Result<(), DmaError> {
+        if src.is_empty() {
+            return Ok(());
+        }
+
+        self.start(dst, src)?;
+
+        while !self.channel.rx.is_done() {}
+
+        Ok(())
+    }
+
+    /// Copy `src` to the beginning of `dst`, the CPU is free for other tasks
+    /// until the copy finishes
+    ///
+    /// The interrupt of the DMA channel needs to be enabled. Dropping the
+    /// future before it completes blocks until the copy has finished.
+    #[cfg(feature = "async")]
+    pub async fn mem_copy_async(&mut self, dst: &mut [u8], src: &[u8]) -> Result<(), DmaError> {
+        struct Guard<'a, RX: Rx>(&'a mut RX);
+
+        impl<'a, RX: Rx> Drop for Guard<'a, RX> {
+            fn drop(&mut self) {
+                // the buffers are only borrowed, the DMA must be done with them
+                // once the future is gone. The interrupt handler stops
+                // listening when it sees the copy finish.
+                while self.0.is_listening_eof() && !self.0.is_done() {}
+            }
+        }
+
+        if src.is_empty() {
+            return Ok(());
+        }
+
+        self.start(dst, src)?;
+
+        let mut guard = Guard(&mut self.channel.rx);
+        crate::dma::asynch::DmaRxFuture::new(&mut *guard.0).await;
+
+        Ok(())
+    }
+
+    /// Return the channel, back in normal mode
+    pub fn free(mut self) -> Channel<TX, RX, P> {
+        self.channel.rx.set_mem2mem_mode(false);
+        self.channel
+    }
+
+    fn start(&mut self, dst: &mut [u8], src: &[u8]) -> Result<(), DmaError> {
+        if dst.len() < src.len() {
+            return Err(DmaError::BufferTooSmall);
+        }
+
+        let burst_mode = self.channel.rx.burst_mode() || self.channel.tx.burst_mode();
+        if burst_mode
+            && (src.len() % 4 != 0 || src.as_ptr() as u32 % 4 != 0 || dst.as_ptr() as u32 % 4 != 0)
+        {
+            return Err(DmaError::InvalidAlignment);
+        }
+
+        self.channel
+            .rx
+            .prepare_transfer(false, self.peripheral, dst.as_mut_ptr(), src.len())?;
+        self.channel
+            .tx
+            .prepare_transfer(self.peripheral, false, src.as_ptr(), src.len())?;
+
+        Ok(())
+    }
+}
@@ -183,6 +183,10 @@ pub trait I2s0Peripheral: I2sPeripheral + PeripheralMarker {}
 /// Marks channels as useable for I2S1
 pub trait I2s1Peripheral: I2sPeripheral + PeripheralMarker {}
 
+/// Marks channels as useable for UHCI
+#[cfg(esp32c3)]
+pub trait UhciPeripheral: PeripheralMarker {}
+
 /// DMA Rx
 pub trait Rx: RxPrivate {}
 
@@ -220,6 +224,14 @@ pub trait RxPrivate {
 
     fn drain_buffer(&mut self, dst: &mut [u8]) -> Result<usize, DmaError>;
 
+    fn is_ring_full(&self) -> bool;
+
+    #[cfg(gdma)]
+    fn set_mem2mem_mode(&mut self, value: bool);
+
+    #[cfg(gdma)]
+    fn burst_mode(&self) -> bool;
+
     #[cfg(feature = "async")]
     fn waker() -> &'static embassy_sync::waitqueue::AtomicWaker;
 }
@@ -461,6 +473,30 @@ where
         Ok(len)
     }
 
+    /// Check whether the DMA filled every descriptor of a circular transfer
+    ///
+    /// In that case the data handed out by the last call to `available` is
+    /// about to be (or already has been) overwritten.
+    fn is_ring_full(&self) -> bool {
+        if self.last_seen_handled_descriptor_ptr.is_null() {
+            return false;
+        }
+
+        // find the descriptor preceding the next one to be handled
+        let first = self.descriptors.as_ptr();
+        let mut dscr = first;
+        loop {
+            let next = unsafe { dscr.offset(2).read_volatile() } as *const u32;
+            if next == self.last_seen_handled_descriptor_ptr || next.is_null() || next == first {
+                break;
+            }
+            dscr = next;
+        }
+
+        let mut dw0 = unsafe { &mut dscr.read_volatile() };
+        dw0.get_owner() == Owner::Cpu && dw0.get_length() != 0
+    }
+
     fn is_listening_eof(&self) -> bool {
         R::is_listening_in_eof()
     }
@@ -473,6 +509,16 @@ where
         R::unlisten_in_eof()
     }
 
+    #[cfg(gdma)]
+    fn set_mem2mem_mode(&mut self, value: bool) {
+        R::set_mem2mem_mode(value);
+    }
+
+    #[cfg(gdma)]
+    fn burst_mode(&self) -> bool {
+        self.burst_mode
+    }
+
     #[cfg(feature = "async")]
     fn waker() -> &'static embassy_sync::waitqueue::AtomicWaker {
         T::waker()
@@ -505,6 +551,9 @@ pub trait TxPrivate {
 
     fn push(&mut self, data: &[u8]) -> Result<usize, DmaError>;
 
+    #[cfg(gdma)]
+    fn burst_mode(&self) -> bool;
+
     #[cfg(feature = "async")]
     fn waker() -> &'static embassy_sync::waitqueue::AtomicWaker;
 }
@@ -777,6 +826,11 @@ where
         Ok(data.len())
     }
 
+    #[cfg(gdma)]
+    fn burst_mode(&self) -> bool {
+        self.burst_mode
+    }
+
     fn is_listening_eof(&self) -> bool {
         R::is_listening_out_eof()
     }
@@ -828,6 +882,9 @@ pub trait RegisterAccess {
     fn listen_out_eof();
     fn unlisten_in_eof();
     fn unlisten_out_eof();
+
+    #[cfg(gdma)]
+    fn set_mem2mem_mode(value: bool);
 }
 /// DMA Channel
 pub struct Channel<TX, RX, P>
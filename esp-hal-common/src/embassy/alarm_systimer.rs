@@ -0,0 +1,105 @@
+//! SYSTIMER backing of the time drivers, used by both the SYSTIMER and the
+//! runtime selected driver
+
+use core::cell::Cell;
+
+use critical_section::Mutex;
+
+use crate::{
+    peripherals,
+    systimer::{Alarm, SystemTimer, Target},
+};
+
+/// Ticks counted by the SYSTIMER between two wraps, zero if it never wraps
+const WRAP: u64 = SystemTimer::BIT_MASK.wrapping_add(1);
+
+/// The last raw counter value seen and the ticks of all previous wraps
+static EPOCH: Mutex<Cell<(u64, u64)>> = Mutex::new(Cell::new((0, 0)));
+
+/// The three SYSTIMER comparators, one per alarm of the time driver
+pub(crate) struct SystimerAlarms {
+    alarm0: Alarm<Target, 0>,
+    alarm1: Alarm<Target, 1>,
+    alarm2: Alarm<Target, 2>,
+}
+
+impl SystimerAlarms {
+    /// # Safety
+    ///
+    /// The comparators must not be used elsewhere, which is ensured by moving
+    /// the `SystemTimer` into the time driver on init
+    pub(crate) const unsafe fn conjure() -> Self {
+        Self {
+            alarm0: Alarm::<_, 0>::conjure(),
+            alarm1: Alarm::<_, 1>::conjure(),
+            alarm2: Alarm::<_, 2>::conjure(),
+        }
+    }
+
+    /// The SYSTIMER counter extended to a monotonic 64 bit value
+    ///
+    /// A wrap of the counter is detected by its value going backwards, which
+    /// requires this to be called at least once per wrap period (about 8.9
+    /// years at 16 MHz). Embassy does much more often than that.
+    pub(crate) fn now() -> u64 {
+        critical_section::with(|cs| {
+            let epoch = EPOCH.borrow(cs);
+            let (last, mut wrapped) = epoch.get();
+            let raw = SystemTimer::now();
+            if raw < last {
+                wrapped += WRAP;
+            }
+            epoch.set((raw, wrapped));
+            wrapped + raw
+        })
+    }
+
+    /// Enable the interrupts of all three comparators
+    pub(crate) fn enable_interrupts() {
+        use crate::{interrupt, interrupt::Priority};
+
+        interrupt::enable(peripherals::Interrupt::SYSTIMER_TARGET0, Priority::max()).unwrap();
+        interrupt::enable(peripherals::Interrupt::SYSTIMER_TARGET1, Priority::max()).unwrap();
+        interrupt::enable(peripherals::Interrupt::SYSTIMER_TARGET2, Priority::max()).unwrap();
+    }
+
+    pub(crate) fn clear_interrupt(&self, id: u8) {
+        match id {
+            0 => self.alarm0.clear_interrupt(),
+            1 => self.alarm1.clear_interrupt(),
+            2 => self.alarm2.clear_interrupt(),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Program the comparator of alarm `id` for `timestamp`, as returned by
+    /// [`Self::now`]
+    ///
+    /// The comparator only sees the lower bits of the timestamp, so an alarm
+    /// more than one wrap ahead matches before it is due. The driver has to
+    /// ignore that interrupt and leave the alarm armed, the same target
+    /// matches again after the next wrap.
+    pub(crate) fn arm(&self, id: u8, timestamp: u64) {
+        let target = timestamp & SystemTimer::BIT_MASK;
+        match id {
+            0 => self.alarm0.set_target(target),
+            1 => self.alarm1.set_target(target),
+            2 => self.alarm2.set_target(target),
+            _ => unreachable!(),
+        }
+        self.set_interrupt_enabled(id, true);
+    }
+
+    pub(crate) fn disarm(&self, id: u8) {
+        self.set_interrupt_enabled(id, false);
+    }
+
+    fn set_interrupt_enabled(&self, id: u8, enabled: bool) {
+        match id {
+            0 => self.alarm0.interrupt_enable(enabled),
+            1 => self.alarm1.interrupt_enable(enabled),
+            2 => self.alarm2.interrupt_enable(enabled),
+            _ => unreachable!(),
+        };
+    }
+}
@@ -0,0 +1,44 @@
+//! TIMG0 backing of the time drivers, used by both the TIMG0 and the runtime
+//! selected driver
+
+use crate::{
+    clock::Clocks,
+    peripherals::{self, TIMG0},
+    timer::{Instance, Timer, Timer0},
+};
+
+pub(crate) type TimgTimer = Timer<Timer0<TIMG0>>;
+
+/// Count `timer` at `tick_hz`, derived from the APB clock
+///
+/// Panics if the APB clock isn't a multiple of `tick_hz`, the tick rate of
+/// `embassy-time` is fixed at compile time and can't be adjusted to it.
+pub(crate) fn set_tick_rate(clocks: &Clocks, timer: &mut TimgTimer, tick_hz: u32) {
+    let apb_hz = clocks.apb_clock.to_Hz();
+    assert!(
+        apb_hz % tick_hz == 0,
+        "APB clock is not a multiple of the tick rate"
+    );
+    timer.set_divider(apb_hz / tick_hz).unwrap();
+}
+
+/// Enable the interrupt of timer 0
+pub(crate) fn enable_interrupt() {
+    use crate::{interrupt, interrupt::Priority};
+
+    interrupt::enable(peripherals::Interrupt::TG0_T0_LEVEL, Priority::max()).unwrap();
+}
+
+/// Program the alarm of `timer` for `timestamp`
+pub(crate) fn arm(timer: &mut TimgTimer, timestamp: u64) {
+    timer.load_alarm_value(timestamp);
+    timer.listen();
+    timer.set_counter_decrementing(false);
+    timer.set_auto_reload(false);
+    timer.set_counter_active(true);
+    timer.set_alarm_active(true);
+}
+
+pub(crate) fn disarm(timer: &mut TimgTimer) {
+    timer.unlisten();
+}
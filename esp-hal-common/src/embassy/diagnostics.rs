@@ -0,0 +1,74 @@
+//! Time driver alarm latency
+//!
+//! With the `embassy-time-diagnostics` feature enabled, the time driver
+//! records how late each alarm is handled: the difference between the
+//! timestamp an alarm was programmed for and the time at which its interrupt
+//! handler started running.
+//!
+//! The latency includes the time the interrupt was held off, e.g. by a
+//! critical section or by another interrupt handler at the same or a higher
+//! priority, plus the interrupt entry overhead. A `max` much larger than `min`
+//! points to code keeping interrupts masked for too long. Note that a timer
+//! being late can also be caused by the executor: the latency recorded here
+//! ends when the task is woken, not when it's polled.
+//!
+//! ```rust,ignore
+//! if let Some(latency) = embassy::diagnostics::alarm_latency() {
+//!     println!("alarm latency: max {} us", latency.max.as_micros());
+//! }
+//! ```
+
+use core::cell::Cell;
+
+use critical_section::{CriticalSection, Mutex};
+use embassy_time::Duration;
+
+/// Latency statistics of the time driver alarms
+#[derive(Debug, Clone, Copy)]
+pub struct AlarmLatency {
+    /// Smallest latency seen
+    pub min: Duration,
+    /// Largest latency seen
+    pub max: Duration,
+    /// Latency of the most recent alarm
+    pub last: Duration,
+    /// Number of alarms recorded
+    pub count: u32,
+}
+
+static LATENCY: Mutex<Cell<Option<AlarmLatency>>> = Mutex::new(Cell::new(None));
+
+/// Get the latency statistics since startup or the last [reset]
+///
+/// Returns `None` if no alarm fired yet.
+pub fn alarm_latency() -> Option<AlarmLatency> {
+    critical_section::with(|cs| LATENCY.borrow(cs).get())
+}
+
+/// Clear the recorded statistics, e.g. after the application finished starting
+/// up
+pub fn reset() {
+    critical_section::with(|cs| LATENCY.borrow(cs).set(None));
+}
+
+/// Record an alarm programmed for `timestamp` being handled at `now`
+pub(crate) fn record(timestamp: u64, now: u64, cs: CriticalSection) {
+    let latency = Duration::from_ticks(now.saturating_sub(timestamp));
+
+    let stats = LATENCY.borrow(cs);
+    let updated = match stats.get() {
+        Some(stats) => AlarmLatency {
+            min: Duration::min(stats.min, latency),
+            max: Duration::max(stats.max, latency),
+            last: latency,
+            count: stats.count.saturating_add(1),
+        },
+        None => AlarmLatency {
+            min: latency,
+            max: latency,
+            last: latency,
+            count: 1,
+        },
+    };
+    stats.set(Some(updated));
+}
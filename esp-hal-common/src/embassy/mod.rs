@@ -1,7 +1,98 @@
+//! Embassy support
+//!
+//! Provides the time driver for `embassy-time`, backed by either the SYSTIMER
+//! (`embassy-time-systick`) or TIMG0 (`embassy-time-timg0`).
+//!
+//! The SYSTIMER driver uses all three SYSTIMER alarms. When one of them is
+//! needed elsewhere, e.g. for a [`Delay`](crate::Delay) sleeping until an
+//! alarm on RISC-V chips, use the TIMG0 driver instead. It counts the 64 bit
+//! timer 0 of TIMG0 at 1 MHz and uses its alarm, which allows a single alarm
+//! for the executor.
+//!
+//! # Number of alarms
+//!
+//! A hardware alarm is allocated per executor, not per `Timer`: with the
+//! `integrated-timers` feature of `embassy-executor` each executor keeps its
+//! own queue of pending timers and only programs the alarm for the earliest
+//! one. Any number of `Timer`s can be pending at the same time, the alarm
+//! count only limits the number of executors (three with the SYSTIMER, one
+//! with TIMG0).
+//!
+//! # Selecting the timer at runtime
+//!
+//! On chips with a SYSTIMER, the `embassy-time-runtime` feature builds a driver
+//! that can use either timer, chosen by the [`TimerSource`] passed to
+//! [`init`]. This is useful when the same firmware sometimes needs the
+//! SYSTIMER for something else. TIMG0 is divided down from the APB clock to
+//! the 16 MHz of the SYSTIMER, so the tick rate of `embassy-time` doesn't
+//! change. The feature is therefore only available on the ESP32-C3, ESP32-C6
+//! and ESP32-S3, the SYSTIMER of the ESP32-C2 runs at 16 MHz only with a 40
+//! MHz XTAL and its 40 MHz APB clock can't be divided down to that. Selecting
+//! TIMG0 still panics if the CPU is clocked from the XTAL, which lowers the
+//! APB clock to 40 MHz.
+//!
+//! There is still only one time driver in the application, so the choice is
+//! global and can only be made once, before the executor is started. The
+//! driver also claims the interrupt handlers of both timers (the three
+//! SYSTIMER targets and `TG0_T0_LEVEL`), even though only the ones of the
+//! selected timer are enabled. With TIMG0 only a single alarm is available.
+//!
+//! # Dual-core chips
+//!
+//! On the ESP32 and ESP32-S3 the alarms may be set from both cores, e.g. by
+//! executors running on either of them. `set_alarm` and the alarm interrupt
+//! run in a critical section, which on these chips also takes a spinlock
+//! shared by both cores, so an alarm is never programmed by both cores at
+//! once. An interrupt that was raised for an earlier timestamp, while the other
+//! core already moved the alarm to a later one, is ignored: the callback only
+//! runs once the timestamp currently set has been reached.
+//!
+//! # Power usage while idle
+//!
+//! When all tasks are pending, the thread-mode `Executor` of
+//! `embassy-executor` puts the CPU to sleep with `wfi` (RISC-V) or `waiti 0`
+//! (Xtensa) until the next interrupt, e.g. the alarm of the time driver or an
+//! interrupt of one of the async drivers. A wake-up signalled right before
+//! going to sleep is not lost: the executor checks for pending work with
+//! interrupts masked, and both instructions return as soon as an interrupt is
+//! pending, even while it is masked.
+//!
+//! On the ESP32-C2, ESP32-C3, ESP32-S2 and ESP32-S3,
+//! [`Rtc::new`](crate::Rtc::new) allows the CPU clock to be gated while
+//! waiting, so an idle application draws considerably less current than a busy
+//! loop.
+
 use core::{cell::Cell, ptr};
 
+use critical_section::CriticalSection;
 use embassy_time::driver::{AlarmHandle, Driver};
 
+#[cfg(any(
+    all(feature = "embassy-time-systick", feature = "embassy-time-timg0"),
+    all(feature = "embassy-time-systick", feature = "embassy-time-runtime"),
+    all(feature = "embassy-time-timg0", feature = "embassy-time-runtime"),
+))]
+compile_error!(
+    "Only one of the `embassy-time-systick`, `embassy-time-timg0` and `embassy-time-runtime` \
+     features may be enabled"
+);
+
+#[cfg(all(feature = "embassy-time-runtime", not(any(esp32c3, esp32c6, esp32s3))))]
+compile_error!(
+    "The `embassy-time-runtime` feature is only available on the ESP32-C3, ESP32-C6 and ESP32-S3"
+);
+
+#[cfg(all(
+    systimer,
+    any(feature = "embassy-time-systick", feature = "embassy-time-runtime")
+))]
+mod alarm_systimer;
+#[cfg(all(
+    timg0,
+    any(feature = "embassy-time-timg0", feature = "embassy-time-runtime")
+))]
+mod alarm_timg;
+
 #[cfg_attr(
     all(systimer, feature = "embassy-time-systick",),
     path = "time_driver_systimer.rs"
@@ -10,16 +101,70 @@ use embassy_time::driver::{AlarmHandle, Driver};
     all(timg0, feature = "embassy-time-timg0"),
     path = "time_driver_timg.rs"
 )]
+#[cfg_attr(
+    all(systimer, timg0, feature = "embassy-time-runtime"),
+    path = "time_driver_runtime.rs"
+)]
 mod time_driver;
 
+#[cfg(feature = "embassy-time-runtime")]
+pub use time_driver::TimerSource;
+
+#[cfg(feature = "embassy-time-diagnostics")]
+pub mod diagnostics;
+
 use time_driver::EmbassyTimer;
 
 use crate::clock::Clocks;
 
+/// Set up the time driver
+///
+/// The timer is moved into the driver for the rest of the program, and with
+/// it the `SYSTIMER` or `TIMG0` peripheral it was created from, so the
+/// compiler rejects any other use of it. The remaining peripherals stay
+/// available as usual.
 pub fn init(clocks: &Clocks, td: time_driver::TimerType) {
     EmbassyTimer::init(clocks, td)
 }
 
+/// Async delay running on the embassy time driver
+///
+/// Every delay is a timer in the queue of the executor, which only ever
+/// programs a single hardware alarm for the earliest of them. A delay doesn't
+/// claim a `SYSTIMER` comparator of its own, so any number of tasks can wait
+/// concurrently, each with its own `Delay`. The resolution is one tick of
+/// `embassy-time`.
+///
+/// ```rust,ignore
+/// use embedded_hal_async::delay::DelayUs;
+///
+/// let mut delay = embassy::Delay;
+/// delay.delay_ms(100).await.unwrap();
+/// ```
+#[cfg(feature = "async")]
+#[derive(Clone, Copy, Default)]
+pub struct Delay;
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayUs for Delay {
+    type Error = core::convert::Infallible;
+
+    async fn delay_us(&mut self, us: u32) -> Result<(), Self::Error> {
+        embassy_time::Timer::after(embassy_time::Duration::from_micros(us as u64)).await;
+        Ok(())
+    }
+
+    async fn delay_ms(&mut self, ms: u32) -> Result<(), Self::Error> {
+        embassy_time::Timer::after(embassy_time::Duration::from_millis(ms as u64)).await;
+        Ok(())
+    }
+}
+
+/// State of one alarm of a time driver
+///
+/// Only ever accessed through the driver's `critical_section::Mutex`. On the
+/// dual-core chips the critical section also takes a spinlock shared by both
+/// cores, so plain `Cell`s can't be observed half-written from the other core.
 pub struct AlarmState {
     pub timestamp: Cell<u64>,
 
@@ -43,6 +188,38 @@ impl AlarmState {
     }
 }
 
+impl EmbassyTimer {
+    /// Run the callback of alarm `n` if its timestamp has been reached at
+    /// `now`, the time its interrupt handler started running
+    ///
+    /// On the dual-core chips the other core may have moved the alarm to a
+    /// later time while this interrupt was already pending, and the SYSTIMER
+    /// comparator matches an alarm more than one wrap ahead early. Such an
+    /// interrupt is ignored, the hardware is already programmed for the
+    /// timestamp currently set.
+    pub(crate) fn on_alarm(&self, n: usize, now: u64, cs: CriticalSection) {
+        let timestamp = self.alarms.borrow(cs)[n].timestamp.get();
+        if timestamp > now {
+            return;
+        }
+
+        #[cfg(feature = "embassy-time-diagnostics")]
+        diagnostics::record(timestamp, now, cs);
+
+        self.trigger_alarm(n, cs);
+    }
+
+    pub(crate) fn trigger_alarm(&self, n: usize, cs: CriticalSection) {
+        let alarm = &self.alarms.borrow(cs)[n];
+        // safety:
+        // - we can ignore the possiblity of `f` being unset (null) because of the
+        //   safety contract of `allocate_alarm`.
+        // - other than that we only store valid function pointers into alarm.callback
+        let f: fn(*mut ()) = unsafe { core::mem::transmute(alarm.callback.get()) };
+        f(alarm.ctx.get());
+    }
+}
+
 impl Driver for EmbassyTimer {
     fn now(&self) -> u64 {
         EmbassyTimer::now()
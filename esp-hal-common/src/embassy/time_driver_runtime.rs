@@ -0,0 +1,143 @@
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use super::{
+    alarm_systimer::SystimerAlarms,
+    alarm_timg::{self, TimgTimer},
+    AlarmState,
+};
+use crate::{clock::Clocks, prelude::*, systimer::SystemTimer};
+
+pub const ALARM_COUNT: usize = 3;
+
+/// Tick rate of `embassy-time` with this driver, the rate of the SYSTIMER
+///
+/// TIMG0 is divided down from the APB clock to the same rate, so
+/// `embassy-time` can be built with a single tick rate no matter which timer
+/// is selected. This restricts the driver to the chips with a 16 MHz SYSTIMER
+/// and an APB clock that is a multiple of it, see the `embassy` module docs.
+const TICK_HZ: u32 = SystemTimer::TICKS_PER_SECOND as u32;
+
+/// Timer backing the time driver
+pub enum TimerSource {
+    /// The SYSTIMER, providing 3 alarms
+    Systimer(SystemTimer<'static>),
+    /// Timer0 of TIMG0, providing a single alarm
+    Timg0(TimgTimer),
+}
+
+pub type TimerType = TimerSource;
+
+pub struct EmbassyTimer {
+    pub(crate) alarms: Mutex<[AlarmState; ALARM_COUNT]>,
+    pub(crate) systimer: SystimerAlarms,
+    // `Some` if TIMG0 was selected
+    pub(crate) timer: Mutex<RefCell<Option<TimgTimer>>>,
+}
+
+const ALARM_STATE_NONE: AlarmState = AlarmState::new();
+
+embassy_time::time_driver_impl!(static DRIVER: EmbassyTimer = EmbassyTimer {
+    alarms: Mutex::new([ALARM_STATE_NONE; ALARM_COUNT]),
+    systimer: unsafe { SystimerAlarms::conjure() },
+    timer: Mutex::new(RefCell::new(None)),
+});
+
+impl EmbassyTimer {
+    pub(crate) fn now() -> u64 {
+        critical_section::with(|cs| match DRIVER.timer.borrow_ref(cs).as_ref() {
+            Some(timer) => timer.now(),
+            None => SystimerAlarms::now(),
+        })
+    }
+
+    fn on_systimer_interrupt(&self, id: u8) {
+        let now = SystimerAlarms::now();
+
+        self.systimer.clear_interrupt(id);
+        critical_section::with(|cs| self.on_alarm(id as usize, now, cs))
+    }
+
+    fn on_timg_interrupt(&self) {
+        critical_section::with(|cs| {
+            let now = {
+                let mut tg = self.timer.borrow_ref_mut(cs);
+                let tg = tg.as_mut().unwrap();
+                tg.clear_interrupt();
+                tg.now()
+            };
+
+            self.on_alarm(0, now, cs);
+        });
+    }
+
+    pub fn init(clocks: &Clocks, source: TimerType) {
+        match source {
+            TimerSource::Systimer(_) => SystimerAlarms::enable_interrupts(),
+            TimerSource::Timg0(mut timer) => {
+                alarm_timg::set_tick_rate(clocks, &mut timer, TICK_HZ);
+
+                critical_section::with(|cs| {
+                    DRIVER.timer.borrow_ref_mut(cs).replace(timer);
+
+                    // only a single alarm is available, make sure the others are never
+                    // handed out
+                    for alarm in &DRIVER.alarms.borrow(cs)[1..] {
+                        alarm.allocated.set(true);
+                    }
+                });
+
+                alarm_timg::enable_interrupt();
+            }
+        }
+
+        #[interrupt]
+        fn SYSTIMER_TARGET0() {
+            DRIVER.on_systimer_interrupt(0);
+        }
+        #[interrupt]
+        fn SYSTIMER_TARGET1() {
+            DRIVER.on_systimer_interrupt(1);
+        }
+        #[interrupt]
+        fn SYSTIMER_TARGET2() {
+            DRIVER.on_systimer_interrupt(2);
+        }
+        #[interrupt]
+        fn TG0_T0_LEVEL() {
+            DRIVER.on_timg_interrupt();
+        }
+    }
+
+    pub(crate) fn set_alarm(
+        &self,
+        alarm: embassy_time::driver::AlarmHandle,
+        timestamp: u64,
+    ) -> bool {
+        critical_section::with(|cs| {
+            let now = Self::now();
+            let alarm_state = unsafe { self.alarms.borrow(cs).get_unchecked(alarm.id() as usize) };
+            let mut tg = self.timer.borrow_ref_mut(cs);
+
+            if timestamp < now {
+                // If alarm timestamp has passed the alarm will not fire.
+                // Disarm the alarm and return `false` to indicate that.
+                match tg.as_mut() {
+                    Some(tg) => alarm_timg::disarm(tg),
+                    None => self.systimer.disarm(alarm.id()),
+                }
+                alarm_state.timestamp.set(u64::MAX);
+                return false;
+            }
+            alarm_state.timestamp.set(timestamp);
+
+            match tg.as_mut() {
+                Some(tg) => alarm_timg::arm(tg, timestamp),
+                None => self.systimer.arm(alarm.id(), timestamp),
+            }
+
+            true
+        })
+    }
+}
@@ -1,19 +1,19 @@
 use core::cell::RefCell;
 
-use critical_section::{CriticalSection, Mutex};
-use peripherals::TIMG0;
-
-use super::AlarmState;
-use crate::{
-    clock::Clocks,
-    peripherals,
-    prelude::*,
-    timer::{Timer, Timer0},
+use critical_section::Mutex;
+
+use super::{
+    alarm_timg::{self, TimgTimer},
+    AlarmState,
 };
+use crate::{clock::Clocks, prelude::*};
 
 pub const ALARM_COUNT: usize = 1;
 
-pub type TimerType = Timer<Timer0<TIMG0>>;
+pub type TimerType = TimgTimer;
+
+/// Tick rate of `embassy-time` with this driver
+const TICK_HZ: u32 = 1_000_000;
 
 pub struct EmbassyTimer {
     pub(crate) alarms: Mutex<[AlarmState; ALARM_COUNT]>,
@@ -32,35 +32,25 @@ impl EmbassyTimer {
         critical_section::with(|cs| DRIVER.timer.borrow_ref(cs).as_ref().unwrap().now())
     }
 
-    pub(crate) fn trigger_alarm(&self, n: usize, cs: CriticalSection) {
-        let alarm = &self.alarms.borrow(cs)[n];
-        // safety:
-        // - we can ignore the possiblity of `f` being unset (null) because of the
-        //   safety contract of `allocate_alarm`.
-        // - other than that we only store valid function pointers into alarm.callback
-        let f: fn(*mut ()) = unsafe { core::mem::transmute(alarm.callback.get()) };
-        f(alarm.ctx.get());
-    }
-
     fn on_interrupt(&self, id: u8) {
         critical_section::with(|cs| {
-            let mut tg = self.timer.borrow_ref_mut(cs);
-            let tg = tg.as_mut().unwrap();
-            tg.clear_interrupt();
-            self.trigger_alarm(id as usize, cs);
+            let now = {
+                let mut tg = self.timer.borrow_ref_mut(cs);
+                let tg = tg.as_mut().unwrap();
+                tg.clear_interrupt();
+                tg.now()
+            };
+
+            self.on_alarm(id as usize, now, cs);
         });
     }
 
     pub fn init(clocks: &Clocks, mut timer: TimerType) {
-        use crate::{interrupt, interrupt::Priority};
-
-        // set divider to get a 1mhz clock. abp (80mhz) / 80 = 1mhz... // TODO assert
-        // abp clock is the source and its at the correct speed for the divider
-        timer.set_divider(clocks.apb_clock.to_MHz() as u16);
+        alarm_timg::set_tick_rate(clocks, &mut timer, TICK_HZ);
 
         critical_section::with(|cs| DRIVER.timer.borrow_ref_mut(cs).replace(timer));
 
-        interrupt::enable(peripherals::Interrupt::TG0_T0_LEVEL, Priority::max()).unwrap();
+        alarm_timg::enable_interrupt();
 
         #[interrupt]
         fn TG0_T0_LEVEL() {
@@ -79,18 +69,12 @@ impl EmbassyTimer {
             let mut tg = self.timer.borrow_ref_mut(cs);
             let tg = tg.as_mut().unwrap();
             if timestamp < now {
-                tg.unlisten();
+                alarm_timg::disarm(tg);
                 alarm_state.timestamp.set(u64::MAX);
                 return false;
             }
             alarm_state.timestamp.set(timestamp);
-
-            tg.load_alarm_value(timestamp);
-            tg.listen();
-            tg.set_counter_decrementing(false);
-            tg.set_auto_reload(false);
-            tg.set_counter_active(true);
-            tg.set_alarm_active(true);
+            alarm_timg::arm(tg, timestamp);
 
             true
         })
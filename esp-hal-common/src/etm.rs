@@ -0,0 +1,237 @@
+//! Event Task Matrix (ETM)
+//!
+//! The ETM connects events of one peripheral to tasks of another in hardware,
+//! e.g. a SYSTIMER alarm to toggling a GPIO. Once a channel is set up, the
+//! task is carried out a few clock cycles after the event, without any
+//! involvement of the CPU or interrupt latency.
+//!
+//! Each of the 50 channels binds one [`Event`] to one [`Task`]. Several
+//! channels can share an event or a task.
+//!
+//! GPIOs take part through the 8 GPIO event channels and the 8 GPIO task
+//! channels of the GPIO matrix: [`Etm::set_gpio_event_channel`] selects the
+//! pin whose edges an event channel reports, [`Etm::add_gpio_to_task_channel`]
+//! adds an output pin to the pins a task channel sets, clears or toggles.
+//!
+//! ```no_run
+//! let mut etm = Etm::new(peripherals.SOC_ETM, &mut system.peripheral_clock_control);
+//! etm.add_gpio_to_task_channel(0, &led);
+//!
+//! let mut channel = etm.get_channel(0);
+//! channel.bind(Event::SystimerAlarm0, Task::GpioToggle(0));
+//! channel.enable();
+//! ```
+//!
+//! Only the ESP32-C6 has an ETM. The events and tasks of the other peripherals
+//! are not supported yet.
+
+use crate::{
+    gpio::Pin,
+    peripheral::{Peripheral, PeripheralRef},
+    peripherals::SOC_ETM,
+    system::PeripheralClockControl,
+};
+
+/// Number of ETM channels
+pub const CHANNEL_COUNT: u8 = 50;
+
+/// Number of GPIO event channels, and of GPIO task channels
+pub const GPIO_CHANNEL_COUNT: u8 = 8;
+
+// SOC_ETM registers
+const CH_ENA_AD0_SET: usize = 0x04;
+const CH_ENA_AD0_CLR: usize = 0x08;
+const CH_ENA_AD1_SET: usize = 0x10;
+const CH_ENA_AD1_CLR: usize = 0x14;
+const CH0_EVT_ID: usize = 0x18;
+const CH0_TASK_ID: usize = 0x1c;
+const CLK_EN: usize = 0x1a8;
+
+// ETM registers of the GPIO matrix (GPIO_EXT)
+const GPIO_EXT_BASE: usize = 0x6009_1f00;
+const GPIO_ETM_EVENT_CH0_CFG: usize = 0x118;
+const GPIO_ETM_TASK_P0_CFG: usize = 0x198;
+const GPIO_ETM_EVENT_EN: u32 = 1 << 7;
+
+/// An event that can trigger an ETM channel
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// Rising edge at the pin of a GPIO event channel (0 to 7)
+    GpioRisingEdge(u8),
+    /// Falling edge at the pin of a GPIO event channel (0 to 7)
+    GpioFallingEdge(u8),
+    /// Any edge at the pin of a GPIO event channel (0 to 7)
+    GpioAnyEdge(u8),
+    /// Alarm of timer 0 of TIMG0
+    Tg0Timer0Alarm,
+    /// Alarm of timer 0 of TIMG1
+    Tg1Timer0Alarm,
+    /// SYSTIMER alarm 0, see
+    /// [`SystemTimer::enable_etm`](crate::systimer::SystemTimer::enable_etm)
+    SystimerAlarm0,
+    /// SYSTIMER alarm 1
+    SystimerAlarm1,
+    /// SYSTIMER alarm 2
+    SystimerAlarm2,
+}
+
+impl Event {
+    fn id(self) -> u32 {
+        match self {
+            Event::GpioRisingEdge(ch) => 1 + gpio_channel(ch),
+            Event::GpioFallingEdge(ch) => 9 + gpio_channel(ch),
+            Event::GpioAnyEdge(ch) => 17 + gpio_channel(ch),
+            Event::Tg0Timer0Alarm => 47,
+            Event::Tg1Timer0Alarm => 49,
+            Event::SystimerAlarm0 => 51,
+            Event::SystimerAlarm1 => 52,
+            Event::SystimerAlarm2 => 53,
+        }
+    }
+}
+
+/// A task an ETM channel can carry out
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Task {
+    /// Set the pins of a GPIO task channel (0 to 7) high
+    GpioSet(u8),
+    /// Set the pins of a GPIO task channel (0 to 7) low
+    GpioClear(u8),
+    /// Toggle the pins of a GPIO task channel (0 to 7)
+    GpioToggle(u8),
+}
+
+impl Task {
+    fn id(self) -> u32 {
+        match self {
+            Task::GpioSet(ch) => 1 + gpio_channel(ch),
+            Task::GpioClear(ch) => 9 + gpio_channel(ch),
+            Task::GpioToggle(ch) => 17 + gpio_channel(ch),
+        }
+    }
+}
+
+fn gpio_channel(channel: u8) -> u32 {
+    assert!(channel < GPIO_CHANNEL_COUNT, "Invalid GPIO ETM channel");
+    channel as u32
+}
+
+fn etm_register(offset: usize) -> *mut u32 {
+    (SOC_ETM::PTR as usize + offset) as *mut u32
+}
+
+fn gpio_register(offset: usize) -> *mut u32 {
+    (GPIO_EXT_BASE + offset) as *mut u32
+}
+
+/// The ETM peripheral
+pub struct Etm<'d> {
+    _instance: PeripheralRef<'d, SOC_ETM>,
+}
+
+impl<'d> Etm<'d> {
+    /// Return a new ETM driver, with all channels disabled
+    pub fn new(
+        instance: impl Peripheral<P = SOC_ETM> + 'd,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Self {
+        crate::into_ref!(instance);
+        peripheral_clock_control.enable(crate::system::Peripheral::Etm);
+
+        unsafe {
+            etm_register(CLK_EN).write_volatile(1);
+            etm_register(CH_ENA_AD0_CLR).write_volatile(u32::MAX);
+            etm_register(CH_ENA_AD1_CLR).write_volatile(u32::MAX);
+        }
+
+        Etm {
+            _instance: instance,
+        }
+    }
+
+    /// Return a channel
+    pub fn get_channel(&self, number: u8) -> Channel {
+        assert!(number < CHANNEL_COUNT, "Invalid ETM channel");
+        Channel { number }
+    }
+
+    /// Report the edges at `pin` through the GPIO event `channel`
+    ///
+    /// A pin used as an input needs its input enabled.
+    pub fn set_gpio_event_channel(&mut self, channel: u8, pin: &impl Pin) {
+        let channel = gpio_channel(channel) as usize;
+        unsafe {
+            gpio_register(GPIO_ETM_EVENT_CH0_CFG + 4 * channel)
+                .write_volatile(GPIO_ETM_EVENT_EN | pin.number() as u32);
+        }
+    }
+
+    /// Let the GPIO task `channel` drive `pin`
+    ///
+    /// A pin belongs to at most one task channel, adding it to another one
+    /// moves it. The pin has to be configured as an output.
+    pub fn add_gpio_to_task_channel(&mut self, channel: u8, pin: &impl Pin) {
+        let channel = gpio_channel(channel);
+        let (register, shift) = Self::gpio_task_field(pin);
+        unsafe {
+            let value = register.read_volatile() & !(0xf << shift);
+            register.write_volatile(value | (channel << 1 | 1) << shift);
+        }
+    }
+
+    /// Stop driving `pin` from its GPIO task channel
+    pub fn remove_gpio_from_task_channel(&mut self, pin: &impl Pin) {
+        let (register, shift) = Self::gpio_task_field(pin);
+        unsafe {
+            register.write_volatile(register.read_volatile() & !(0xf << shift));
+        }
+    }
+
+    // four pins per register, 8 bits each: enable, then the channel
+    fn gpio_task_field(pin: &impl Pin) -> (*mut u32, u32) {
+        let number = pin.number() as usize;
+        (
+            gpio_register(GPIO_ETM_TASK_P0_CFG + 4 * (number / 4)),
+            8 * (number % 4) as u32,
+        )
+    }
+}
+
+/// An ETM channel
+pub struct Channel {
+    number: u8,
+}
+
+impl Channel {
+    /// Carry out `task` on every `event`
+    ///
+    /// Takes effect once the channel is enabled.
+    pub fn bind(&mut self, event: Event, task: Task) {
+        let number = self.number as usize;
+        unsafe {
+            etm_register(CH0_EVT_ID + 8 * number).write_volatile(event.id());
+            etm_register(CH0_TASK_ID + 8 * number).write_volatile(task.id());
+        }
+    }
+
+    /// Enable the channel
+    pub fn enable(&mut self) {
+        let (register, bit) = self.enable_bit(CH_ENA_AD0_SET, CH_ENA_AD1_SET);
+        unsafe { etm_register(register).write_volatile(bit) };
+    }
+
+    /// Disable the channel
+    pub fn disable(&mut self) {
+        let (register, bit) = self.enable_bit(CH_ENA_AD0_CLR, CH_ENA_AD1_CLR);
+        unsafe { etm_register(register).write_volatile(bit) };
+    }
+
+    // channels 0 to 31 are in the first register, the others in the second
+    fn enable_bit(&self, first: usize, second: usize) -> (usize, u32) {
+        if self.number < 32 {
+            (first, 1 << self.number)
+        } else {
+            (second, 1 << (self.number - 32))
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Minimal single-future executor
+//!
+//! For applications which only need to run one async task (e.g. a protocol
+//! state machine) and don't want to pull in a full executor such as
+//! `embassy-executor`, [`block_on`] drives a single future to completion.
+//!
+//! Between polls the CPU is put to sleep until the next interrupt arrives. The
+//! async drivers of this crate wake their futures from their interrupt
+//! handlers, so the relevant peripheral interrupts still need to be enabled,
+//! just like when using embassy.
+//!
+//! Only one top-level future is supported: all tasks share a single wake flag,
+//! so [`block_on`] must not be called from within a future it is driving, nor
+//! from an interrupt handler.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+/// Set by the waker, cleared before every poll
+static SIGNALED: AtomicBool = AtomicBool::new(false);
+
+static VTABLE: RawWakerVTable =
+    RawWakerVTable::new(waker_clone, waker_wake, waker_wake, waker_drop);
+
+unsafe fn waker_clone(_: *const ()) -> RawWaker {
+    RawWaker::new(core::ptr::null(), &VTABLE)
+}
+
+unsafe fn waker_wake(_: *const ()) {
+    SIGNALED.store(true, Ordering::Release);
+}
+
+unsafe fn waker_drop(_: *const ()) {}
+
+/// Run `future` to completion, sleeping between polls
+pub fn block_on<F: Future>(mut future: F) -> F::Output {
+    // safety: `future` is shadowed and never moved again
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        SIGNALED.store(false, Ordering::Release);
+
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+
+        wait_for_event();
+    }
+}
+
+#[cfg(riscv)]
+fn wait_for_event() {
+    // `wfi` returns as soon as an interrupt is pending, even while they are
+    // masked, so checking the flag with interrupts disabled can't miss a wake
+    // up happening right before going to sleep
+    critical_section::with(|_| {
+        if !SIGNALED.load(Ordering::Acquire) {
+            unsafe { core::arch::asm!("wfi") };
+        }
+    });
+}
+
+#[cfg(xtensa)]
+fn wait_for_event() {
+    unsafe {
+        let token: u32;
+        core::arch::asm!("rsil {0}, 5", out(reg) token);
+        if !SIGNALED.load(Ordering::Acquire) {
+            // lowers the interrupt level to 0 and waits, atomically
+            core::arch::asm!("waiti 0");
+        }
+        core::arch::asm!("wsr.ps {0}", "rsync", in(reg) token);
+    }
+}
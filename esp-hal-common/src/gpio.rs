@@ -7,12 +7,47 @@
 //! let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
 //! let mut led = io.pins.gpio5.into_push_pull_output();
 //! ```
+//!
+//! ## Pull resistors
+//!
+//! The internal pull-up and pull-down resistors (enabled with e.g.
+//! [`GpioPin::into_pull_up_input`] or [`OutputPin::internal_pull_up`]) have a
+//! fixed value of roughly 45 kΩ on all supported chips. The IO MUX only has an
+//! enable bit per direction, there is no way to select a stronger pull. Buses
+//! that need faster rise times, like I2C at 400 kHz, still require external
+//! resistors.
+//!
+//! ## Interrupts and direction changes
+//!
+//! The interrupt configuration set with [`Pin::listen`] belongs to the pin,
+//! not to its mode: converting a pin between input and output (or calling
+//! [`InputPin::set_to_input`] and [`OutputPin::set_to_push_pull_output`]) keeps
+//! it. Call [`Pin::unlisten`] to get rid of it explicitly.
+//!
+//! Events are detected on the input path of the pin though, so what is
+//! detected depends on the mode:
+//! - In input mode all events on the pin are detected.
+//! - A push-pull output has its input disabled, no events are detected until
+//!   the pin is an input again. Enable the input with
+//!   [`InputPin::enable_input`] to also detect the edges driven by the pin
+//!   itself.
+//! - An open-drain output keeps its input enabled and detects all events,
+//!   including the ones it causes itself.
+//!
+//! Switching the input back on can look like an edge to the interrupt logic
+//! without any change on the pin. That status is cleared when a pin with a
+//! disabled input is converted into an input, so a bidirectional line only
+//! sees the edges that happen while it listens.
 
-use core::{convert::Infallible, marker::PhantomData};
+use core::{
+    convert::Infallible,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
 
 use crate::peripherals::{GPIO, IO_MUX};
 pub use crate::soc::gpio::*;
-pub(crate) use crate::{analog, gpio};
+pub(crate) use crate::{analog, gpio, rtc_pins};
 
 #[derive(Copy, Clone)]
 pub enum Event {
@@ -83,6 +118,7 @@ pub enum AlternateFunction {
     Function5 = 5,
 }
 
+/// A pin in the RTC domain
 pub trait RTCPin {}
 
 pub trait AnalogPin {}
@@ -190,8 +226,12 @@ pub trait OutputPin: Pin {
     /// outputs connected to the signal remain intact.
     fn disconnect_peripheral_from_output(&mut self) -> &mut Self;
 
+    /// Enable/disable the internal pull-up resistor (about 45 kΩ, not
+    /// adjustable)
     fn internal_pull_up(&mut self, on: bool) -> &mut Self;
 
+    /// Enable/disable the internal pull-down resistor (about 45 kΩ, not
+    /// adjustable)
     fn internal_pull_down(&mut self, on: bool) -> &mut Self;
 }
 
@@ -344,6 +384,8 @@ pub trait BankGpioRegisterAccess {
 
     fn write_interrupt_status_clear(word: u32);
 
+    fn write_output(word: u32);
+
     fn write_output_set(word: u32);
 
     fn write_output_clear(word: u32);
@@ -430,6 +472,12 @@ impl BankGpioRegisterAccess for Bank0GpioRegisterAccess {
             .write(|w| unsafe { w.bits(word) });
     }
 
+    fn write_output(word: u32) {
+        unsafe { &*GPIO::PTR }
+            .out
+            .write(|w| unsafe { w.bits(word) });
+    }
+
     fn write_output_set(word: u32) {
         unsafe { &*GPIO::PTR }
             .out_w1ts
@@ -471,6 +519,12 @@ impl BankGpioRegisterAccess for Bank1GpioRegisterAccess {
             .write(|w| unsafe { w.bits(word) });
     }
 
+    fn write_output(word: u32) {
+        unsafe { &*GPIO::PTR }
+            .out1
+            .write(|w| unsafe { w.bits(word) });
+    }
+
     fn write_output_set(word: u32) {
         unsafe { &*GPIO::PTR }
             .out1_w1ts
@@ -484,6 +538,40 @@ impl BankGpioRegisterAccess for Bank1GpioRegisterAccess {
     }
 }
 
+/// Toggle the output level of all pins in `mask` at the same time
+///
+/// Bit `n` of `mask` corresponds to GPIO`n`, see [`Pin::number`]. All pins are
+/// updated by a single write to the output register, so they change their
+/// level in the same clock cycle, which keeps e.g. differential or
+/// multi-phase signals aligned. Separate writes to the set and clear
+/// registers would be a few cycles apart.
+///
+/// The set (W1TS) and clear (W1TC) registers can't be used for this: pins
+/// going high and pins going low would need one write each, so e.g. the two
+/// lines of a differential pair would switch a few cycles apart.
+///
+/// Only pins configured as GPIO outputs are affected. The read-modify-write
+/// runs in a critical section, which keeps interrupt handlers on the current
+/// core from changing other pins of the same bank in between. It doesn't stop
+/// the other core of the ESP32 and ESP32-S3: a pin of the same bank written
+/// from there at the same time can lose its update.
+pub fn toggle_pins(mask: u32) {
+    critical_section::with(|_| {
+        Bank0GpioRegisterAccess::write_output(Bank0GpioRegisterAccess::read_output() ^ mask);
+    });
+}
+
+/// Toggle the output level of all pins in `mask` at the same time
+///
+/// Like [`toggle_pins`] for GPIO32 and above, bit `n` of `mask` corresponds
+/// to GPIO`32 + n`. Pins in different banks can't be toggled at the same time.
+#[cfg(not(any(esp32c2, esp32c3, esp32c6)))]
+pub fn toggle_pins_bank1(mask: u32) {
+    critical_section::with(|_| {
+        Bank1GpioRegisterAccess::write_output(Bank1GpioRegisterAccess::read_output() ^ mask);
+    });
+}
+
 pub fn connect_low_to_peripheral(signal: InputSignal) {
     unsafe { &*GPIO::PTR }.func_in_sel_cfg[signal as usize].modify(|_, w| unsafe {
         w.sel()
@@ -642,6 +730,7 @@ where
 
     fn init_input(&self, pull_down: bool, pull_up: bool) {
         let gpio = unsafe { &*GPIO::PTR };
+        let input_was_enabled = get_io_mux_reg(GPIONUM).read().fun_ie().bit_is_set();
 
         RA::write_out_en_clear(1 << (GPIONUM % 32));
         gpio.func_out_sel_cfg[GPIONUM as usize]
@@ -662,6 +751,11 @@ where
                 .slp_sel()
                 .clear_bit()
         });
+
+        // enabling the input buffer isn't an edge on the pin, see the module docs
+        if !input_was_enabled {
+            RA::write_interrupt_status_clear(1 << (GPIONUM % 32));
+        }
     }
 
     pub fn into_floating_input(self) -> GpioPin<Input<Floating>, RA, IRA, PINTYPE, SIG, GPIONUM> {
@@ -883,6 +977,62 @@ where
     }
 }
 
+impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8>
+    GpioPin<Output<MODE>, RA, IRA, PINTYPE, SIG, GPIONUM>
+where
+    RA: BankGpioRegisterAccess,
+    IRA: InteruptStatusRegisterAccess,
+    PINTYPE: IsOutputPin,
+    SIG: GpioSignal,
+{
+    /// Drive the pin high with a single store to the `W1TS` register
+    ///
+    /// Does the same as [`OutputPin::set_high`], but is always inlined and
+    /// writes the register directly instead of going through the register
+    /// access layer of the pin's bank. That layer isn't generic, so without
+    /// LTO calling it from another crate is a function call per edge. No
+    /// checks are needed: the pin is an output by its type.
+    ///
+    /// On the ESP32-C3 in a release build without LTO, an edge with this
+    /// method is one `sw` to the `W1TS` register, the address and mask are
+    /// kept in registers across a toggle loop. With [`OutputPin::set_high`]
+    /// it is a `jal` to the register access layer, the same `sw` and a `ret`,
+    /// plus loading the address there, so about four instructions more per
+    /// edge. These are instruction counts from the code, not timings: how
+    /// many CPU cycles an edge takes also depends on the bus the store goes
+    /// through. The `gpio_toggle_speed` example measures the cycles per edge
+    /// of both at 160 MHz, the difference is largest in debug builds.
+    ///
+    /// [`OutputPin::set_high`]: embedded_hal::digital::v2::OutputPin::set_high
+    #[inline(always)]
+    pub fn set_high_unchecked(&mut self) {
+        let mask = 1 << (GPIONUM % 32);
+        let gpio = unsafe { &*GPIO::PTR };
+
+        #[cfg(not(any(esp32c2, esp32c3, esp32c6)))]
+        if GPIONUM >= 32 {
+            gpio.out1_w1ts.write(|w| unsafe { w.bits(mask) });
+            return;
+        }
+        gpio.out_w1ts.write(|w| unsafe { w.bits(mask) });
+    }
+
+    /// Drive the pin low with a single store to the `W1TC` register, see
+    /// [`Self::set_high_unchecked`]
+    #[inline(always)]
+    pub fn set_low_unchecked(&mut self) {
+        let mask = 1 << (GPIONUM % 32);
+        let gpio = unsafe { &*GPIO::PTR };
+
+        #[cfg(not(any(esp32c2, esp32c3, esp32c6)))]
+        if GPIONUM >= 32 {
+            gpio.out1_w1tc.write(|w| unsafe { w.bits(mask) });
+            return;
+        }
+        gpio.out_w1tc.write(|w| unsafe { w.bits(mask) });
+    }
+}
+
 impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8> embedded_hal::digital::v2::StatefulOutputPin
     for GpioPin<Output<MODE>, RA, IRA, PINTYPE, SIG, GPIONUM>
 where
@@ -985,6 +1135,141 @@ where
     }
 }
 
+/// Error returned by [`GpioPin::verify_output`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The input of the pin is disabled, its level can't be read back
+    InputDisabled,
+    /// The pin isn't at the level it is driven to
+    Mismatch {
+        /// The level the pin is driven to
+        driven_high: bool,
+    },
+}
+
+impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8>
+    GpioPin<Output<MODE>, RA, IRA, PINTYPE, SIG, GPIONUM>
+where
+    RA: BankGpioRegisterAccess,
+    IRA: InteruptStatusRegisterAccess,
+    PINTYPE: IsOutputPin,
+    SIG: GpioSignal,
+{
+    /// Checks that the pin actually is at the level it is driven to
+    ///
+    /// Waits `settle_us` microseconds, then compares the level read back on
+    /// the pin to the commanded one. A mismatch means the line is shorted,
+    /// stuck or overdriven by something else.
+    ///
+    /// The level is read through the input buffer of the pin, which push-pull
+    /// outputs have disabled. Enable it with [`InputPin::enable_input`] first,
+    /// [`VerifyError::InputDisabled`] is returned otherwise. Open-drain outputs
+    /// keep their input enabled. Note that a released open-drain output is
+    /// only high if something pulls the line up.
+    ///
+    /// The settling time has to cover the edge of the commanded level: with a
+    /// capacitive load, or a weak pull-up on an open-drain line, the pin takes
+    /// a while to cross the input threshold after it was set. Verifying too
+    /// early reports a mismatch on a healthy line.
+    pub fn verify_output<D>(&self, delay: &mut D, settle_us: u32) -> Result<(), VerifyError>
+    where
+        D: embedded_hal::blocking::delay::DelayUs<u32>,
+    {
+        if get_io_mux_reg(GPIONUM).read().fun_ie().bit_is_clear() {
+            return Err(VerifyError::InputDisabled);
+        }
+
+        delay.delay_us(settle_us);
+
+        let driven_high = RA::read_output() & (1 << (GPIONUM % 32)) != 0;
+        let is_high = RA::read_input() & (1 << (GPIONUM % 32)) != 0;
+        if is_high != driven_high {
+            return Err(VerifyError::Mismatch { driven_high });
+        }
+
+        Ok(())
+    }
+}
+
+/// Synchronization of one stage of the input path, see
+/// [`GpioPin::set_input_sync`]
+#[cfg(not(esp32))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSync {
+    /// The stage is bypassed
+    Bypass,
+    /// The input is sampled on the falling edge of the APB clock
+    FallingEdge,
+    /// The input is sampled on the rising edge of the APB clock
+    RisingEdge,
+}
+
+#[cfg(not(esp32))]
+impl InputSync {
+    fn bits(self) -> u8 {
+        match self {
+            InputSync::Bypass => 0,
+            InputSync::FallingEdge => 1,
+            InputSync::RisingEdge => 2,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits {
+            0 => InputSync::Bypass,
+            1 => InputSync::FallingEdge,
+            _ => InputSync::RisingEdge,
+        }
+    }
+}
+
+#[cfg(not(esp32))]
+impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8> GpioPin<MODE, RA, IRA, PINTYPE, SIG, GPIONUM>
+where
+    RA: BankGpioRegisterAccess,
+    IRA: InteruptStatusRegisterAccess,
+    PINTYPE: PinType,
+    SIG: GpioSignal,
+{
+    /// Configure the two synchronizer stages the input passes through
+    /// before it reaches the GPIO matrix
+    ///
+    /// Each stage samples the pin on an edge of the APB clock, which delays
+    /// the input by up to a clock cycle per stage. Bypassing stages reduces
+    /// the latency from pin to register, e.g. for cycle-accurate timing of
+    /// external signals.
+    ///
+    /// The pin is asynchronous to the APB clock. A flip-flop sampling it
+    /// while it changes can go metastable, and the synchronizers give it time
+    /// to settle before the level is used. With both stages bypassed an edge
+    /// can be seen as a level between low and high, which may be read
+    /// differently by different parts of the chip, or trigger an interrupt
+    /// more than once. Only bypass them for signals that are already
+    /// synchronous to the APB clock, or where an occasional wrong sample is
+    /// acceptable.
+    pub fn set_input_sync(&mut self, first: InputSync, second: InputSync) -> &mut Self {
+        unsafe { &*GPIO::PTR }.pin[GPIONUM as usize].modify(|_, w| unsafe {
+            w.sync1_bypass()
+                .bits(first.bits())
+                .sync2_bypass()
+                .bits(second.bits())
+        });
+
+        self
+    }
+
+    /// The current configuration of the synchronizer stages, see
+    /// [`Self::set_input_sync`]
+    pub fn input_sync(&self) -> (InputSync, InputSync) {
+        let pin = unsafe { &*GPIO::PTR }.pin[GPIONUM as usize].read();
+
+        (
+            InputSync::from_bits(pin.sync1_bypass().bits()),
+            InputSync::from_bits(pin.sync2_bypass().bits()),
+        )
+    }
+}
+
 impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8> crate::peripheral::Peripheral
     for GpioPin<MODE, RA, IRA, PINTYPE, SIG, GPIONUM>
 where
@@ -1500,6 +1785,53 @@ impl IO {
         };
         io
     }
+
+    /// Enable the GPIO interrupt at `priority`
+    ///
+    /// All pins share one peripheral interrupt, so the priority applies to
+    /// every pin listening for events. Per-pin priorities aren't possible: a
+    /// pin which has to be serviced at a different priority needs to be
+    /// handled through another peripheral which can be routed to a separate
+    /// CPU interrupt.
+    ///
+    /// On Xtensa chips a higher priority interrupt preempts the handlers of
+    /// lower priority interrupts. On RISC-V chips handlers don't nest, the
+    /// priority only decides which pending interrupt is serviced first.
+    #[cfg(feature = "vectored")]
+    pub fn set_interrupt_priority(
+        &mut self,
+        priority: crate::interrupt::Priority,
+    ) -> Result<(), crate::interrupt::Error> {
+        crate::interrupt::enable(crate::peripherals::Interrupt::GPIO, priority)
+    }
+}
+
+/// Output an internal clock on `pin`
+///
+/// The clock is routed through the GPIO matrix, so any GPIO can be used. This
+/// is useful to provide a reference clock to an external device or to verify
+/// the clock tree with a scope. The pads are not designed for fast clocks,
+/// expect the 80 MHz clock to be distorted.
+///
+/// There's no divider in the clock output path, to output a divided clock use
+/// the LEDC peripheral instead.
+#[cfg(esp32c3)]
+pub fn set_clock_output<P: OutputPin>(
+    pin: &mut P,
+    channel: ClockOutputChannel,
+    source: ClockOutputSource,
+) {
+    crate::soc::gpio::set_clock_output_source(channel, Some(source));
+
+    pin.set_to_push_pull_output()
+        .connect_peripheral_to_output(channel.output_signal());
+}
+
+/// Stop outputting the clock previously selected for `channel` by
+/// [`set_clock_output`]
+#[cfg(esp32c3)]
+pub fn disable_clock_output(channel: ClockOutputChannel) {
+    crate::soc::gpio::set_clock_output_source(channel, None);
 }
 
 #[doc(hidden)]
@@ -1805,9 +2137,153 @@ macro_rules! analog {
     }
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! rtc_pins {
+    (
+        $($pin_num:literal)+
+    ) => {
+        $(
+            paste! {
+                impl<MODE> $crate::gpio::RTCPin for [<Gpio $pin_num>]<MODE> {}
+            }
+        )+
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_COUNT: AtomicU32 = AtomicU32::new(0);
+static EDGE_COUNTS: [AtomicU32; NUM_PINS] = [NEW_COUNT; NUM_PINS];
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_COUNTING: AtomicBool = AtomicBool::new(false);
+static EDGE_COUNTING: [AtomicBool; NUM_PINS] = [NEW_COUNTING; NUM_PINS];
+
+impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8>
+    GpioPin<Input<MODE>, RA, IRA, PINTYPE, SIG, GPIONUM>
+where
+    RA: BankGpioRegisterAccess,
+    IRA: InteruptStatusRegisterAccess,
+    PINTYPE: PinType,
+    SIG: GpioSignal,
+{
+    /// Count `event` edges on this pin using the GPIO interrupt
+    ///
+    /// See [`EdgeCounter`].
+    pub fn into_counter(self, event: Event) -> EdgeCounter<Self> {
+        EdgeCounter::new(self, event)
+    }
+}
+
+/// Edge counter driven by the GPIO interrupt
+///
+/// An alternative to the PCNT peripheral for chips which don't have one or
+/// when all of its units are in use. Every edge raises an interrupt, so this
+/// only works for slow signals: each edge costs an interrupt entry and exit,
+/// which limits the edge rate to a few 100 kHz at best. Faster signals, or
+/// interrupts being masked for longer than one period, lead to edges being
+/// missed, as two edges arriving before the interrupt is serviced count once.
+/// Use the PCNT peripheral where available for anything faster, or if no edge
+/// may be lost.
+///
+/// With the `async` feature the counting is done by the GPIO interrupt handler
+/// of this crate. Otherwise [`count_edges`] has to be called from the
+/// application's GPIO interrupt handler. In both cases the GPIO interrupt has
+/// to be enabled.
+pub struct EdgeCounter<P: Pin> {
+    pin: P,
+}
+
+impl<P: Pin> EdgeCounter<P> {
+    /// Start counting `event` edges on `pin`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `event` is a level instead of an edge.
+    pub fn new(mut pin: P, event: Event) -> Self {
+        assert!(
+            matches!(
+                event,
+                Event::RisingEdge | Event::FallingEdge | Event::AnyEdge
+            ),
+            "Only edges can be counted"
+        );
+
+        let n = pin.number() as usize;
+        EDGE_COUNTS[n].store(0, Ordering::Relaxed);
+        EDGE_COUNTING[n].store(true, Ordering::Release);
+
+        pin.clear_interrupt();
+        pin.listen(event);
+
+        Self { pin }
+    }
+
+    /// Number of edges counted so far, wrapping around on overflow
+    pub fn count(&self) -> u32 {
+        EDGE_COUNTS[self.pin.number() as usize].load(Ordering::Relaxed)
+    }
+
+    /// Reset the count to 0
+    pub fn reset(&mut self) {
+        EDGE_COUNTS[self.pin.number() as usize].store(0, Ordering::Relaxed);
+    }
+
+    /// Stop counting and return the pin
+    pub fn free(mut self) -> P {
+        self.pin.unlisten();
+        EDGE_COUNTING[self.pin.number() as usize].store(false, Ordering::Release);
+
+        self.pin
+    }
+}
+
+/// Update the [`EdgeCounter`]s whose pins fired and clear their interrupts
+///
+/// Call this from the GPIO interrupt handler when not using the `async`
+/// feature. Interrupts of other pins are left untouched.
+pub fn count_edges() {
+    let mut intrs =
+        SingleCoreInteruptStatusRegisterAccessBank0::pro_cpu_interrupt_status_read() as u64;
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    {
+        intrs |= (SingleCoreInteruptStatusRegisterAccessBank1::pro_cpu_interrupt_status_read()
+            as u64)
+            << 32;
+    }
+
+    let mut handled = 0u64;
+    while intrs != 0 {
+        let pin_nr = intrs.trailing_zeros() as usize;
+        intrs &= !(1 << pin_nr);
+
+        if EDGE_COUNTING[pin_nr].load(Ordering::Acquire) {
+            // only ever incremented from the interrupt handler, so there's no
+            // need for a read-modify-write operation
+            let count = &EDGE_COUNTS[pin_nr];
+            count.store(
+                count.load(Ordering::Relaxed).wrapping_add(1),
+                Ordering::Relaxed,
+            );
+            handled |= 1 << pin_nr;
+        }
+    }
+
+    Bank0GpioRegisterAccess::write_interrupt_status_clear(handled as u32);
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    Bank1GpioRegisterAccess::write_interrupt_status_clear((handled >> 32) as u32);
+}
+
+#[cfg(feature = "async")]
+pub use self::asynch::{clear_interrupt_callback, set_interrupt_callback};
+
 #[cfg(feature = "async")]
 mod asynch {
-    use core::task::{Context, Poll};
+    use core::{
+        ptr,
+        sync::atomic::{AtomicPtr, Ordering},
+        task::{Context, Poll},
+    };
 
     use embassy_sync::waitqueue::AtomicWaker;
     use embedded_hal_async::digital::Wait;
@@ -1819,6 +2295,30 @@ mod asynch {
     const NEW_AW: AtomicWaker = AtomicWaker::new();
     static PIN_WAKERS: [AtomicWaker; NUM_PINS] = [NEW_AW; NUM_PINS];
 
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW_CB: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+    static PIN_CALLBACKS: [AtomicPtr<()>; NUM_PINS] = [NEW_CB; NUM_PINS];
+
+    /// Call `callback` from the GPIO interrupt handler whenever `pin` fires
+    ///
+    /// With the `async` feature the GPIO interrupt handler is provided by this
+    /// crate. By default it wakes the task awaiting the pin, registering a
+    /// callback makes the handler call `callback` instead, so pins used by
+    /// async tasks and pins needing an immediate reaction can be mixed freely.
+    ///
+    /// The interrupt still needs to be enabled via [`Pin::listen`]. Unlike for
+    /// awaited pins, it stays enabled after firing, so a level triggered
+    /// callback has to [`Pin::unlisten`] the pin itself. The pin must not be
+    /// awaited while a callback is registered.
+    pub fn set_interrupt_callback<P: Pin>(pin: &mut P, callback: fn()) {
+        PIN_CALLBACKS[pin.number() as usize].store(callback as *mut (), Ordering::Release);
+    }
+
+    /// Remove the callback registered with [`set_interrupt_callback`]
+    pub fn clear_interrupt_callback<P: Pin>(pin: &mut P) {
+        PIN_CALLBACKS[pin.number() as usize].store(ptr::null_mut(), Ordering::Release);
+    }
+
     impl<MODE, RA, IRA, PINTYPE, SIG, const GPIONUM: u8> Wait
         for GpioPin<Input<MODE>, RA, IRA, PINTYPE, SIG, GPIONUM>
     where
@@ -1890,6 +2390,8 @@ mod asynch {
         #[cfg(any(esp32, esp32s2, esp32s3))]
         type Bank1 = SingleCoreInteruptStatusRegisterAccessBank1;
 
+        count_edges();
+
         let mut intrs = Bank0::pro_cpu_interrupt_status_read() as u64;
 
         #[cfg(any(esp32, esp32s2, esp32s3))]
@@ -1897,13 +2399,23 @@ mod asynch {
             intrs |= (Bank1::pro_cpu_interrupt_status_read() as u64) << 32;
         }
 
-        // clear interrupts
-        Bank0GpioRegisterAccess::write_interrupt_status_clear(!0);
+        // only clear the interrupts handled below, another pin might have fired
+        // in the meantime
+        Bank0GpioRegisterAccess::write_interrupt_status_clear(intrs as u32);
         #[cfg(any(esp32, esp32s2, esp32s3))]
-        Bank1GpioRegisterAccess::write_interrupt_status_clear(!0);
+        Bank1GpioRegisterAccess::write_interrupt_status_clear((intrs >> 32) as u32);
 
         while intrs != 0 {
             let pin_nr = intrs.trailing_zeros();
+            intrs &= !(1 << pin_nr);
+
+            let callback = PIN_CALLBACKS[pin_nr as usize].load(Ordering::Acquire);
+            if !callback.is_null() {
+                let callback: fn() = core::mem::transmute(callback);
+                callback();
+                continue;
+            }
+
             cfg_if::cfg_if! {
                 if #[cfg(any(esp32, esp32s2, esp32s3))] {
                     if pin_nr < 32 {
@@ -1916,7 +2428,6 @@ mod asynch {
                 }
             }
             PIN_WAKERS[pin_nr as usize].wake(); // wake task
-            intrs &= !(1 << pin_nr);
         }
     }
 }
@@ -25,7 +25,15 @@ cfg_if::cfg_if! {
 pub enum Error {
     ExceedingFifo,
     AckCheckFailed,
-    TimeOut,
+    /// SCL didn't change for longer than the timeout, e.g. because a device
+    /// stretched the clock
+    ///
+    /// Carries the number of bytes received before the timeout: the start of
+    /// the read buffer up to this length holds valid data, the rest is
+    /// unchanged. This allows using the partial data of an interrupted read.
+    /// Always 0 for writes and on the ESP32 and ESP32-S2, which only read
+    /// the data once the whole transfer completed.
+    TimeOut(usize),
     ArbitrationLost,
     ExecIncomplete,
     CommandNrExceeded,
@@ -152,6 +160,9 @@ enum Opcode {
 }
 
 /// I2C peripheral container (I2C)
+///
+/// Dropping the driver leaves the peripheral enabled and the pins connected,
+/// use [`I2C::free`] to also stop the peripheral clock.
 pub struct I2C<'d, T> {
     peripheral: PeripheralRef<'d, T>,
 }
@@ -263,6 +274,9 @@ where
     /// Create a new I2C instance
     /// This will enable the peripheral but the peripheral won't get
     /// automatically disabled when this gets dropped.
+    ///
+    /// Transfers time out with [`Error::TimeOut`] if SCL doesn't change for
+    /// about 10 bus clock cycles, see [`I2C::new_with_timeout`].
     pub fn new<SDA: OutputPin + InputPin, SCL: OutputPin + InputPin>(
         i2c: impl Peripheral<P = T> + 'd,
         sda: impl Peripheral<P = SDA> + 'd,
@@ -270,6 +284,38 @@ where
         frequency: HertzU32,
         peripheral_clock_control: &mut PeripheralClockControl,
         clocks: &Clocks,
+    ) -> Self {
+        Self::new_with_timeout(
+            i2c,
+            sda,
+            scl,
+            frequency,
+            peripheral_clock_control,
+            clocks,
+            Some(10),
+        )
+    }
+
+    /// Create a new I2C instance with a custom timeout
+    ///
+    /// `timeout` is given in bus clock cycles: if SCL doesn't change for that
+    /// long during a transfer (e.g. because a device holds it low), the
+    /// transfer is aborted and returns [`Error::TimeOut`] instead of hanging
+    /// forever. The hardware rounds the value, on the ESP32-C2, ESP32-C3,
+    /// ESP32-C6 and ESP32-S3 up to a power of two.
+    ///
+    /// `None` selects the longest timeout the hardware supports, which is
+    /// generous enough for devices that stretch the clock for a long time:
+    /// around 13 ms on the ESP32, 210 ms on the ESP32-S2 and 100 ms on the
+    /// other chips.
+    pub fn new_with_timeout<SDA: OutputPin + InputPin, SCL: OutputPin + InputPin>(
+        i2c: impl Peripheral<P = T> + 'd,
+        sda: impl Peripheral<P = SDA> + 'd,
+        scl: impl Peripheral<P = SCL> + 'd,
+        frequency: HertzU32,
+        peripheral_clock_control: &mut PeripheralClockControl,
+        clocks: &Clocks,
+        timeout: Option<u32>,
     ) -> Self {
         crate::into_ref!(i2c, sda, scl);
         enable_peripheral(&i2c, peripheral_clock_control);
@@ -289,10 +335,314 @@ where
             .connect_peripheral_to_output(OutputSignal::I2CEXT0_SDA)
             .connect_input_to_peripheral(InputSignal::I2CEXT0_SDA);
 
-        i2c.peripheral.setup(frequency, clocks);
+        i2c.peripheral.setup(frequency, clocks, timeout);
+
+        i2c
+    }
+
+    /// Change the timeout, in bus clock cycles
+    ///
+    /// Takes effect from the next transfer on. One bus clock cycle is the
+    /// period of the configured bus frequency, i.e. 10 us at 100 kHz, so a
+    /// timeout of 1000 cycles gives up after SCL was stuck for 10 ms. The
+    /// value is rounded and limited like in [`I2C::new_with_timeout`], `None`
+    /// selects the longest timeout.
+    pub fn set_timeout(&mut self, timeout: Option<u32>) {
+        self.peripheral.set_timeout(timeout);
+    }
+
+    /// Write `bytes` to the general call address (0x00)
+    ///
+    /// A general call is received by every device on the bus that supports
+    /// it, devices that don't simply ignore it. It succeeds as soon as one
+    /// device acknowledges, [`Error::AckCheckFailed`] means none did.
+    ///
+    /// The meaning of the first byte is defined by the I2C specification:
+    /// `0x06` asks the devices to reset and take over the programmable part of
+    /// their address, `0x04` to only take over the address. Other values are
+    /// device specific, e.g. to latch the outputs of several DACs at once.
+    /// Check the datasheets of the devices, many sensors don't respond to
+    /// general calls at all.
+    pub fn write_general_call(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.peripheral.master_write(0x00, bytes)
+    }
+
+    /// Scan the bus for devices
+    ///
+    /// Probes the 7-bit addresses 0x08 to 0x77 in ascending order with a
+    /// zero-length write, i.e. a START, the address and a STOP, and yields
+    /// the addresses that were acknowledged. The addresses reserved by the
+    /// I2C specification are skipped, 0x00 is the general call, see
+    /// [`I2C::write_general_call`]. Probing happens lazily while iterating,
+    /// every address takes about 10 bus clock cycles.
+    ///
+    /// An address that isn't acknowledged aborts its transfer and resets the
+    /// controller like any other failed transfer, which releases the bus
+    /// before the next address is probed. Any other error, like a timeout or
+    /// lost arbitration, ends the scan early: the bus is unusable at that
+    /// point, check [`I2C::is_bus_busy`] afterwards.
+    ///
+    /// Some devices don't acknowledge zero-length writes, or misinterpret
+    /// them, e.g. as the start of a command. Such devices aren't found by a
+    /// scan even if they are present.
+    pub fn scan(&mut self) -> impl Iterator<Item = u8> + '_ {
+        let mut failed = false;
+        (0x08..=0x77).filter_map(move |address| {
+            if failed {
+                return None;
+            }
+
+            match self.peripheral.master_write(address, &[]) {
+                Ok(()) => Some(address),
+                Err(Error::AckCheckFailed) => None,
+                Err(_) => {
+                    failed = true;
+                    None
+                }
+            }
+        })
+    }
+
+    /// Whether the bus is busy
+    ///
+    /// The bus is busy between a START and a STOP condition, no matter which
+    /// master issued them. A bus that stays busy outside of our own transfers
+    /// indicates another master, or a device holding SDA or SCL low - e.g.
+    /// after a transfer was aborted with [`Error::TimeOut`].
+    pub fn is_bus_busy(&self) -> bool {
+        self.peripheral.is_bus_busy()
+    }
+
+    /// Release the peripheral
+    ///
+    /// Stops the peripheral clock. Transfers are blocking, so none is in
+    /// progress at this point and SDA and SCL have been released by the
+    /// controller. The pins stay connected to the peripheral, and as the I2C
+    /// instance and the pins were only borrowed for `'d`, they can be
+    /// reconfigured afterwards or be used to construct a new driver, which
+    /// enables the clock again.
+    pub fn free(self, peripheral_clock_control: &mut PeripheralClockControl) {
+        disable_peripheral(&self.peripheral, peripheral_clock_control);
+    }
+}
+
+/// I2C peripheral operating as a slave
+///
+/// Data written by the master is collected in the RX FIFO and data read by
+/// the master is served from the TX FIFO, both of which are 32 bytes deep.
+/// Longer transfers are possible as long as the FIFOs are drained/refilled in
+/// time, typically from the I2C interrupt handler.
+///
+/// When the master reads while the TX FIFO is empty, the slave holds SCL low
+/// (clock stretching) until new data has been written and
+/// [`I2cSlave::release_clock_stretch`] is called, which gives the firmware
+/// time to prepare a response.
+///
+/// Only 7-bit addressing is supported.
+#[cfg(any(esp32c2, esp32c3, esp32c6, esp32s3))]
+pub struct I2cSlave<'d, T> {
+    peripheral: PeripheralRef<'d, T>,
+}
+
+#[cfg(any(esp32c2, esp32c3, esp32c6, esp32s3))]
+impl<'d, T> I2cSlave<'d, T>
+where
+    T: Instance,
+{
+    /// Depth of the RX and TX FIFOs
+    pub const FIFO_SIZE: usize = 32;
+
+    /// Create a new I2C slave instance responding to the 7-bit `address`
+    pub fn new<SDA: OutputPin + InputPin, SCL: OutputPin + InputPin>(
+        i2c: impl Peripheral<P = T> + 'd,
+        sda: impl Peripheral<P = SDA> + 'd,
+        scl: impl Peripheral<P = SCL> + 'd,
+        address: u8,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Self {
+        crate::into_ref!(i2c, sda, scl);
+        enable_peripheral(&i2c, peripheral_clock_control);
+
+        let mut i2c = I2cSlave { peripheral: i2c };
+
+        // SCL is driven by the slave as well in order to stretch the clock
+        scl.set_to_open_drain_output()
+            .enable_input(true)
+            .internal_pull_up(true)
+            .connect_peripheral_to_output(OutputSignal::I2CEXT0_SCL)
+            .connect_input_to_peripheral(InputSignal::I2CEXT0_SCL);
+
+        sda.set_to_open_drain_output()
+            .enable_input(true)
+            .internal_pull_up(true)
+            .connect_peripheral_to_output(OutputSignal::I2CEXT0_SDA)
+            .connect_input_to_peripheral(InputSignal::I2CEXT0_SDA);
+
+        let register_block = i2c.peripheral.register_block();
+
+        register_block.ctr.write(|w| {
+            // Slave mode (`ms_mode` cleared)
+            w.sda_force_out()
+                .set_bit()
+                .scl_force_out()
+                .set_bit()
+                // Start sending as soon as the master addresses us for reading
+                .slv_tx_auto_start_en()
+                .set_bit()
+                .clk_en()
+                .set_bit()
+        });
+
+        register_block.slave_addr.write(|w| unsafe {
+            w.slave_addr()
+                .bits(address as u16)
+                .addr_10bit_en()
+                .clear_bit()
+        });
+
+        register_block.scl_stretch_conf.modify(|_, w| unsafe {
+            w.slave_scl_stretch_en()
+                .set_bit()
+                .stretch_protect_num()
+                .bits(0x3ff)
+        });
+
+        i2c.peripheral.set_filter(Some(7), Some(7));
+
+        // Reset entire peripheral (also resets fifo)
+        i2c.peripheral.reset();
+        i2c.peripheral.update_config();
 
         i2c
     }
+
+    /// Also respond to the general call address (0x00)
+    ///
+    /// Disabled by default. When enabled, the bytes of a general call written
+    /// by the master end up in the RX FIFO like those of a write to our own
+    /// address, there is no way to tell them apart afterwards.
+    pub fn set_general_call(&mut self, enabled: bool) {
+        self.peripheral
+            .register_block()
+            .ctr
+            .modify(|_, w| w.addr_broadcasting_en().bit(enabled));
+        self.peripheral.update_config();
+    }
+
+    /// Move the bytes written by the master from the RX FIFO into `buffer`
+    ///
+    /// Returns the number of bytes read, which is limited by both the length
+    /// of `buffer` and the number of bytes available.
+    pub fn read(&mut self, buffer: &mut [u8]) -> usize {
+        let available = self
+            .peripheral
+            .register_block()
+            .sr
+            .read()
+            .rxfifo_cnt()
+            .bits() as usize;
+        let count = available.min(buffer.len());
+
+        for byte in buffer[..count].iter_mut() {
+            *byte = read_fifo(self.peripheral.register_block());
+        }
+
+        count
+    }
+
+    /// Queue `bytes` to be sent to the master on its next read
+    ///
+    /// Returns the number of bytes which fit into the TX FIFO.
+    pub fn write(&mut self, bytes: &[u8]) -> usize {
+        let queued = self
+            .peripheral
+            .register_block()
+            .sr
+            .read()
+            .txfifo_cnt()
+            .bits() as usize;
+        let count = (Self::FIFO_SIZE - queued).min(bytes.len());
+
+        for byte in &bytes[..count] {
+            write_fifo(self.peripheral.register_block(), *byte);
+        }
+
+        count
+    }
+
+    /// Returns `true` if the current (or last) transfer is a read by the
+    /// master
+    pub fn is_master_read(&self) -> bool {
+        self.peripheral
+            .register_block()
+            .sr
+            .read()
+            .slave_rw()
+            .bit_is_set()
+    }
+
+    /// Discard any data left in the RX and TX FIFOs
+    pub fn reset_fifo(&mut self) {
+        self.peripheral.reset_fifo();
+    }
+
+    /// Listen for TRANS-COMPLETE, RX-FIFO-WATERMARK and SLAVE-STRETCH
+    /// interrupts
+    pub fn listen(&mut self) {
+        self.peripheral.register_block().int_ena.modify(|_, w| {
+            w.trans_complete_int_ena()
+                .set_bit()
+                .rxfifo_wm_int_ena()
+                .set_bit()
+                .slave_stretch_int_ena()
+                .set_bit()
+        });
+    }
+
+    /// Stop listening for interrupts
+    pub fn unlisten(&mut self) {
+        self.peripheral.register_block().int_ena.modify(|_, w| {
+            w.trans_complete_int_ena()
+                .clear_bit()
+                .rxfifo_wm_int_ena()
+                .clear_bit()
+                .slave_stretch_int_ena()
+                .clear_bit()
+        });
+    }
+
+    /// Checks if the master has finished a transfer (STOP condition)
+    pub fn is_transfer_complete(&self) -> bool {
+        self.peripheral
+            .register_block()
+            .int_raw
+            .read()
+            .trans_complete_int_raw()
+            .bit_is_set()
+    }
+
+    /// Checks if SCL is currently being held low waiting for the firmware
+    pub fn is_clock_stretched(&self) -> bool {
+        self.peripheral
+            .register_block()
+            .int_raw
+            .read()
+            .slave_stretch_int_raw()
+            .bit_is_set()
+    }
+
+    /// Release SCL after the TX FIFO has been refilled
+    pub fn release_clock_stretch(&mut self) {
+        self.peripheral
+            .register_block()
+            .scl_stretch_conf
+            .modify(|_, w| w.slave_scl_stretch_clr().set_bit());
+    }
+
+    /// Reset all interrupts
+    pub fn clear_interrupts(&mut self) {
+        self.peripheral.clear_all_interrupts();
+    }
 }
 
 fn enable_peripheral<'d, T>(
@@ -310,13 +660,27 @@ fn enable_peripheral<'d, T>(
     }
 }
 
+fn disable_peripheral<'d, T>(
+    i2c: &PeripheralRef<'d, T>,
+    peripheral_clock_control: &mut PeripheralClockControl,
+) where
+    T: Instance,
+{
+    match i2c.i2c_number() {
+        0 => peripheral_clock_control.disable(crate::system::Peripheral::I2cExt0),
+        #[cfg(i2c1)]
+        1 => peripheral_clock_control.disable(crate::system::Peripheral::I2cExt1),
+        _ => unreachable!(), // will never happen
+    }
+}
+
 /// I2C Peripheral Instance
 pub trait Instance {
     fn register_block(&self) -> &RegisterBlock;
 
     fn i2c_number(&self) -> usize;
 
-    fn setup(&mut self, frequency: HertzU32, clocks: &Clocks) {
+    fn setup(&mut self, frequency: HertzU32, clocks: &Clocks, timeout: Option<u32>) {
         self.register_block().ctr.modify(|_, w| unsafe {
             // Clear register
             w.bits(0)
@@ -348,7 +712,7 @@ pub trait Instance {
         self.set_filter(Some(7), Some(7));
 
         // Configure frequency
-        self.set_frequency(clocks.i2c_clock.convert(), frequency);
+        self.set_frequency(clocks.i2c_clock.convert(), frequency, timeout);
 
         // Propagate configuration changes (only necessary with C2, C3, and S3)
         #[cfg(any(esp32c2, esp32c3, esp32c6, esp32s3))]
@@ -429,7 +793,7 @@ pub trait Instance {
     /// Sets the frequency of the I2C interface by calculating and applying the
     /// associated timings - corresponds to i2c_ll_cal_bus_clk and
     /// i2c_ll_set_bus_timing in ESP-IDF
-    fn set_frequency(&mut self, source_clk: HertzU32, bus_freq: HertzU32) {
+    fn set_frequency(&mut self, source_clk: HertzU32, bus_freq: HertzU32, timeout: Option<u32>) {
         let source_clk = source_clk.raw();
         let bus_freq = bus_freq.raw();
 
@@ -440,7 +804,7 @@ pub trait Instance {
         let sda_sample = scl_high / 2;
         let setup = half_cycle;
         let hold = half_cycle;
-        let tout = half_cycle * 20; // default we set the timeout value to 10 bus cycles.
+        let tout = timeout_value(timeout, half_cycle);
 
         // SCL period. According to the TRM, we should always subtract 1 to SCL low
         // period
@@ -502,7 +866,7 @@ pub trait Instance {
     /// Sets the frequency of the I2C interface by calculating and applying the
     /// associated timings - corresponds to i2c_ll_cal_bus_clk and
     /// i2c_ll_set_bus_timing in ESP-IDF
-    fn set_frequency(&mut self, source_clk: HertzU32, bus_freq: HertzU32) {
+    fn set_frequency(&mut self, source_clk: HertzU32, bus_freq: HertzU32, timeout: Option<u32>) {
         let source_clk = source_clk.raw();
         let bus_freq = bus_freq.raw();
 
@@ -517,8 +881,7 @@ pub trait Instance {
         let sda_sample = half_cycle / 2 - 1;
         let setup = half_cycle;
         let hold = half_cycle;
-        // default we set the timeout value to 10 bus cycles
-        let tout = half_cycle * 20;
+        let tout = timeout_value(timeout, half_cycle);
 
         // scl period
         let scl_low_period = scl_low - 1;
@@ -556,7 +919,7 @@ pub trait Instance {
     /// Sets the frequency of the I2C interface by calculating and applying the
     /// associated timings - corresponds to i2c_ll_cal_bus_clk and
     /// i2c_ll_set_bus_timing in ESP-IDF
-    fn set_frequency(&mut self, source_clk: HertzU32, bus_freq: HertzU32) {
+    fn set_frequency(&mut self, source_clk: HertzU32, bus_freq: HertzU32, timeout: Option<u32>) {
         let source_clk = source_clk.raw();
         let bus_freq = bus_freq.raw();
 
@@ -580,9 +943,7 @@ pub trait Instance {
         let sda_sample = half_cycle / 2 + scl_wait_high;
         let setup = half_cycle;
         let hold = half_cycle;
-        // default we set the timeout value to about 10 bus cycles
-        // log(20*half_cycle)/log(2) = log(half_cycle)/log(2) +  log(20)/log(2)
-        let tout = (4 * 8 - (5 * half_cycle).leading_zeros()) + 2;
+        let tout = timeout_value(timeout, half_cycle);
 
         // According to the Technical Reference Manual, the following timings must be
         // subtracted by 1. However, according to the practical measurement and
@@ -704,30 +1065,59 @@ pub trait Instance {
             self.register_block()
                 .scl_stop_hold
                 .write(|w| w.time().bits(scl_stop_hold_time as u16));
+        }
 
-            // The ESP32 variant does not have an enable flag for the
-            // timeout mechanism
-            cfg_if::cfg_if! {
-                if #[cfg(esp32)] {
-                    // timeout
-                    self.register_block()
-                        .to
-                        .write(|w| w.time_out().bits(time_out_value));
-                }
-                else {
-                    // timeout
-                    // FIXME: Enable timout for other chips!
-                    self.register_block()
-                        .to
-                        .write(|w| w.time_out_en().bit(time_out_en)
-                        .time_out_value()
-                        .variant(time_out_value.try_into().unwrap())
-                    );
-                }
+        self.configure_timeout(time_out_value, time_out_en);
+    }
+
+    fn configure_timeout(&mut self, time_out_value: u32, time_out_en: bool) {
+        // The ESP32 variant does not have an enable flag for the
+        // timeout mechanism
+        cfg_if::cfg_if! {
+            if #[cfg(esp32)] {
+                let _ = time_out_en;
+                self.register_block()
+                    .to
+                    .write(|w| unsafe { w.time_out().bits(time_out_value) });
+            }
+            else {
+                self.register_block()
+                    .to
+                    .write(|w| w.time_out_en().bit(time_out_en)
+                    .time_out_value()
+                    .variant(time_out_value.try_into().unwrap())
+                );
             }
         }
     }
 
+    /// Changes the timeout of an already configured interface, in bus clock
+    /// cycles - see [`I2C::set_timeout`]
+    fn set_timeout(&mut self, timeout: Option<u32>) {
+        // every timing is derived from half a bus clock cycle in source clock
+        // cycles, and the SCL low period is programmed to exactly that minus one
+        let half_cycle = self
+            .register_block()
+            .scl_low_period
+            .read()
+            .scl_low_period()
+            .bits() as u32
+            + 1;
+
+        self.configure_timeout(timeout_value(timeout, half_cycle), true);
+
+        #[cfg(any(esp32c2, esp32c3, esp32c6, esp32s3))]
+        self.register_block()
+            .ctr
+            .modify(|_, w| w.conf_upgate().set_bit());
+    }
+
+    /// Whether a transfer is in progress on the bus, i.e. a START condition
+    /// was seen without a matching STOP condition
+    fn is_bus_busy(&self) -> bool {
+        self.register_block().sr.read().bus_busy().bit_is_set()
+    }
+
     fn perform_write<'a, I>(
         &self,
         addr: u8,
@@ -841,7 +1231,110 @@ pub trait Instance {
 
         self.read_all_from_fifo(buffer)?;
 
-        self.wait_for_completion()?;
+        // all data has been received when the STOP times out
+        self.wait_for_completion()
+            .map_err(|err| with_received(err, buffer.len()))?;
+
+        Ok(())
+    }
+
+    /// Write `bytes` and read into `buffer` in a single transaction
+    ///
+    /// The command list is START, WRITE(address + bytes), repeated START,
+    /// WRITE(address), READ(n), STOP, so there is no STOP condition between
+    /// both phases. Some devices reset their register pointer on STOP.
+    fn perform_write_read<'a, I>(
+        &self,
+        addr: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+        cmd_iterator: &mut I,
+    ) -> Result<(), Error>
+    where
+        I: Iterator<Item = &'a COMD>,
+    {
+        if bytes.len() > 254 || buffer.len() > 254 {
+            // we could support more by adding multiple write/read operations
+            return Err(Error::ExceedingFifo);
+        }
+
+        // Clear all I2C interrupts
+        self.clear_all_interrupts();
+
+        // RSTART command
+        add_cmd(cmd_iterator, Command::Start)?;
+
+        // WRITE command
+        add_cmd(
+            cmd_iterator,
+            Command::Write {
+                ack_exp: Ack::Ack,
+                ack_check_en: true,
+                length: 1 + bytes.len() as u8,
+            },
+        )?;
+
+        // repeated RSTART command
+        add_cmd(cmd_iterator, Command::Start)?;
+
+        // WRITE command (address for the read)
+        add_cmd(
+            cmd_iterator,
+            Command::Write {
+                ack_exp: Ack::Ack,
+                ack_check_en: true,
+                length: 1,
+            },
+        )?;
+
+        if buffer.len() > 1 {
+            // READ command (N - 1)
+            add_cmd(
+                cmd_iterator,
+                Command::Read {
+                    ack_value: Ack::Ack,
+                    length: buffer.len() as u8 - 1,
+                },
+            )?;
+        }
+
+        // READ w/o ACK
+        add_cmd(
+            cmd_iterator,
+            Command::Read {
+                ack_value: Ack::Nack,
+                length: 1,
+            },
+        )?;
+
+        add_cmd(cmd_iterator, Command::Stop)?;
+
+        self.update_config();
+
+        // Load address and R/W bit into FIFO
+        write_fifo(
+            self.register_block(),
+            addr << 1 | OperationType::Write as u8,
+        );
+
+        // the address of the read phase is sent right after the written bytes
+        let mut tx = [0u8; 255];
+        tx[..bytes.len()].copy_from_slice(bytes);
+        tx[bytes.len()] = addr << 1 | OperationType::Read as u8;
+        let tx = &tx[..bytes.len() + 1];
+
+        let index = self.fill_tx_fifo(tx);
+
+        self.start_transmission();
+
+        // fill FIFO with remaining bytes
+        self.write_remaining_tx_fifo(index, tx)?;
+
+        self.read_all_from_fifo(buffer)?;
+
+        // all data has been received when the STOP times out
+        self.wait_for_completion()
+            .map_err(|err| with_received(err, buffer.len()))?;
 
         Ok(())
     }
@@ -851,14 +1344,16 @@ pub trait Instance {
         // Read bytes from FIFO
         // FIXME: Handle case where less data has been provided by the slave than
         // requested? Or is this prevented from a protocol perspective?
-        for byte in buffer.iter_mut() {
+        for (received, byte) in buffer.iter_mut().enumerate() {
             loop {
-                self.check_errors()?;
-
+                // bytes already in the FIFO are still taken after an error
                 let reg = self.register_block().fifo_st.read();
                 if reg.rxfifo_raddr().bits() != reg.rxfifo_waddr().bits() {
                     break;
                 }
+
+                self.check_errors()
+                    .map_err(|err| with_received(err, received))?;
             }
 
             *byte = read_fifo(self.register_block());
@@ -931,7 +1426,7 @@ pub trait Instance {
                 // Handle error cases
                 if interrupts.time_out_int_raw().bit_is_set() {
                     self.reset();
-                    return Err(Error::TimeOut);
+                    return Err(Error::TimeOut(0));
                 } else if interrupts.ack_err_int_raw().bit_is_set() {
                     self.reset();
                     return Err(Error::AckCheckFailed);
@@ -944,7 +1439,7 @@ pub trait Instance {
                 // Handle error cases
                 if interrupts.time_out_int_raw().bit_is_set() {
                     self.reset();
-                    return Err(Error::TimeOut);
+                    return Err(Error::TimeOut(0));
                 } else if interrupts.nack_int_raw().bit_is_set() {
                     self.reset();
                     return Err(Error::AckCheckFailed);
@@ -1015,7 +1510,9 @@ pub trait Instance {
                 .read()
                 .txfifo_wm_int_raw()
                 .bit_is_set()
-            {}
+            {
+                self.check_errors()?;
+            }
 
             self.register_block()
                 .int_clr
@@ -1027,7 +1524,9 @@ pub trait Instance {
                 .read()
                 .txfifo_wm_int_raw()
                 .bit_is_set()
-            {}
+            {
+                self.check_errors()?;
+            }
 
             if index >= bytes.len() {
                 break Ok(());
@@ -1156,23 +1655,70 @@ pub trait Instance {
     }
 
     /// Write bytes from the `bytes` array first and then read n bytes into
-    /// the `buffer` array with n being the size of the array.
+    /// the `buffer` array with n being the size of the array. Both phases are
+    /// separated by a repeated START, not by a STOP.
     fn master_write_read(
         &mut self,
         addr: u8,
         bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Error> {
-        // it would be possible to combine the write and read
-        // in one transaction but filling the tx fifo with
-        // the current code is somewhat slow even in release mode
-        // which can cause issues
-        self.master_write(addr, bytes)?;
-        self.master_read(addr, buffer)?;
+        // Reset FIFO and command list
+        self.reset_fifo();
+        self.reset_command_list();
+        self.perform_write_read(addr, bytes, buffer, &mut self.register_block().comd.iter())?;
         Ok(())
     }
 }
 
+/// Sets the number of bytes received reported by [`Error::TimeOut`]
+fn with_received(err: Error, received: usize) -> Error {
+    match err {
+        Error::TimeOut(_) => Error::TimeOut(received),
+        err => err,
+    }
+}
+
+/// Converts a timeout in bus clock cycles into the value of the timeout
+/// register, given half a bus clock cycle in source clock cycles. `None`
+/// selects the longest timeout.
+#[cfg(esp32)]
+fn timeout_value(timeout: Option<u32>, half_cycle: u32) -> u32 {
+    // the timeout is given in APB clock cycles, 20 bits at most
+    timeout
+        .map(|to_bus| to_bus.saturating_mul(2 * half_cycle))
+        .unwrap_or(u32::MAX)
+        .min(0xf_ffff)
+}
+
+/// Converts a timeout in bus clock cycles into the value of the timeout
+/// register, given half a bus clock cycle in source clock cycles. `None`
+/// selects the longest timeout.
+#[cfg(esp32s2)]
+fn timeout_value(timeout: Option<u32>, half_cycle: u32) -> u32 {
+    // the timeout is given in APB clock cycles, 24 bits at most
+    timeout
+        .map(|to_bus| to_bus.saturating_mul(2 * half_cycle))
+        .unwrap_or(u32::MAX)
+        .min(0xff_ffff)
+}
+
+/// Converts a timeout in bus clock cycles into the value of the timeout
+/// register, given half a bus clock cycle in source clock cycles. `None`
+/// selects the longest timeout.
+#[cfg(any(esp32c2, esp32c3, esp32c6, esp32s3))]
+fn timeout_value(timeout: Option<u32>, half_cycle: u32) -> u32 {
+    // the timeout is given as the exponent of a power of two of sclk cycles,
+    // 22 at most, so round up to the next power of two
+    timeout
+        .map(|to_bus| {
+            let to_sclk = to_bus.saturating_mul(2 * half_cycle).max(1);
+            u32::BITS - (to_sclk - 1).leading_zeros()
+        })
+        .unwrap_or(u32::MAX)
+        .min(22)
+}
+
 fn add_cmd<'a, I>(cmd_iterator: &mut I, command: Command) -> Result<(), Error>
 where
     I: Iterator<Item = &'a COMD>,
@@ -0,0 +1,112 @@
+//! Awaiting arbitrary peripheral interrupts
+//!
+//! [`InterruptFuture`] completes when a peripheral interrupt fires, which
+//! allows writing async wrappers for peripherals this crate doesn't have async
+//! drivers for.
+//!
+//! Interrupts without a handler defined with `#[interrupt]` end up in the
+//! default handler, which wakes the future awaiting the interrupt and disables
+//! the interrupt again. The peripheral's own interrupt status is not touched:
+//! it usually stays asserted until cleared, so the wrapper has to clear it
+//! before awaiting the next interrupt, otherwise the future completes right
+//! away.
+//!
+//! ```rust,ignore
+//! // enable the interrupt in the peripheral, then
+//! InterruptFuture::new(Interrupt::RMT).await;
+//! // clear the interrupt in the peripheral
+//! ```
+
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll},
+};
+
+use embassy_sync::waitqueue::AtomicWaker;
+
+use super::Priority;
+use crate::peripherals::Interrupt;
+
+// Larger than the number of peripheral interrupts on any of the chips
+const INTERRUPT_COUNT: usize = 128;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_AW: AtomicWaker = AtomicWaker::new();
+static WAKERS: [AtomicWaker; INTERRUPT_COUNT] = [NEW_AW; INTERRUPT_COUNT];
+
+#[allow(clippy::declare_interior_mutable_const)]
+const NEW_FLAG: AtomicBool = AtomicBool::new(false);
+static FIRED: [AtomicBool; INTERRUPT_COUNT] = [NEW_FLAG; INTERRUPT_COUNT];
+
+/// Future completing when a peripheral interrupt fires
+///
+/// The interrupt is enabled when the future is first polled and disabled
+/// when it fires or the future is dropped. The interrupt must not have a
+/// handler defined with `#[interrupt]`, as the handler would be called instead
+/// of waking the future. Only one future per interrupt may exist at a time.
+pub struct InterruptFuture {
+    interrupt: Interrupt,
+    priority: Priority,
+    enabled: bool,
+}
+
+impl InterruptFuture {
+    /// Wait for `interrupt` at the lowest priority
+    pub fn new(interrupt: Interrupt) -> Self {
+        Self::new_with_priority(interrupt, Priority::min())
+    }
+
+    /// Wait for `interrupt` at the given priority
+    pub fn new_with_priority(interrupt: Interrupt, priority: Priority) -> Self {
+        Self {
+            interrupt,
+            priority,
+            enabled: false,
+        }
+    }
+}
+
+impl Future for InterruptFuture {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let n = self.interrupt as usize;
+
+        WAKERS[n].register(cx.waker());
+
+        if !self.enabled {
+            FIRED[n].store(false, Ordering::Release);
+            super::enable(self.interrupt, self.priority).unwrap();
+            self.enabled = true;
+        }
+
+        if FIRED[n].load(Ordering::Acquire) {
+            self.enabled = false;
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for InterruptFuture {
+    fn drop(&mut self) {
+        if self.enabled {
+            super::disable(crate::get_core(), self.interrupt);
+        }
+    }
+}
+
+/// Called by the default interrupt handler for interrupts without handler
+pub(crate) fn on_interrupt(interrupt: Interrupt) {
+    let n = interrupt as usize;
+
+    // the peripheral keeps the interrupt asserted until its cause is cleared,
+    // so disable it to not end up here again right away
+    super::disable(crate::get_core(), interrupt);
+
+    FIRED[n].store(true, Ordering::Release);
+    WAKERS[n].wake();
+}
@@ -1,3 +1,43 @@
+//! Interrupt handling
+//!
+//! With the `vectored` feature, a peripheral interrupt is either handled by a
+//! function declared with `#[interrupt]`, or by a handler registered at
+//! runtime with [`set_handler`]. The latter doesn't need a symbol of a fixed
+//! name, so it can also be used by libraries:
+//!
+//! ```rust,ignore
+//! fn on_gpio() {
+//!     // ...
+//! }
+//!
+//! interrupt::set_handler(Interrupt::GPIO, on_gpio, Priority::Priority1).unwrap();
+//! // ...
+//! interrupt::free(Interrupt::GPIO);
+//! ```
+//!
+//! A registered handler takes precedence over an `#[interrupt]` function for
+//! the same interrupt.
+//!
+//! The priority models of the two architectures differ:
+//!
+//! - on the RISC-V chips, every CPU interrupt can be given any of the 15
+//!   priority levels with `set_priority`
+//! - on the Xtensa chips, the level of every CPU interrupt is fixed, as spelled
+//!   out in the names of `CpuInterrupt`, and only the levels 1 to 3 are handled
+//!   in Rust. `set_priority` only checks the level there
+//!
+//! `set_priority` has the same signature on both, it returns
+//! `Error::InvalidInterruptPriority` for a priority the CPU interrupt can't be
+//! given.
+//!
+//! With the `vectored` feature, [`enable`] hides the difference by mapping the
+//! peripheral interrupt to a CPU interrupt of the requested priority.
+
+#[cfg(feature = "vectored")]
+use core::cell::Cell;
+
+#[cfg(feature = "vectored")]
+use critical_section::Mutex;
 #[cfg(riscv)]
 pub use riscv::*;
 #[cfg(xtensa)]
@@ -7,3 +47,53 @@ pub use xtensa::*;
 mod riscv;
 #[cfg(xtensa)]
 mod xtensa;
+
+#[cfg(all(feature = "async", feature = "vectored"))]
+pub use asynch::InterruptFuture;
+
+#[cfg(all(feature = "async", feature = "vectored"))]
+pub(crate) mod asynch;
+
+// Enough for the interrupt sources of every chip, the same as the width of the
+// interrupt status
+#[cfg(feature = "vectored")]
+const MAX_INTERRUPTS: usize = 128;
+
+#[cfg(feature = "vectored")]
+const NO_HANDLER: Cell<Option<fn()>> = Cell::new(None);
+
+#[cfg(feature = "vectored")]
+static HANDLERS: Mutex<[Cell<Option<fn()>>; MAX_INTERRUPTS]> =
+    Mutex::new([NO_HANDLER; MAX_INTERRUPTS]);
+
+/// Register `handler` for `interrupt` and enable it at `priority`
+///
+/// The interrupt is enabled on the current core. A handler registered before
+/// for the same interrupt is replaced.
+#[cfg(feature = "vectored")]
+pub fn set_handler(
+    interrupt: crate::peripherals::Interrupt,
+    handler: fn(),
+    priority: Priority,
+) -> Result<(), Error> {
+    critical_section::with(|cs| HANDLERS.borrow(cs)[interrupt as usize].set(Some(handler)));
+
+    enable(interrupt, priority).map_err(|err| {
+        critical_section::with(|cs| HANDLERS.borrow(cs)[interrupt as usize].set(None));
+        err
+    })
+}
+
+/// Disable `interrupt` on the current core and remove its registered handler
+#[cfg(feature = "vectored")]
+pub fn free(interrupt: crate::peripherals::Interrupt) {
+    disable(crate::get_core(), interrupt);
+    critical_section::with(|cs| HANDLERS.borrow(cs)[interrupt as usize].set(None));
+}
+
+// The handler registered for `interrupt`, if any
+#[cfg(feature = "vectored")]
+#[inline(always)]
+fn registered_handler(interrupt: crate::peripherals::Interrupt) -> Option<fn()> {
+    critical_section::with(|cs| HANDLERS.borrow(cs)[interrupt as usize].get())
+}
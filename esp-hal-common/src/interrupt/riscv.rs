@@ -105,6 +105,10 @@ pub enum CpuInterrupt {
 }
 
 /// Interrupt priority levels.
+///
+/// Every RISC-V chip implements the levels 1 to 15, so any value of this enum
+/// is valid on any of them. `None` disables the CPU interrupt.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Priority {
     None,
@@ -210,13 +214,31 @@ pub fn set_kind(_core: Cpu, which: CpuInterrupt, kind: InterruptKind) {
     }
 }
 
+/// Interrupt Error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidInterruptPriority,
+}
+
 /// Set the priority level of an CPU interrupt
 ///
+/// Unlike on the Xtensa chips, where the level of each CPU interrupt is fixed,
+/// any CPU interrupt can be given any of the levels of [`Priority`].
+///
 /// Great care must be taken when using the `vectored` feature (enabled by
-/// default). Avoid changing the priority of interrupts 1 - 15 when interrupt
-/// vectoring is enabled.
+/// default). Interrupt vectoring assigns one CPU interrupt to each priority,
+/// and the dispatcher relies on that CPU interrupt having this priority:
+/// changing the priority of the CPU interrupts 1 - 15 (on the ESP32-C6: 1, 2,
+/// 5, 6 and 9 - 19) is rejected with [`Error::InvalidInterruptPriority`].
 #[cfg(not(plic))]
-pub unsafe fn set_priority(_core: Cpu, which: CpuInterrupt, priority: Priority) {
+pub unsafe fn set_priority(
+    _core: Cpu,
+    which: CpuInterrupt,
+    priority: Priority,
+) -> Result<(), Error> {
+    #[cfg(feature = "vectored")]
+    vectored::check_priority(which, priority)?;
+
     let intr = &*crate::peripherals::INTERRUPT_CORE0::PTR;
     let cpu_interrupt_number = which as isize;
     let intr_prio_base = intr.cpu_int_pri_0.as_ptr();
@@ -224,15 +246,29 @@ pub unsafe fn set_priority(_core: Cpu, which: CpuInterrupt, priority: Priority)
     intr_prio_base
         .offset(cpu_interrupt_number)
         .write_volatile(priority as u32);
+
+    Ok(())
 }
 
 /// Set the priority level of an CPU interrupt
 ///
+/// Unlike on the Xtensa chips, where the level of each CPU interrupt is fixed,
+/// any CPU interrupt can be given any of the levels of [`Priority`].
+///
 /// Great care must be taken when using the `vectored` feature (enabled by
-/// default). Avoid changing the priority of interrupts 1 - 15 when interrupt
-/// vectoring is enabled.
+/// default). Interrupt vectoring assigns one CPU interrupt to each priority,
+/// and the dispatcher relies on that CPU interrupt having this priority:
+/// changing the priority of the CPU interrupts 1 - 15 (on the ESP32-C6: 1, 2,
+/// 5, 6 and 9 - 19) is rejected with [`Error::InvalidInterruptPriority`].
 #[cfg(plic)]
-pub unsafe fn set_priority(_core: Cpu, which: CpuInterrupt, priority: Priority) {
+pub unsafe fn set_priority(
+    _core: Cpu,
+    which: CpuInterrupt,
+    priority: Priority,
+) -> Result<(), Error> {
+    #[cfg(feature = "vectored")]
+    vectored::check_priority(which, priority)?;
+
     const DR_REG_PLIC_MX_BASE: u32 = 0x20001000;
     const PLIC_MXINT0_PRI_REG: u32 = DR_REG_PLIC_MX_BASE + 0x10;
 
@@ -242,6 +278,8 @@ pub unsafe fn set_priority(_core: Cpu, which: CpuInterrupt, priority: Priority)
     plic_mxint_pri_ptr
         .offset(cpu_interrupt_number)
         .write_volatile(priority as u32);
+
+    Ok(())
 }
 
 /// Clear a CPU interrupt
@@ -258,6 +296,145 @@ pub fn clear(_core: Cpu, which: CpuInterrupt) {
     }
 }
 
+/// Mask all interrupts with a priority below `priority`
+///
+/// Interrupts of `priority` and above are still serviced, the masked ones stay
+/// pending until the threshold is lowered again. Unlike a critical section,
+/// this allows e.g. a high priority timer interrupt through while the code
+/// is protected from lower priority handlers. Use [`PriorityGuard`] to restore
+/// the previous threshold automatically.
+///
+/// The threshold applies to the current core, [`Priority::min`] unmasks all
+/// interrupts again.
+pub fn set_threshold(_core: Cpu, priority: Priority) {
+    write_threshold(priority as u32);
+}
+
+#[cfg(not(plic))]
+#[inline]
+fn read_threshold() -> u32 {
+    let intr = unsafe { &*crate::peripherals::INTERRUPT_CORE0::PTR };
+    intr.cpu_int_thresh.read().bits()
+}
+
+#[cfg(not(plic))]
+#[inline]
+fn write_threshold(threshold: u32) {
+    let intr = unsafe { &*crate::peripherals::INTERRUPT_CORE0::PTR };
+    intr.cpu_int_thresh.write(|w| unsafe { w.bits(threshold) });
+}
+
+#[cfg(plic)]
+const PLIC_MXINT_THRESH_REG: u32 = 0x20001000 + 0x90;
+
+#[cfg(plic)]
+#[inline]
+fn read_threshold() -> u32 {
+    unsafe { (PLIC_MXINT_THRESH_REG as *const u32).read_volatile() }
+}
+
+#[cfg(plic)]
+#[inline]
+fn write_threshold(threshold: u32) {
+    unsafe { (PLIC_MXINT_THRESH_REG as *mut u32).write_volatile(threshold) }
+}
+
+/// Masks interrupts below a priority until dropped
+///
+/// See [`set_threshold`]. The threshold is only ever raised, so guards can be
+/// nested, and the previous threshold is restored when the guard is dropped.
+///
+/// ```rust,ignore
+/// {
+///     let _guard = interrupt::PriorityGuard::new(Cpu::ProCpu, Priority::Priority3);
+///     // only interrupts of priority 3 and above are serviced here
+/// }
+/// ```
+#[must_use = "the previous threshold is restored as soon as the guard is dropped"]
+pub struct PriorityGuard {
+    previous: u32,
+}
+
+impl PriorityGuard {
+    /// Mask the interrupts below `priority` on the current core
+    pub fn new(_core: Cpu, priority: Priority) -> Self {
+        let previous = read_threshold();
+        write_threshold(previous.max(priority as u32));
+
+        Self { previous }
+    }
+}
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        write_threshold(self.previous);
+    }
+}
+
+/// Get the priority level of a CPU interrupt, see [`set_priority`]
+#[cfg(not(plic))]
+#[inline]
+pub fn priority(which: CpuInterrupt) -> Priority {
+    unsafe {
+        let intr = &*crate::peripherals::INTERRUPT_CORE0::PTR;
+        let intr_prio_base = intr.cpu_int_pri_0.as_ptr();
+
+        let prio = intr_prio_base.offset(which as isize).read_volatile();
+        core::mem::transmute(prio as u8)
+    }
+}
+
+/// Get the priority level of a CPU interrupt, see [`set_priority`]
+#[cfg(plic)]
+#[inline]
+pub fn priority(which: CpuInterrupt) -> Priority {
+    const DR_REG_PLIC_MX_BASE: u32 = 0x20001000;
+    const PLIC_MXINT0_PRI_REG: u32 = DR_REG_PLIC_MX_BASE + 0x10;
+
+    unsafe {
+        let plic_mxint_pri_ptr = PLIC_MXINT0_PRI_REG as *mut u32;
+
+        let prio = plic_mxint_pri_ptr.offset(which as isize).read_volatile();
+        core::mem::transmute(prio as u8)
+    }
+}
+
+/// Check if a CPU interrupt is enabled, see [`enable_cpu_interrupt`]
+#[cfg(not(esp32c6))]
+#[inline]
+pub fn is_enabled(which: CpuInterrupt) -> bool {
+    let intr = unsafe { &*crate::peripherals::INTERRUPT_CORE0::PTR };
+
+    intr.cpu_int_enable.read().bits() & (1 << which as u32) != 0
+}
+
+/// Check if a CPU interrupt is enabled, see [`enable_cpu_interrupt`]
+#[cfg(esp32c6)]
+#[inline]
+pub fn is_enabled(which: CpuInterrupt) -> bool {
+    const DR_REG_PLIC_MX_BASE: u32 = 0x20001000;
+    const PLIC_MXINT_ENABLE_REG: u32 = DR_REG_PLIC_MX_BASE + 0x0;
+
+    let mxint_enable = PLIC_MXINT_ENABLE_REG as *const u32;
+    unsafe { mxint_enable.read_volatile() & (1 << which as u32) != 0 }
+}
+
+/// Check if a CPU interrupt is pending
+///
+/// A CPU interrupt is pending while any peripheral interrupt mapped to it is
+/// raised (or, for edge interrupts, until it is cleared with [`clear`]),
+/// independent of whether it is enabled or masked by the current priority
+/// threshold.
+#[inline]
+pub fn is_pending(which: CpuInterrupt) -> bool {
+    #[cfg(not(esp32c6))]
+    let intr = unsafe { &*crate::peripherals::INTERRUPT_CORE0::PTR };
+    #[cfg(esp32c6)]
+    let intr = unsafe { &*crate::peripherals::INTPRI::PTR };
+
+    intr.cpu_int_eip_status.read().bits() & (1 << which as u32) != 0
+}
+
 /// Get status of peripheral interrupts
 #[cfg(not(esp32c6))]
 #[inline]
@@ -297,6 +474,114 @@ pub fn get_status(_core: Cpu) -> u128 {
     }
 }
 
+/// Interrupts raised by software
+///
+/// Each one is a peripheral interrupt of its own (`FROM_CPU_INTR0` ..
+/// `FROM_CPU_INTR3`), enabled and handled like any other. This makes them
+/// useful to defer work from a high priority interrupt to a lower priority
+/// one, or to run an interrupt-mode executor: the executor pends its software
+/// interrupt when a task is woken and polls the tasks in the handler.
+#[cfg(esp32c3)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SoftwareInterrupt {
+    SoftwareInterrupt0,
+    SoftwareInterrupt1,
+    SoftwareInterrupt2,
+    SoftwareInterrupt3,
+}
+
+#[cfg(esp32c3)]
+impl SoftwareInterrupt {
+    /// The peripheral interrupt raised by this software interrupt
+    pub fn interrupt(&self) -> Interrupt {
+        match self {
+            SoftwareInterrupt::SoftwareInterrupt0 => Interrupt::FROM_CPU_INTR0,
+            SoftwareInterrupt::SoftwareInterrupt1 => Interrupt::FROM_CPU_INTR1,
+            SoftwareInterrupt::SoftwareInterrupt2 => Interrupt::FROM_CPU_INTR2,
+            SoftwareInterrupt::SoftwareInterrupt3 => Interrupt::FROM_CPU_INTR3,
+        }
+    }
+}
+
+/// Raise a software interrupt
+///
+/// The interrupt is level triggered and stays pending until [`unpend`] is
+/// called, usually first thing in its handler. Pending it again while it is
+/// still pending has no further effect, the handler runs once for both.
+#[cfg(esp32c3)]
+pub fn pend(interrupt: SoftwareInterrupt) {
+    let system = unsafe { &*crate::peripherals::SYSTEM::PTR };
+
+    match interrupt {
+        SoftwareInterrupt::SoftwareInterrupt0 => system
+            .cpu_intr_from_cpu_0
+            .write(|w| w.cpu_intr_from_cpu_0().set_bit()),
+        SoftwareInterrupt::SoftwareInterrupt1 => system
+            .cpu_intr_from_cpu_1
+            .write(|w| w.cpu_intr_from_cpu_1().set_bit()),
+        SoftwareInterrupt::SoftwareInterrupt2 => system
+            .cpu_intr_from_cpu_2
+            .write(|w| w.cpu_intr_from_cpu_2().set_bit()),
+        SoftwareInterrupt::SoftwareInterrupt3 => system
+            .cpu_intr_from_cpu_3
+            .write(|w| w.cpu_intr_from_cpu_3().set_bit()),
+    }
+}
+
+/// Clear a software interrupt raised with [`pend`]
+///
+/// A [`pend`] after this raises the interrupt again, even when it happens
+/// while the handler is still running, so no request is lost as long as the
+/// handler calls this before doing its work.
+#[cfg(esp32c3)]
+pub fn unpend(interrupt: SoftwareInterrupt) {
+    let system = unsafe { &*crate::peripherals::SYSTEM::PTR };
+
+    match interrupt {
+        SoftwareInterrupt::SoftwareInterrupt0 => system
+            .cpu_intr_from_cpu_0
+            .write(|w| w.cpu_intr_from_cpu_0().clear_bit()),
+        SoftwareInterrupt::SoftwareInterrupt1 => system
+            .cpu_intr_from_cpu_1
+            .write(|w| w.cpu_intr_from_cpu_1().clear_bit()),
+        SoftwareInterrupt::SoftwareInterrupt2 => system
+            .cpu_intr_from_cpu_2
+            .write(|w| w.cpu_intr_from_cpu_2().clear_bit()),
+        SoftwareInterrupt::SoftwareInterrupt3 => system
+            .cpu_intr_from_cpu_3
+            .write(|w| w.cpu_intr_from_cpu_3().clear_bit()),
+    }
+}
+
+/// Check if a software interrupt is pending
+#[cfg(esp32c3)]
+pub fn is_software_interrupt_pending(interrupt: SoftwareInterrupt) -> bool {
+    let system = unsafe { &*crate::peripherals::SYSTEM::PTR };
+
+    match interrupt {
+        SoftwareInterrupt::SoftwareInterrupt0 => system
+            .cpu_intr_from_cpu_0
+            .read()
+            .cpu_intr_from_cpu_0()
+            .bit_is_set(),
+        SoftwareInterrupt::SoftwareInterrupt1 => system
+            .cpu_intr_from_cpu_1
+            .read()
+            .cpu_intr_from_cpu_1()
+            .bit_is_set(),
+        SoftwareInterrupt::SoftwareInterrupt2 => system
+            .cpu_intr_from_cpu_2
+            .read()
+            .cpu_intr_from_cpu_2()
+            .bit_is_set(),
+        SoftwareInterrupt::SoftwareInterrupt3 => system
+            .cpu_intr_from_cpu_3
+            .read()
+            .cpu_intr_from_cpu_3()
+            .bit_is_set(),
+    }
+}
+
 #[cfg(feature = "vectored")]
 pub use vectored::*;
 
@@ -324,6 +609,18 @@ mod vectored {
         1, 2, 0, 0, 3, 4, 0, 0, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
     ];
 
+    // Reject changing the priority of a CPU interrupt used for vectoring, the
+    // dispatcher relies on it
+    pub(super) fn check_priority(which: CpuInterrupt, priority: Priority) -> Result<(), Error> {
+        match PRIORITY_TO_INTERRUPT
+            .iter()
+            .position(|num| *num == which as usize)
+        {
+            Some(prio) if prio + 1 != priority as usize => Err(Error::InvalidInterruptPriority),
+            _ => Ok(()),
+        }
+    }
+
     // Setup interrupts ready for vectoring
     #[doc(hidden)]
     pub(crate) unsafe fn init_vectoring() {
@@ -337,7 +634,8 @@ mod vectored {
                 crate::get_core(),
                 core::mem::transmute(*num as u32),
                 core::mem::transmute((prio as u8) + 1),
-            );
+            )
+            .unwrap();
             enable_cpu_interrupt(core::mem::transmute(*num as u32));
         }
     }
@@ -361,7 +659,7 @@ mod vectored {
                 let cpu_interrupt = intr_map_base.offset(i).read_volatile();
                 // safety: cast is safe because of repr(u32)
                 let cpu_interrupt: CpuInterrupt = core::mem::transmute(cpu_interrupt);
-                let prio = get_priority(cpu_interrupt);
+                let prio = priority(cpu_interrupt);
 
                 prios[prio as usize] |= 1 << i;
                 status &= !(1u128 << interrupt_nr);
@@ -371,41 +669,6 @@ mod vectored {
         }
     }
 
-    /// Get interrupt priority
-    #[cfg(not(plic))]
-    #[inline]
-    unsafe fn get_priority(cpu_interrupt: CpuInterrupt) -> Priority {
-        let intr = &*crate::peripherals::INTERRUPT_CORE0::PTR;
-        let intr_prio_base = intr.cpu_int_pri_0.as_ptr();
-
-        let prio = intr_prio_base
-            .offset(cpu_interrupt as isize)
-            .read_volatile();
-        core::mem::transmute(prio as u8)
-    }
-
-    /// Get interrupt priority
-    #[cfg(plic)]
-    #[inline]
-    unsafe fn get_priority(cpu_interrupt: CpuInterrupt) -> Priority {
-        const DR_REG_PLIC_MX_BASE: u32 = 0x20001000;
-        const PLIC_MXINT0_PRI_REG: u32 = DR_REG_PLIC_MX_BASE + 0x10;
-
-        let plic_mxint_pri_ptr = PLIC_MXINT0_PRI_REG as *mut u32;
-
-        let cpu_interrupt_number = cpu_interrupt as isize;
-        let prio = plic_mxint_pri_ptr
-            .offset(cpu_interrupt_number)
-            .read_volatile();
-        core::mem::transmute(prio as u8)
-    }
-
-    /// Interrupt Error
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-    pub enum Error {
-        InvalidInterruptPriority,
-    }
-
     /// Enables a interrupt at a given priority
     ///
     /// Note that interrupts still need to be enabled globally for interrupts
@@ -451,6 +714,13 @@ mod vectored {
             // defined in each hal
             fn EspDefaultHandler(interrupt: Interrupt);
         }
+        // called outside of the critical section, so higher priority interrupts
+        // can still preempt it
+        if let Some(handler) = super::super::registered_handler(interrupt) {
+            handler();
+            return;
+        }
+
         let handler = peripherals::__EXTERNAL_INTERRUPTS[interrupt as usize]._handler;
         if handler as *const _ == EspDefaultHandler as *const unsafe extern "C" fn() {
             EspDefaultHandler(interrupt);
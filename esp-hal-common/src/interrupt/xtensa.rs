@@ -97,6 +97,22 @@ pub fn clear(_core: Cpu, which: CpuInterrupt) {
     }
 }
 
+/// Check if a CPU interrupt is enabled on the current core
+///
+/// Reads the `INTENABLE` special register, which is private to each core.
+pub fn is_enabled(which: CpuInterrupt) -> bool {
+    xtensa_lx::interrupt::get_mask() & (1 << which as u32) != 0
+}
+
+/// Check if a CPU interrupt is pending on the current core
+///
+/// Reads the `INTERRUPT` special register, which is private to each core. A
+/// pending interrupt is reported independent of whether it is enabled or
+/// masked by the current interrupt level.
+pub fn is_pending(which: CpuInterrupt) -> bool {
+    xtensa_lx::interrupt::get() & (1 << which as u32) != 0
+}
+
 /// Get status of peripheral interrupts
 pub fn get_status(core: Cpu) -> u128 {
     unsafe {
@@ -177,6 +193,117 @@ unsafe fn core1_interrupt_peripheral() -> *const crate::peripherals::interrupt_c
     crate::peripherals::INTERRUPT_CORE1::PTR
 }
 
+/// Interrupt Error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidInterrupt,
+    /// The priority isn't the fixed level of the CPU interrupt, see
+    /// [`set_priority`]
+    InvalidInterruptPriority,
+}
+
+/// Interrupt priority levels.
+///
+/// Only the levels 1 to 3 can be handled in Rust, the higher levels of
+/// the Xtensa cores are reserved for e.g. the debugger and the NMI.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Priority {
+    None = 0,
+    Priority1,
+    Priority2,
+    Priority3,
+}
+
+impl Priority {
+    pub fn max() -> Priority {
+        Priority::Priority3
+    }
+
+    pub fn min() -> Priority {
+        Priority::Priority1
+    }
+}
+
+impl CpuInterrupt {
+    #[inline]
+    fn level(&self) -> Priority {
+        match self {
+            CpuInterrupt::Interrupt0LevelPriority1
+            | CpuInterrupt::Interrupt1LevelPriority1
+            | CpuInterrupt::Interrupt2LevelPriority1
+            | CpuInterrupt::Interrupt3LevelPriority1
+            | CpuInterrupt::Interrupt4LevelPriority1
+            | CpuInterrupt::Interrupt5LevelPriority1
+            | CpuInterrupt::Interrupt6Timer0Priority1
+            | CpuInterrupt::Interrupt7SoftwarePriority1
+            | CpuInterrupt::Interrupt8LevelPriority1
+            | CpuInterrupt::Interrupt9LevelPriority1
+            | CpuInterrupt::Interrupt10EdgePriority1
+            | CpuInterrupt::Interrupt12LevelPriority1
+            | CpuInterrupt::Interrupt13LevelPriority1
+            | CpuInterrupt::Interrupt17LevelPriority1
+            | CpuInterrupt::Interrupt18LevelPriority1 => Priority::Priority1,
+
+            CpuInterrupt::Interrupt19LevelPriority2
+            | CpuInterrupt::Interrupt20LevelPriority2
+            | CpuInterrupt::Interrupt21LevelPriority2 => Priority::Priority2,
+
+            CpuInterrupt::Interrupt11ProfilingPriority3
+            | CpuInterrupt::Interrupt15Timer1Priority3
+            | CpuInterrupt::Interrupt22EdgePriority3
+            | CpuInterrupt::Interrupt27LevelPriority3
+            | CpuInterrupt::Interrupt29SoftwarePriority3
+            | CpuInterrupt::Interrupt23LevelPriority3 => Priority::Priority3,
+
+            // we direct these to None because we do not support interrupts at this level
+            // through Rust
+            CpuInterrupt::Interrupt24LevelPriority4
+            | CpuInterrupt::Interrupt25LevelPriority4
+            | CpuInterrupt::Interrupt28EdgePriority4
+            | CpuInterrupt::Interrupt30EdgePriority4
+            | CpuInterrupt::Interrupt31EdgePriority5
+            | CpuInterrupt::Interrupt16Timer2Priority5
+            | CpuInterrupt::Interrupt26LevelPriority5
+            | CpuInterrupt::Interrupt14NmiPriority7 => Priority::None,
+        }
+    }
+}
+
+/// Get the priority level of a CPU interrupt
+///
+/// The levels of the Xtensa CPU interrupts are fixed, this is the level in the
+/// name of `which`. Levels above 3 can't be handled in Rust and are reported as
+/// [`Priority::None`].
+pub fn priority(which: CpuInterrupt) -> Priority {
+    which.level()
+}
+
+/// Check the priority level of a CPU interrupt
+///
+/// Unlike on the RISC-V chips, the levels of the Xtensa CPU interrupts are
+/// fixed in hardware, this is the level in the name of `which`. Nothing is
+/// written, this only returns [`Error::InvalidInterruptPriority`] if
+/// `priority` differs from that level, instead of silently ignoring it.
+///
+/// To run a peripheral interrupt at another priority, map it to a CPU
+/// interrupt of that level, which is what `enable` does with the `vectored`
+/// feature.
+///
+/// This is `unsafe` and returns a `Result` to have the same signature as on
+/// the RISC-V chips.
+pub unsafe fn set_priority(
+    _core: Cpu,
+    which: CpuInterrupt,
+    priority: Priority,
+) -> Result<(), Error> {
+    if which.level() != priority {
+        return Err(Error::InvalidInterruptPriority);
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "vectored")]
 pub use vectored::*;
 
@@ -187,73 +314,69 @@ mod vectored {
     use super::*;
     use crate::get_core;
 
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-    pub enum Error {
-        InvalidInterrupt,
+    /// Mask all interrupts with a priority below `priority`
+    ///
+    /// Interrupts of `priority` and above are still serviced, the masked ones
+    /// stay pending until the threshold is lowered again. Unlike a critical
+    /// section, this allows e.g. a high priority timer interrupt through while
+    /// the code is protected from lower priority handlers. Use
+    /// [`PriorityGuard`] to restore the previous threshold automatically.
+    ///
+    /// This sets `PS.INTLEVEL` of the current core to one below `priority`,
+    /// [`Priority::min`] unmasks all interrupts again. A critical section
+    /// restores `PS` when it ends, so the threshold must not be changed inside
+    /// of one.
+    pub fn set_threshold(_core: Cpu, priority: Priority) {
+        write_intlevel((priority as u32).saturating_sub(1));
     }
 
-    /// Interrupt priority levels.
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-    #[repr(u8)]
-    pub enum Priority {
-        None = 0,
-        Priority1,
-        Priority2,
-        Priority3,
+    #[inline]
+    fn read_intlevel() -> u32 {
+        let ps: u32;
+        unsafe { core::arch::asm!("rsr.ps {0}", out(reg) ps) };
+        ps & 0xf
     }
 
-    impl Priority {
-        pub fn max() -> Priority {
-            Priority::Priority3
+    #[inline]
+    fn write_intlevel(level: u32) {
+        unsafe {
+            let mut ps: u32;
+            core::arch::asm!("rsr.ps {0}", out(reg) ps);
+            ps = (ps & !0xf) | level;
+            core::arch::asm!("wsr.ps {0}", "rsync", in(reg) ps);
         }
+    }
+
+    /// Masks interrupts below a priority until dropped
+    ///
+    /// See [`set_threshold`]. The threshold is only ever raised, so guards can
+    /// be nested, and the previous threshold is restored when the guard is
+    /// dropped.
+    ///
+    /// ```rust,ignore
+    /// {
+    ///     let _guard = interrupt::PriorityGuard::new(Cpu::ProCpu, Priority::Priority3);
+    ///     // only interrupts of priority 3 and above are serviced here
+    /// }
+    /// ```
+    #[must_use = "the previous threshold is restored as soon as the guard is dropped"]
+    pub struct PriorityGuard {
+        previous: u32,
+    }
+
+    impl PriorityGuard {
+        /// Mask the interrupts below `priority` on the current core
+        pub fn new(_core: Cpu, priority: Priority) -> Self {
+            let previous = read_intlevel();
+            write_intlevel(previous.max((priority as u32).saturating_sub(1)));
 
-        pub fn min() -> Priority {
-            Priority::Priority1
+            Self { previous }
         }
     }
 
-    impl CpuInterrupt {
-        #[inline]
-        fn level(&self) -> Priority {
-            match self {
-                CpuInterrupt::Interrupt0LevelPriority1
-                | CpuInterrupt::Interrupt1LevelPriority1
-                | CpuInterrupt::Interrupt2LevelPriority1
-                | CpuInterrupt::Interrupt3LevelPriority1
-                | CpuInterrupt::Interrupt4LevelPriority1
-                | CpuInterrupt::Interrupt5LevelPriority1
-                | CpuInterrupt::Interrupt6Timer0Priority1
-                | CpuInterrupt::Interrupt7SoftwarePriority1
-                | CpuInterrupt::Interrupt8LevelPriority1
-                | CpuInterrupt::Interrupt9LevelPriority1
-                | CpuInterrupt::Interrupt10EdgePriority1
-                | CpuInterrupt::Interrupt12LevelPriority1
-                | CpuInterrupt::Interrupt13LevelPriority1
-                | CpuInterrupt::Interrupt17LevelPriority1
-                | CpuInterrupt::Interrupt18LevelPriority1 => Priority::Priority1,
-
-                CpuInterrupt::Interrupt19LevelPriority2
-                | CpuInterrupt::Interrupt20LevelPriority2
-                | CpuInterrupt::Interrupt21LevelPriority2 => Priority::Priority2,
-
-                CpuInterrupt::Interrupt11ProfilingPriority3
-                | CpuInterrupt::Interrupt15Timer1Priority3
-                | CpuInterrupt::Interrupt22EdgePriority3
-                | CpuInterrupt::Interrupt27LevelPriority3
-                | CpuInterrupt::Interrupt29SoftwarePriority3
-                | CpuInterrupt::Interrupt23LevelPriority3 => Priority::Priority3,
-
-                // we direct these to None because we do not support interrupts at this level
-                // through Rust
-                CpuInterrupt::Interrupt24LevelPriority4
-                | CpuInterrupt::Interrupt25LevelPriority4
-                | CpuInterrupt::Interrupt28EdgePriority4
-                | CpuInterrupt::Interrupt30EdgePriority4
-                | CpuInterrupt::Interrupt31EdgePriority5
-                | CpuInterrupt::Interrupt16Timer2Priority5
-                | CpuInterrupt::Interrupt26LevelPriority5
-                | CpuInterrupt::Interrupt14NmiPriority7 => Priority::None,
-            }
+    impl Drop for PriorityGuard {
+        fn drop(&mut self) {
+            write_intlevel(self.previous);
         }
     }
 
@@ -432,6 +555,12 @@ mod vectored {
             // defined in each hal
             fn EspDefaultHandler(level: u32, interrupt: Interrupt);
         }
+        // called outside of the critical section, so higher priority interrupts
+        // can still preempt it
+        if let Some(handler) = super::super::registered_handler(interrupt) {
+            handler();
+            return;
+        }
 
         let handler = peripherals::__INTERRUPTS[interrupt.number() as usize]._handler;
         if handler as *const _ == EspDefaultHandler as *const unsafe extern "C" fn() {
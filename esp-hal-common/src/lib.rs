@@ -65,10 +65,16 @@ pub use self::{
 pub mod aes;
 pub mod analog;
 pub mod clock;
+#[cfg(esp32c3)]
+pub mod dedicated_gpio;
 pub mod delay;
 pub mod dma;
 #[cfg(feature = "embassy")]
 pub mod embassy;
+#[cfg(etm)]
+pub mod etm;
+#[cfg(feature = "async")]
+pub mod executor;
 pub mod gpio;
 pub mod i2c;
 #[cfg(i2s)]
@@ -92,6 +98,7 @@ pub mod rom;
 pub mod rtc_cntl;
 pub mod sha;
 pub mod soc;
+pub mod soft_pwm;
 pub mod spi;
 pub mod system;
 #[cfg(systimer)]
@@ -113,8 +120,19 @@ pub mod trapframe {
     pub use xtensa_lx_rt::exception::Context as TrapFrame;
 }
 
+#[cfg(riscv)]
+#[no_mangle]
+extern "C" fn EspDefaultHandler(_interrupt: peripherals::Interrupt) {
+    #[cfg(all(feature = "async", feature = "vectored"))]
+    interrupt::asynch::on_interrupt(_interrupt);
+}
+
+#[cfg(xtensa)]
 #[no_mangle]
-extern "C" fn EspDefaultHandler(_level: u32, _interrupt: peripherals::Interrupt) {}
+extern "C" fn EspDefaultHandler(_level: u32, _interrupt: peripherals::Interrupt) {
+    #[cfg(all(feature = "async", feature = "vectored"))]
+    interrupt::asynch::on_interrupt(_interrupt);
+}
 
 #[cfg(xtensa)]
 #[no_mangle]
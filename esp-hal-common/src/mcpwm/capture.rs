@@ -0,0 +1,140 @@
+use core::{convert::Infallible, marker::PhantomData};
+
+use crate::{
+    gpio::InputPin,
+    mcpwm::PwmPeripheral,
+    peripheral::{Peripheral, PeripheralRef},
+};
+
+// CAP_TIMER_CFG
+const CAP_TIMER_EN: u32 = 1 << 0;
+// CAP_CHn_CFG
+const CAP_EN: u32 = 1 << 0;
+const CAP_MODE_SHIFT: u32 = 1;
+// INT_RAW / INT_CLR, one bit per channel
+const CAP_INT_SHIFT: u32 = 27;
+
+/// Edges of the input signal that are captured
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CaptureEdge {
+    /// Capture on rising edges
+    Rising  = 0b10,
+    /// Capture on falling edges
+    Falling = 0b01,
+    /// Capture on both edges
+    Both    = 0b11,
+}
+
+/// Edge a [`Timestamp`] was captured on
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Edge {
+    /// Rising edge
+    Rising,
+    /// Falling edge
+    Falling,
+}
+
+/// The value of the capture timer latched by the hardware at an edge
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timestamp {
+    /// Ticks of the capture timer, which counts at the APB clock (80 MHz by
+    /// default) and wraps around every 2^32 ticks
+    pub ticks: u32,
+    /// The edge that was captured
+    pub edge: Edge,
+}
+
+/// A MCPWM capture channel
+///
+/// Latches the free running capture timer of the [`MCPWM`](super::MCPWM)
+/// peripheral when an edge occurs at its input. The timer value is stored by
+/// the hardware, so it isn't affected by interrupt latency or by the time it
+/// takes to read it. All three channels share the capture timer, so timestamps
+/// of different channels can be compared with each other.
+pub struct Capture<const CH: u8, PWM> {
+    phantom: PhantomData<PWM>,
+}
+
+impl<const CH: u8, PWM: PwmPeripheral> Capture<CH, PWM> {
+    pub(super) fn new() -> Self {
+        Capture {
+            phantom: PhantomData,
+        }
+    }
+
+    /// Capture the timer on every `edge` at `pin`
+    ///
+    /// Also starts the capture timer, if it isn't running yet.
+    pub fn capture_timestamp_on_edge<'d, Pin: InputPin>(
+        self,
+        pin: impl Peripheral<P = Pin> + 'd,
+        edge: CaptureEdge,
+    ) -> CapturePin<'d, Pin, CH, PWM> {
+        crate::into_ref!(pin);
+        pin.set_to_input()
+            .connect_input_to_peripheral(PWM::input_signal::<CH>());
+
+        let block = unsafe { &*PWM::block() };
+        block
+            .cap_timer_cfg
+            .modify(|r, w| unsafe { w.bits(r.bits() | CAP_TIMER_EN) });
+
+        let cfg = CAP_EN | (edge as u32) << CAP_MODE_SHIFT;
+        unsafe {
+            match CH {
+                0 => block.cap_ch0_cfg.write(|w| w.bits(cfg)),
+                1 => block.cap_ch1_cfg.write(|w| w.bits(cfg)),
+                2 => block.cap_ch2_cfg.write(|w| w.bits(cfg)),
+                _ => unreachable!(),
+            }
+            block
+                .int_clr
+                .write(|w| w.bits(1 << (CAP_INT_SHIFT + CH as u32)));
+        }
+
+        CapturePin {
+            _pin: pin,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// A pin whose edges are captured by a [`Capture`] channel
+pub struct CapturePin<'d, Pin, const CH: u8, PWM> {
+    _pin: PeripheralRef<'d, Pin>,
+    phantom: PhantomData<PWM>,
+}
+
+impl<'d, Pin, const CH: u8, PWM: PwmPeripheral> CapturePin<'d, Pin, CH, PWM> {
+    /// The timestamp of the latest edge, if one was captured since the last
+    /// call
+    ///
+    /// Only the latest edge is kept: when edges come faster than they are
+    /// read, the earlier ones are lost.
+    pub fn timestamp(&mut self) -> nb::Result<Timestamp, Infallible> {
+        let block = unsafe { &*PWM::block() };
+        let mask = 1 << (CAP_INT_SHIFT + CH as u32);
+
+        if block.int_raw.read().bits() & mask == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        // Read the capture before acknowledging it. The other way around, an edge
+        // in between would be returned here and stay flagged, so the next call
+        // would return it a second time.
+        let ticks = match CH {
+            0 => block.cap_ch0.read().bits(),
+            1 => block.cap_ch1.read().bits(),
+            2 => block.cap_ch2.read().bits(),
+            _ => unreachable!(),
+        };
+        let edge = if block.cap_status.read().bits() & (1 << CH) != 0 {
+            Edge::Falling
+        } else {
+            Edge::Rising
+        };
+        unsafe { block.int_clr.write(|w| w.bits(mask)) };
+
+        Ok(Timestamp { ticks, edge })
+    }
+}
@@ -20,7 +20,10 @@
 //!     * Period, time stamps and important control registers have shadow
 //!       registers with flexible updating methods.
 //! * Fault Detection Module (Not yet implemented)
-//! * Capture Module (Not yet implemented)
+//! * Capture Module
+//!     * Three capture channels latch a free running 32-bit timer on edges of
+//!       their inputs, see [`capture::Capture`]. (Not yet implemented on the
+//!       ESP32-C6)
 //!
 //! # Example
 //! Uses timer0 and operator0 of the MCPWM0 peripheral to output a 50% duty
@@ -65,11 +68,14 @@ use timer::Timer;
 
 use crate::{
     clock::Clocks,
-    gpio::OutputSignal,
+    gpio::{InputSignal, OutputSignal},
     peripheral::{Peripheral, PeripheralRef},
     system::{Peripheral as PeripheralEnable, PeripheralClockControl},
 };
 
+/// MCPWM capture channels
+#[cfg(not(esp32c6))]
+pub mod capture;
 /// MCPWM operators
 pub mod operator;
 /// MCPWM timers
@@ -101,6 +107,15 @@ pub struct MCPWM<'d, PWM> {
     pub operator1: Operator<1, PWM>,
     /// Operator2
     pub operator2: Operator<2, PWM>,
+    /// Capture channel 0
+    #[cfg(not(esp32c6))]
+    pub capture0: capture::Capture<0, PWM>,
+    /// Capture channel 1
+    #[cfg(not(esp32c6))]
+    pub capture1: capture::Capture<1, PWM>,
+    /// Capture channel 2
+    #[cfg(not(esp32c6))]
+    pub capture2: capture::Capture<2, PWM>,
 }
 
 impl<'d, PWM: PwmPeripheral> MCPWM<'d, PWM> {
@@ -150,6 +165,12 @@ impl<'d, PWM: PwmPeripheral> MCPWM<'d, PWM> {
             operator0: Operator::new(),
             operator1: Operator::new(),
             operator2: Operator::new(),
+            #[cfg(not(esp32c6))]
+            capture0: capture::Capture::new(),
+            #[cfg(not(esp32c6))]
+            capture1: capture::Capture::new(),
+            #[cfg(not(esp32c6))]
+            capture2: capture::Capture::new(),
         }
     }
 }
@@ -280,6 +301,8 @@ pub unsafe trait PwmPeripheral: Deref<Target = RegisterBlock> {
     fn block() -> *const RegisterBlock;
     /// Get operator GPIO mux output signal
     fn output_signal<const OP: u8, const IS_A: bool>() -> OutputSignal;
+    /// Get capture channel GPIO mux input signal
+    fn input_signal<const CH: u8>() -> InputSignal;
 }
 
 unsafe impl PwmPeripheral for PWM {
@@ -302,6 +325,15 @@ unsafe impl PwmPeripheral for PWM {
             _ => unreachable!(),
         }
     }
+
+    fn input_signal<const CH: u8>() -> InputSignal {
+        match CH {
+            0 => InputSignal::PWM0_CAP0,
+            1 => InputSignal::PWM0_CAP1,
+            2 => InputSignal::PWM0_CAP2,
+            _ => unreachable!(),
+        }
+    }
 }
 
 #[cfg(not(esp32c6))]
@@ -325,4 +357,13 @@ unsafe impl PwmPeripheral for crate::peripherals::PWM1 {
             _ => unreachable!(),
         }
     }
+
+    fn input_signal<const CH: u8>() -> InputSignal {
+        match CH {
+            0 => InputSignal::PWM1_CAP0,
+            1 => InputSignal::PWM1_CAP1,
+            2 => InputSignal::PWM1_CAP2,
+            _ => unreachable!(),
+        }
+    }
 }
@@ -1,7 +1,24 @@
 //! The prelude
 //!
 //! Re-exports all traits required for interacting with the various peripheral
-//! drivers implemented in this crate.
+//! drivers implemented in this crate, so that `use prelude::*` together with
+//! the drivers themselves is all an application needs:
+//!
+//! - the `embedded-hal` 0.2 traits from its prelude (delay, serial, SPI, I2C,
+//!   timers, ADC, PWM, watchdog) and the `digital::v2` pin traits
+//! - [`core::fmt::Write`], for `write!` and `writeln!` on e.g. a `Uart`
+//! - [`nb`] and its [`block!`](nb::block) macro
+//! - the `fugit` extension traits, for `1u64.secs()`, `500u32.millis()` or
+//!   `100u32.kHz()`
+//! - the extension traits splitting peripherals into their parts, like
+//!   `SYSTEM.split()` or `SENS.split()`, and the traits of the driver instances
+//! - the `#[entry]` and `#[interrupt]` macros
+//!
+//! All traits are re-exported under private names, so they don't collide with
+//! types or traits of the same name in the application. The traits of the
+//! 1.0.0-alpha releases of `embedded-hal` are in [`eh1`] instead.
+
+pub use core::fmt::Write as _core_fmt_Write;
 
 pub use embedded_hal::{
     digital::v2::{
@@ -18,7 +35,7 @@ pub use fugit::{
     RateExtU32 as _fugit_RateExtU32,
     RateExtU64 as _fugit_RateExtU64,
 };
-pub use nb;
+pub use nb::{self, block};
 
 #[cfg(any(esp32c2, esp32c3, esp32c6))]
 pub use crate::analog::SarAdcExt as _esp_hal_analog_SarAdcExt;
@@ -33,6 +50,8 @@ pub use crate::pulse_control::{
 pub use crate::radio::RadioExt as _esp_hal_RadioExt;
 #[cfg(any(esp32, esp32s2))]
 pub use crate::spi::dma::WithDmaSpi3 as _esp_hal_spi_dma_WithDmaSpi3;
+#[cfg(all(esp32c3, feature = "async"))]
+pub use crate::uart::dma::WithDmaUart as _esp_hal_uart_dma_WithDmaUart;
 pub use crate::{
     clock::Clock as _esp_hal_clock_Clock,
     dma::{
@@ -73,6 +92,8 @@ pub use crate::{
 /// All traits required for using the 1.0.0-alpha.x release of embedded-hal
 #[cfg(feature = "eh1")]
 pub mod eh1 {
+    pub use core::fmt::Write as _core_fmt_Write;
+
     pub use embedded_hal_1::{
         delay::DelayUs as _embedded_hal_delay_blocking_DelayUs,
         digital::{
@@ -99,9 +120,9 @@ pub mod eh1 {
         RateExtU32 as _fugit_RateExtU32,
         RateExtU64 as _fugit_RateExtU64,
     };
-    pub use nb;
+    pub use nb::{self, block};
 
-    #[cfg(any(esp32c2, esp32c3))]
+    #[cfg(any(esp32c2, esp32c3, esp32c6))]
     pub use crate::analog::SarAdcExt as _esp_hal_analog_SarAdcExt;
     #[cfg(any(esp32, esp32s2, esp32s3))]
     pub use crate::analog::SensExt as _esp_hal_analog_SensExt;
@@ -112,6 +133,8 @@ pub mod eh1 {
     };
     #[cfg(any(esp32, esp32s2))]
     pub use crate::spi::dma::WithDmaSpi3 as _esp_hal_spi_dma_WithDmaSpi3;
+    #[cfg(all(esp32c3, feature = "async"))]
+    pub use crate::uart::dma::WithDmaUart as _esp_hal_uart_dma_WithDmaUart;
     pub use crate::{
         clock::Clock as _esp_hal_clock_Clock,
         dma::{
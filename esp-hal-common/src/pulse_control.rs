@@ -83,7 +83,7 @@
 
 use core::slice::Iter;
 
-use fugit::NanosDurationU32;
+use fugit::{HertzU32, MicrosDurationU32, NanosDurationU32};
 pub use paste::paste;
 
 #[cfg(esp32c6)]
@@ -101,6 +101,9 @@ pub enum SetupError {
     /// The global configuration for the RMT peripheral is invalid
     /// (e.g. the fractional parameters are outOfBound)
     InvalidGlobalConfig,
+    /// The requested tone frequency cannot be generated from the channel's
+    /// source clock
+    UnachievableFrequency,
 }
 
 /// Errors that can occur during a transmission attempt
@@ -231,9 +234,25 @@ pub trait OutputChannel {
     /// Enable/Disable the output while the channel is idle
     fn set_idle_output(&mut self, state: bool) -> &mut Self;
 
-    /// Set channel clock divider value
+    /// Set channel clock divider value, a value of 0 divides by 256
     fn set_channel_divider(&mut self, divider: u8) -> &mut Self;
 
+    /// Set the channel clock divider whose tick length is closest to
+    /// `resolution`, returning the achieved tick frequency
+    ///
+    /// `source_frequency` is the clock feeding the channel divider, see
+    /// [`divider_for_resolution`].
+    fn set_resolution(
+        &mut self,
+        source_frequency: HertzU32,
+        resolution: NanosDurationU32,
+    ) -> HertzU32 {
+        let (divider, tick_frequency) = divider_for_resolution(source_frequency, resolution);
+        // `DIV_CNT` is 8 bits wide, 256 is written as 0
+        self.set_channel_divider(divider as u8);
+        tick_frequency
+    }
+
     /// Enable/Disable carrier modulation
     fn set_carrier_modulation(&mut self, state: bool) -> &mut Self;
 
@@ -277,6 +296,339 @@ pub trait ConfiguredChannel {
     fn stop_transmission(&self);
 }
 
+mod sealed {
+    /// Changing the divider of a configured channel, only needed by
+    /// [`super::ToneGenerator`]
+    pub trait ChannelDivider {
+        fn set_channel_divider(&mut self, divider: u8);
+    }
+}
+
+/// A configured output channel that can be started in sync with others, see
+/// [`sync_transmit`]
+#[cfg(not(esp32))]
+pub trait SyncChannel {
+    #[doc(hidden)]
+    fn channel_number(&self) -> u8;
+
+    #[doc(hidden)]
+    fn prepare(&mut self, sequence_iter: &mut Iter<u32>) -> Result<(), TransmissionError>;
+
+    #[doc(hidden)]
+    fn start(&self);
+
+    #[doc(hidden)]
+    fn poll(&mut self, sequence_iter: &mut Iter<u32>) -> Result<bool, TransmissionError>;
+
+    #[doc(hidden)]
+    fn stop(&self);
+}
+
+// Number of channels that can transmit, all of them can be part of a
+// synchronized start
+#[cfg(any(esp32c3, esp32c6))]
+const TX_CHANNEL_COUNT: usize = 2;
+#[cfg(any(esp32s2, esp32s3))]
+const TX_CHANNEL_COUNT: usize = 4;
+
+/// Send raw pulse sequences on several channels, starting them at the same
+/// time
+///
+/// The channels are put into a group in `RMT_TX_SIM_REG` and only start
+/// transmitting once all of them were started, on the same clock cycle. Each
+/// channel sends its sequence once, like [`RepeatMode::SingleShot`], and this
+/// blocks until all of them are done. The sequences are in the raw format of
+/// [`ConfiguredChannel::send_pulse_sequence_raw`] and need their own end
+/// marker, they may be longer than the channel RAM and of different lengths.
+///
+/// Only the start is synchronized: the channels stay aligned for the whole
+/// transmission if they use the same divider (and clock source on the
+/// ESP32-S2), as their pulse codes are then timed by the same clock. Any
+/// transmitting channel can be part of the group, on the ESP32-C3 and
+/// ESP32-C6 these are `Channel0` and `Channel1`, on the ESP32-S2 and ESP32-S3
+/// `Channel0` to `Channel3`. The ESP32 can't start channels in sync.
+///
+/// If one of the channels reports an error, all of them are stopped.
+///
+/// ```rust,ignore
+/// pulse_control::sync_transmit(&mut [
+///     (&mut rmt_channel0, &strip0[..]),
+///     (&mut rmt_channel1, &strip1[..]),
+/// ])
+/// .unwrap();
+/// ```
+#[cfg(not(esp32))]
+pub fn sync_transmit(
+    transmissions: &mut [(&mut dyn SyncChannel, &[u32])],
+) -> Result<(), TransmissionError> {
+    let rmt = unsafe { &*RMT::PTR };
+
+    let mut group = 0u32;
+    let mut iters: [Option<Iter<u32>>; TX_CHANNEL_COUNT] = Default::default();
+    for ((channel, sequence), iter) in transmissions.iter_mut().zip(iters.iter_mut()) {
+        group |= 1 << channel.channel_number();
+        let sequence_iter = iter.insert(sequence.iter());
+        channel.prepare(sequence_iter)?;
+    }
+
+    // The channels of the group wait for each other once the group is enabled
+    rmt.tx_sim
+        .write(|w| unsafe { w.bits(group | 1 << TX_CHANNEL_COUNT) });
+    for (channel, _) in transmissions.iter() {
+        channel.start();
+    }
+
+    let mut result = Ok(());
+    let mut done = 0u32;
+    while done != (1 << transmissions.len()) - 1 && result.is_ok() {
+        for (i, ((channel, _), iter)) in transmissions.iter_mut().zip(iters.iter_mut()).enumerate()
+        {
+            if done & (1 << i) != 0 {
+                continue;
+            }
+
+            match channel.poll(iter.as_mut().unwrap()) {
+                Ok(true) => done |= 1 << i,
+                Ok(false) => (),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+    }
+
+    if result.is_err() {
+        for (channel, _) in transmissions.iter() {
+            channel.stop();
+        }
+    }
+
+    // Leave the channels free to be started on their own again
+    rmt.tx_sim.write(|w| unsafe { w.bits(0) });
+
+    result
+}
+
+// Range of the channel clock divider (`DIV_CNT`, where 0 divides by 256)
+const MIN_CHANNEL_DIVIDER: u64 = 1;
+const MAX_CHANNEL_DIVIDER: u64 = 256;
+
+/// Compute the channel clock divider for a tick length of `resolution`
+///
+/// Returns the divider and the achieved tick frequency, the achieved tick
+/// length is its inverse. `source_frequency` is the clock feeding the channel
+/// divider, i.e. the source selected in [`PulseControl::new`] (or with
+/// `set_clock_source` on the ESP32 and ESP32-S2) after the peripheral-wide
+/// divider.
+///
+/// The channel divider ranges from 1 to 256. When `resolution` isn't a whole
+/// number of source clock cycles, the nearest divider is used, and
+/// resolutions outside of the range give the smallest or the largest divider.
+/// With the 80 MHz APB clock, the tick length goes from 12.5 ns to 3.2 µs in
+/// steps of 12.5 ns, e.g. 100 ns for WS2812 LEDs are a divider of 8.
+pub fn divider_for_resolution(
+    source_frequency: HertzU32,
+    resolution: NanosDurationU32,
+) -> (u16, HertzU32) {
+    let source = source_frequency.raw() as u64;
+    let cycles = resolution.ticks() as u64 * source;
+    let divider =
+        ((cycles + 500_000_000) / 1_000_000_000).clamp(MIN_CHANNEL_DIVIDER, MAX_CHANNEL_DIVIDER);
+
+    (
+        divider as u16,
+        HertzU32::from_raw((source / divider) as u32),
+    )
+}
+
+// Longest interval a single half of a pulse code can describe (15 bits)
+const MAX_PULSE_LENGTH: u32 = 0x7fff;
+
+// Number of pulse codes used for one period of a tone. This always fits into
+// the channel RAM, so the hardware loop modes can be used.
+const TONE_SEQUENCE_LEN: usize = 8;
+
+// Each level of the square wave may be spread over up to 7 intervals, which
+// leaves room for the end marker in the last pulse code
+const MAX_TONE_PERIOD: u32 = 2 * (TONE_SEQUENCE_LEN as u32 - 1) * MAX_PULSE_LENGTH;
+
+/// Square wave generator on top of a configured output channel
+///
+/// Computes the channel divider and pulse codes for a 50% duty cycle square
+/// wave, e.g. to drive a passive buzzer. The divider is chosen to minimize the
+/// frequency error, use [`ToneGenerator::frequency`] to get the frequency
+/// that is actually generated. Low frequencies whose half period exceeds the
+/// 15 bit pulse length are produced by chaining several pulse codes.
+///
+/// `source_frequency` is the clock feeding the channel divider, i.e. the
+/// source selected in [`PulseControl::new`] after the peripheral-wide divider
+/// (80 MHz for `ClockSource::APB` without division).
+///
+/// The generator sets the channel divider each time a tone is played, so it
+/// only works with the configured channels of this module.
+pub struct ToneGenerator<C> {
+    channel: C,
+    source_frequency: HertzU32,
+    divider: u8,
+    period: u32,
+    sequence: [u32; TONE_SEQUENCE_LEN],
+}
+
+impl<C> ToneGenerator<C>
+where
+    C: ConfiguredChannel + sealed::ChannelDivider,
+{
+    /// Create a new tone generator producing `frequency`
+    pub fn new(
+        channel: C,
+        source_frequency: HertzU32,
+        frequency: HertzU32,
+    ) -> Result<Self, SetupError> {
+        let mut tone = Self {
+            channel,
+            source_frequency,
+            divider: 1,
+            period: 0,
+            sequence: [0; TONE_SEQUENCE_LEN],
+        };
+        tone.set_frequency(frequency)?;
+
+        Ok(tone)
+    }
+
+    /// Change the generated frequency, returning the achieved frequency
+    ///
+    /// Takes effect with the next call to [`ToneGenerator::play`] or
+    /// [`ToneGenerator::start`].
+    pub fn set_frequency(&mut self, frequency: HertzU32) -> Result<HertzU32, SetupError> {
+        let source = self.source_frequency.raw() as u64;
+        let frequency = frequency.raw() as u64;
+        if frequency == 0 {
+            return Err(SetupError::UnachievableFrequency);
+        }
+
+        // Search for the divider / period pair with the smallest relative error
+        // `|source - frequency * divider * period| / (frequency * divider * period)`
+        let mut best: Option<(u8, u32, u64)> = None;
+        for divider in 1..=u8::MAX {
+            let ticks = divider as u64 * frequency;
+            let period = (source + ticks / 2) / ticks;
+            if period < 2 || period > MAX_TONE_PERIOD as u64 {
+                continue;
+            }
+
+            let error = source.abs_diff(ticks * period);
+            let better = match best {
+                None => true,
+                Some((best_divider, best_period, best_error)) => {
+                    error * (best_divider as u64 * best_period as u64)
+                        < best_error * (divider as u64 * period)
+                }
+            };
+            if better {
+                best = Some((divider, period as u32, error));
+                if error == 0 {
+                    break;
+                }
+            }
+        }
+
+        let (divider, period, _) = best.ok_or(SetupError::UnachievableFrequency)?;
+
+        self.divider = divider;
+        self.period = period;
+        self.sequence = Self::build_sequence(period);
+
+        Ok(self.frequency())
+    }
+
+    /// The frequency that is actually generated
+    pub fn frequency(&self) -> HertzU32 {
+        let ticks = self.divider as u32 * self.period;
+        HertzU32::from_raw((self.source_frequency.raw() + ticks / 2) / ticks)
+    }
+
+    /// The selected channel clock divider
+    pub fn divider(&self) -> u8 {
+        self.divider
+    }
+
+    /// Play the tone for `duration`, blocking until done
+    ///
+    /// The duration is rounded to whole periods of the tone. On the ESP32,
+    /// which lacks the hardware loop counter, every period is sent
+    /// separately, which leaves small gaps at high frequencies.
+    pub fn play(&mut self, duration: MicrosDurationU32) -> Result<(), TransmissionError> {
+        self.channel.set_channel_divider(self.divider);
+
+        let ticks = self.divider as u64 * self.period as u64;
+        let mut periods = (duration.ticks() as u64 * self.source_frequency.raw() as u64
+            + ticks * 500_000)
+            / (ticks * 1_000_000);
+
+        while periods > 0 {
+            cfg_if::cfg_if! {
+                if #[cfg(esp32)] {
+                    self.channel
+                        .send_pulse_sequence_raw(RepeatMode::SingleShot, &self.sequence)?;
+                    periods -= 1;
+                } else {
+                    // The loop counter is 10 bits wide
+                    let count = periods.min(1023);
+                    self.channel
+                        .send_pulse_sequence_raw(RepeatMode::RepeatNtimes(count as u16), &self.sequence)?;
+                    periods -= count;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start playing the tone continuously
+    ///
+    /// Use [`ToneGenerator::stop`] to end the tone. Note that the ESP32 has
+    /// no way to stop a running transmission.
+    pub fn start(&mut self) -> Result<(), TransmissionError> {
+        self.channel.set_channel_divider(self.divider);
+        self.channel
+            .send_pulse_sequence_raw(RepeatMode::Forever, &self.sequence)
+    }
+
+    /// Stop a tone started with [`ToneGenerator::start`]
+    pub fn stop(&mut self) {
+        self.channel.stop_transmission();
+    }
+
+    /// Release the underlying channel
+    pub fn free(self) -> C {
+        self.channel
+    }
+
+    // One period of the square wave, levels longer than a single interval are
+    // split up, followed by the end marker
+    fn build_sequence(period: u32) -> [u32; TONE_SEQUENCE_LEN] {
+        let mut sequence = [0u32; TONE_SEQUENCE_LEN];
+        let high = period / 2;
+        let low = period - high;
+
+        let mut slot = 0;
+        for (level, mut remaining) in [(true, high), (false, low)] {
+            while remaining > 0 {
+                let length = remaining.min(MAX_PULSE_LENGTH);
+                remaining -= length;
+
+                let entry = length | ((level as u32) << 15);
+                sequence[slot / 2] |= entry << (16 * (slot % 2));
+                slot += 1;
+            }
+        }
+
+        sequence
+    }
+}
+
 macro_rules! channel_instance {
     ($num:literal, $cxi:ident, $output_signal:path
         ) => {
@@ -387,196 +739,286 @@ macro_rules! channel_instance {
                     self.send_pulse_sequence_raw(repeat_mode, &precomputed_sequence)
                 }
 
-            /// Send a raw pulse sequence in a blocking fashion
-            ///
-            /// In this function we expect the `sequence` elements to be already
-            /// in the correct u32 format that is understood by the RMT.
-            /// Please refer to the reference manual or use the variant which
-            /// accepts `PulseCode` objects instead.
-            ///
-            /// We expect that the end marker is already part of the provided
-            /// sequence and to be provided in all modes!
-            fn send_pulse_sequence_raw<const N: usize>(
-                &mut self,
-                repeat_mode: RepeatMode,
-                sequence: &[u32; N],
-            ) -> Result<(), TransmissionError> {
-                // Check for any configuration error states
-                match repeat_mode {
-                    #[cfg(not(esp32))]
-                    RepeatMode::RepeatNtimes(val) => {
-                        if val >= 1024 {
-                            return Err(TransmissionError::RepetitionOverflow);
+                /// Send a raw pulse sequence in a blocking fashion
+                ///
+                /// In this function we expect the `sequence` elements to be already
+                /// in the correct u32 format that is understood by the RMT.
+                /// Please refer to the reference manual or use the variant which
+                /// accepts `PulseCode` objects instead.
+                ///
+                /// We expect that the end marker is already part of the provided
+                /// sequence and to be provided in all modes!
+                fn send_pulse_sequence_raw<const N: usize>(
+                    &mut self,
+                    repeat_mode: RepeatMode,
+                    sequence: &[u32; N],
+                ) -> Result<(), TransmissionError> {
+                    let mut sequence_iter = sequence.iter();
+                    self.prepare_transmission(repeat_mode, &mut sequence_iter)?;
+                    self.start_transmission();
+
+                    // If we're in forever mode, we return right away, otherwise we wait
+                    // for completion
+                    if repeat_mode != RepeatMode::Forever {
+                        while !self.poll_transmission(&mut sequence_iter)? {}
+                    }
+
+                    Ok(())
+                }
+
+                /// Stop any ongoing (repetitive) transmission
+                ///
+                /// This function needs to be called to stop sending when
+                /// previously a sequence was sent with `RepeatMode::Forever`.
+                fn stop_transmission(&self) {
+                    cfg_if::cfg_if! {
+                        if #[cfg(any(esp32c3, esp32c6, esp32s3))] {
+                            unsafe { &*RMT::PTR }
+                                .ch_tx_conf0[$num]
+                                .modify(|_, w| w.tx_stop().set_bit());
+                        }
+                        else if #[cfg(esp32s2)] {
+                            conf1!($num)
+                                .modify(|_, w| w.tx_stop().set_bit());
+                        }
+                        // The ESP32 variant does not have any way to stop a
+                        // transmission once it has been started!
+                    };
+                }
+            }
+
+            impl<'d, P: OutputPin> sealed::ChannelDivider for [<Configured $cxi>]<'d, P> {
+                fn set_channel_divider(&mut self, divider: u8) {
+                    self.channel.set_channel_divider(divider);
+                }
+            }
+
+            impl<'d, P: OutputPin> [<Configured $cxi>]<'d, P> {
+                // Configure the channel for a transmission and fill its RAM with
+                // the start of the sequence, without starting it yet
+                fn prepare_transmission(
+                    &mut self,
+                    repeat_mode: RepeatMode,
+                    sequence_iter: &mut Iter<u32>,
+                ) -> Result<(), TransmissionError> {
+                    // Check for any configuration error states
+                    match repeat_mode {
+                        #[cfg(not(esp32))]
+                        RepeatMode::RepeatNtimes(val) => {
+                            if val >= 1024 {
+                                return Err(TransmissionError::RepetitionOverflow);
+                            }
+                            if sequence_iter.len() > CHANNEL_RAM_SIZE as usize {
+                                return Err(TransmissionError::IncompatibleRepeatMode);
+                            }
                         }
-                        if sequence.len() > CHANNEL_RAM_SIZE as usize {
-                            return Err(TransmissionError::IncompatibleRepeatMode);
+                        RepeatMode::Forever => {
+                            if sequence_iter.len() > CHANNEL_RAM_SIZE as usize {
+                                return Err(TransmissionError::IncompatibleRepeatMode);
+                            }
+                        }
+                        _ => (),
+                    };
+
+                    // Depending on the variant, other registers have to be used here
+                    cfg_if::cfg_if! {
+                        if #[cfg(any(esp32, esp32s2))] {
+                            let conf_reg = & conf1!($num);
+                        } else {
+                            let conf_reg = & unsafe{ &*RMT::PTR }.ch_tx_conf0[$num];
                         }
                     }
-                    RepeatMode::Forever => {
-                        if sequence.len() > CHANNEL_RAM_SIZE as usize {
-                            return Err(TransmissionError::IncompatibleRepeatMode);
+
+                    // The ESP32 does not support loop/count modes, as such we have to
+                    // only configure a subset of registers
+                    cfg_if::cfg_if! {
+                        if #[cfg(esp32)] {
+                            // Configure counting mode and repetitions
+                            unsafe { &*RMT::PTR }.ch_tx_lim[$num].modify(|_, w| unsafe {
+                                // Set the interrupt threshold for sent pulse codes to
+                                // half the size of the RAM in case we use wrap mode
+                                w.tx_lim()
+                                    .bits(CHANNEL_RAM_SIZE as u16 /2)
+                            });
+                        } else {
+                            // Extract repetition value
+                            let mut reps = 0;
+                            if let RepeatMode::RepeatNtimes(val) = repeat_mode {
+                                reps = val;
+                            }
+
+                            // Configure counting mode and repetitions
+                            unsafe { &*RMT::PTR }.ch_tx_lim[$num].modify(|_, w| unsafe {
+                                // Set number of repetitions
+                                w.tx_loop_num()
+                                    .bits(reps)
+                                    // Enable loop counting
+                                    .tx_loop_cnt_en()
+                                    .bit(reps != 0)
+                                    // Reset any pre-existing counting value
+                                    .loop_count_reset()
+                                    .set_bit()
+                                    // Set the interrupt threshold for sent pulse codes to 24
+                                    // (= half the size of the RAM) in case we use wrap mode
+                                    .tx_lim()
+                                    .bits(CHANNEL_RAM_SIZE as u16/2)
+                            });
                         }
                     }
-                    _ => (),
-                };
 
-                // Depending on the variant, other registers have to be used here
-                cfg_if::cfg_if! {
-                    if #[cfg(any(esp32, esp32s2))] {
-                        let conf_reg = & conf1!($num);
+                    #[cfg(any(esp32c3, esp32c6, esp32s3))]
+                    conf_reg.modify(|_, w| {
+                        // Set config update bit
+                        w.conf_update().set_bit()
+                    });
+
+                    // Setup configuration
+                    conf_reg.modify(|_, w| {
+                        // Set configure continuous
+                        // (also reset FIFO buffer pointers)
+                        w.tx_conti_mode()
+                            .bit(repeat_mode != RepeatMode::SingleShot)
+                            .mem_rd_rst()
+                            .set_bit()
+                            .apb_mem_rst()
+                            .set_bit()
+                    });
+
+                    self.channel.reset_fifo();
+
+                    // We have to differentiate here if we can fit the whole sequence
+                    // in the RAM in one go or if we have to use the wrap mode to split
+                    // the sequence into chuncks.
+                    if sequence_iter.len() >= CHANNEL_RAM_SIZE as usize {
+                        // Write the first 48 entries
+                        self.channel.write_sequence(sequence_iter, CHANNEL_RAM_SIZE);
                     } else {
-                        let conf_reg = & unsafe{ &*RMT::PTR }.ch_tx_conf0[$num];
+                        // Write whole sequence to FIFO RAM
+                        self.channel.write_sequence(sequence_iter, CHANNEL_RAM_SIZE);
                     }
-                }
 
-                // The ESP32 does not support loop/count modes, as such we have to
-                // only configure a subset of registers
-                cfg_if::cfg_if! {
-                    if #[cfg(esp32)] {
-                        // Configure counting mode and repetitions
-                        unsafe { &*RMT::PTR }.ch_tx_lim[$num].modify(|_, w| unsafe {
-                            // Set the interrupt threshold for sent pulse codes to
-                            // half the size of the RAM in case we use wrap mode
-                            w.tx_lim()
-                                .bits(CHANNEL_RAM_SIZE as u16 /2)
-                        });
-                    } else {
-                        // Extract repetition value
-                        let mut reps = 0;
-                        if let RepeatMode::RepeatNtimes(val) = repeat_mode {
-                            reps = val;
+                    // Clear the relevant interrupts
+                    //
+                    // (since this is a write-through register, we can do this
+                    // safely for multiple separate channel instances without
+                    // having concurrency issues)
+                    // Depending on the variant, other registers have to be used here
+                    cfg_if::cfg_if! {
+                        if #[cfg(esp32)] {
+                            unsafe { &*RMT::PTR }.int_clr.write(|w| {
+                                // The ESP32 variant does not have the loop functionality
+                                paste!(
+                                    w.[<ch $num _tx_end_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _err_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _tx_thr_event_int_clr>]()
+                                        .set_bit()
+                                )
+                            });
+                        } else if #[cfg(esp32s2)] {
+                            unsafe { &*RMT::PTR }.int_clr.write(|w| {
+                                paste!(
+                                    w.[<ch $num _tx_end_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _tx_loop_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _err_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _tx_thr_event_int_clr>]()
+                                        .set_bit()
+                                )
+                            });
+                        } else {
+                            unsafe { &*RMT::PTR }.int_clr.write(|w| {
+                                paste!(
+                                    w.[<ch $num _tx_end_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _tx_loop_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _tx_err_int_clr>]()
+                                        .set_bit()
+                                        .[<ch $num _tx_thr_event_int_clr>]()
+                                        .set_bit()
+                                )
+                            });
                         }
-
-                        // Configure counting mode and repetitions
-                        unsafe { &*RMT::PTR }.ch_tx_lim[$num].modify(|_, w| unsafe {
-                            // Set number of repetitions
-                            w.tx_loop_num()
-                                .bits(reps)
-                                // Enable loop counting
-                                .tx_loop_cnt_en()
-                                .bit(reps != 0)
-                                // Reset any pre-existing counting value
-                                .loop_count_reset()
-                                .set_bit()
-                                // Set the interrupt threshold for sent pulse codes to 24
-                                // (= half the size of the RAM) in case we use wrap mode
-                                .tx_lim()
-                                .bits(CHANNEL_RAM_SIZE as u16/2)
-                        });
                     }
-                }
 
-                #[cfg(any(esp32c3, esp32c6, esp32s3))]
-                conf_reg.modify(|_, w| {
-                    // Set config update bit
-                    w.conf_update().set_bit()
-                });
-
-                // Setup configuration
-                conf_reg.modify(|_, w| {
-                    // Set configure continuous
-                    // (also reset FIFO buffer pointers)
-                    w.tx_conti_mode()
-                        .bit(repeat_mode != RepeatMode::SingleShot)
-                        .mem_rd_rst()
-                        .set_bit()
-                        .apb_mem_rst()
-                        .set_bit()
-                });
+                    // always enable tx wrap
+                    #[cfg(any(esp32c3, esp32c6, esp32s3))]
+                    unsafe { &*RMT::PTR }.ch_tx_conf0[$num].modify(|_, w| {
+                        w.mem_tx_wrap_en()
+                            .set_bit()
+                    });
 
-                self.channel.reset_fifo();
-                let mut sequence_iter = sequence.iter();
+                    // apply configuration updates
+                    #[cfg(any(esp32c3, esp32c6, esp32s3))]
+                    unsafe { &*RMT::PTR }.ch_tx_conf0[$num].modify(|_, w| {
+                        w.conf_update()
+                            .set_bit()
+                    });
 
-                // We have to differentiate here if we can fit the whole sequence
-                // in the RAM in one go or if we have to use the wrap mode to split
-                // the sequence into chuncks.
-                if sequence.len() >= CHANNEL_RAM_SIZE as usize {
-                    // Write the first 48 entries
-                    self.channel.write_sequence(&mut sequence_iter, CHANNEL_RAM_SIZE);
-                } else {
-                    // Write whole sequence to FIFO RAM
-                    self.channel.write_sequence(&mut sequence_iter, CHANNEL_RAM_SIZE);
+                    Ok(())
                 }
 
-                // Clear the relevant interrupts
-                //
-                // (since this is a write-through register, we can do this
-                // safely for multiple separate channel instances without
-                // having concurrency issues)
-                // Depending on the variant, other registers have to be used here
-                cfg_if::cfg_if! {
-                    if #[cfg(esp32)] {
-                        unsafe { &*RMT::PTR }.int_clr.write(|w| {
-                            // The ESP32 variant does not have the loop functionality
-                            paste!(
-                                w.[<ch $num _tx_end_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _err_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _tx_thr_event_int_clr>]()
-                                    .set_bit()
-                            )
-                        });
-                    } else if #[cfg(esp32s2)] {
-                        unsafe { &*RMT::PTR }.int_clr.write(|w| {
-                            paste!(
-                                w.[<ch $num _tx_end_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _tx_loop_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _err_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _tx_thr_event_int_clr>]()
-                                    .set_bit()
-                            )
-                        });
-                    } else {
-                        unsafe { &*RMT::PTR }.int_clr.write(|w| {
-                            paste!(
-                                w.[<ch $num _tx_end_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _tx_loop_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _tx_err_int_clr>]()
-                                    .set_bit()
-                                    .[<ch $num _tx_thr_event_int_clr>]()
-                                    .set_bit()
-                            )
-                        });
+                fn start_transmission(&self) {
+                    // Depending on the variant, other registers have to be used here
+                    cfg_if::cfg_if! {
+                        if #[cfg(any(esp32, esp32s2))] {
+                            conf1!($num).modify(|_, w| w.tx_start().set_bit());
+                        } else {
+                            unsafe{ &*RMT::PTR }.ch_tx_conf0[$num].modify(|_, w| w.tx_start().set_bit());
+                        }
                     }
                 }
 
-                // always enable tx wrap
-                #[cfg(any(esp32c3, esp32c6, esp32s3))]
-                unsafe { &*RMT::PTR }.ch_tx_conf0[$num].modify(|_, w| {
-                    w.mem_tx_wrap_en()
-                        .set_bit()
-                });
-
-                // apply configuration updates
-                #[cfg(any(esp32c3, esp32c6, esp32s3))]
-                unsafe { &*RMT::PTR }.ch_tx_conf0[$num].modify(|_, w| {
-                    w.conf_update()
-                        .set_bit()
-                });
-
-                // Depending on the variant, other registers have to be used here
-                cfg_if::cfg_if! {
-                    if #[cfg(any(esp32, esp32s2))] {
-                        conf1!($num).modify(|_, w| w.tx_start().set_bit());
-                    } else {
-                        unsafe{ &*RMT::PTR }.ch_tx_conf0[$num].modify(|_, w| w.tx_start().set_bit());
-                    }
-                }
+                // Check the state of a transmission and refill the RAM when needed,
+                // returns `true` once the transmission is done
+                fn poll_transmission(
+                    &mut self,
+                    sequence_iter: &mut Iter<u32>,
+                ) -> Result<bool, TransmissionError> {
+                    // Check for the interrupts being raised, either completion or error
+                    let interrupts = unsafe { &*RMT::PTR }.int_raw.read();
+
+                    match (
+                        unsafe { interrupts.ch_tx_end_int_raw($num).bit() },
+                        // The ESP32 variant does not support the loop functionality
+                        #[cfg(not(esp32))]
+                        unsafe {interrupts.ch_tx_loop_int_raw($num).bit()},
+                        #[cfg(esp32)]
+                        false,
+                        // The C3/S3 have a slightly different interrupt naming scheme
+                        #[cfg(any(esp32, esp32s2))]
+                        unsafe { interrupts.ch_err_int_raw($num).bit() },
+                        #[cfg(any(esp32c3, esp32c6, esp32s3))]
+                        unsafe { interrupts.ch_tx_err_int_raw($num).bit() },
+                        unsafe { interrupts.ch_tx_thr_event_int_raw($num).bit() },
+                    ) {
+                        // SingleShot completed and no error -> success
+                        (true, false, false, _) => Ok(true),
+                        // Sequence completed and no error -> success
+                        (false, true, false, _) => {
+                            // Stop transmitting (only necessary in sequence case)
+                            self.stop_transmission();
+                            Ok(true)
+                        }
+                        // Refill the buffer
+                        (false, false, false, true) => {
+                            self.channel.write_sequence(sequence_iter, CHANNEL_RAM_SIZE / 2);
 
-                // If we're in forever mode, we return right away, otherwise we wait
-                // for completion
-                if repeat_mode != RepeatMode::Forever {
-                    // Wait for interrupt being raised, either completion or error
-                    loop {
-                        let interrupts = unsafe { &*RMT::PTR }.int_raw.read();
+                            // Clear the threshold interrupt (write-through)
+                            unsafe { &*RMT::PTR }.int_clr.write(|w| {
+                                paste!(w.[<ch $num _tx_thr_event_int_clr>]().set_bit())
+                            });
 
-                        match (
+                            Ok(false)
+                        }
+                        // Neither completed nor error -> continue busy waiting
+                        (false, false, false, false) => Ok(false),
+                        // Anything else constitutes an error state
+                        _ => Err(TransmissionError::Failure(
                             unsafe { interrupts.ch_tx_end_int_raw($num).bit() },
                             // The ESP32 variant does not support the loop functionality
                             #[cfg(not(esp32))]
@@ -589,69 +1031,32 @@ macro_rules! channel_instance {
                             #[cfg(any(esp32c3, esp32c6, esp32s3))]
                             unsafe { interrupts.ch_tx_err_int_raw($num).bit() },
                             unsafe { interrupts.ch_tx_thr_event_int_raw($num).bit() },
-                        ) {
-                            // SingleShot completed and no error -> success
-                            (true, false, false, _) => break,
-                            // Sequence completed and no error -> success
-                            (false, true, false, _) => {
-                                // Stop transmitting (only necessary in sequence case)
-                                self.stop_transmission();
-                                break;
-                            }
-                            // Refill the buffer
-                            (false, false, false, true) => {
-                                self.channel.write_sequence(&mut sequence_iter, CHANNEL_RAM_SIZE / 2);
-
-                                // Clear the threshold interrupt (write-through)
-                                unsafe { &*RMT::PTR }.int_clr.write(|w| {
-                                    paste!(w.[<ch $num _tx_thr_event_int_clr>]().set_bit())
-                                });
-                        }
-                            // Neither completed nor error -> continue busy waiting
-                            (false, false, false, false) => (),
-                            // Anything else constitutes an error state
-                            _ => {
-                                return Err(TransmissionError::Failure(
-                                    unsafe { interrupts.ch_tx_end_int_raw($num).bit() },
-                                    // The ESP32 variant does not support the loop functionality
-                                    #[cfg(not(esp32))]
-                                    unsafe {interrupts.ch_tx_loop_int_raw($num).bit()},
-                                    #[cfg(esp32)]
-                                    false,
-                                    // The C3/S3 have a slightly different interrupt naming scheme
-                                    #[cfg(any(esp32, esp32s2))]
-                                    unsafe { interrupts.ch_err_int_raw($num).bit() },
-                                    #[cfg(any(esp32c3, esp32c6, esp32s3))]
-                                    unsafe { interrupts.ch_tx_err_int_raw($num).bit() },
-                                    unsafe { interrupts.ch_tx_thr_event_int_raw($num).bit() },
-                                ))
-                            }
-                        }
+                        )),
                     }
                 }
-
-                Ok(())
             }
 
-            /// Stop any ongoing (repetitive) transmission
-            ///
-            /// This function needs to be called to stop sending when
-            /// previously a sequence was sent with `RepeatMode::Forever`.
-            fn stop_transmission(&self) {
-                cfg_if::cfg_if! {
-                    if #[cfg(any(esp32c3, esp32c6, esp32s3))] {
-                        unsafe { &*RMT::PTR }
-                            .ch_tx_conf0[$num]
-                            .modify(|_, w| w.tx_stop().set_bit());
-                    }
-                    else if #[cfg(esp32s2)] {
-                        conf1!($num)
-                            .modify(|_, w| w.tx_stop().set_bit());
-                    }
-                    // The ESP32 variant does not have any way to stop a
-                    // transmission once it has been started!
-                };
-            }
+            #[cfg(not(esp32))]
+            impl<'d, P: OutputPin> SyncChannel for [<Configured $cxi>]<'d, P> {
+                fn channel_number(&self) -> u8 {
+                    $num
+                }
+
+                fn prepare(&mut self, sequence_iter: &mut Iter<u32>) -> Result<(), TransmissionError> {
+                    self.prepare_transmission(RepeatMode::SingleShot, sequence_iter)
+                }
+
+                fn start(&self) {
+                    self.start_transmission();
+                }
+
+                fn poll(&mut self, sequence_iter: &mut Iter<u32>) -> Result<bool, TransmissionError> {
+                    self.poll_transmission(sequence_iter)
+                }
+
+                fn stop(&self) {
+                    self.stop_transmission();
+                }
             }
 
         );
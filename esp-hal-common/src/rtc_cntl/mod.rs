@@ -3,11 +3,12 @@ use embedded_hal::watchdog::{Watchdog, WatchdogDisable, WatchdogEnable};
 use fugit::HertzU32;
 use fugit::MicrosDurationU64;
 
-use self::rtc::SocResetReason;
 #[cfg(not(esp32c6))]
 use crate::clock::{Clock, XtalClock};
 #[cfg(not(esp32))]
 use crate::efuse::Efuse;
+#[cfg(any(esp32c2, esp32c3))]
+use crate::gpio::{OutputPin, RTCPin};
 #[cfg(esp32c6)]
 use crate::peripherals::LP_WDT;
 #[cfg(not(esp32c6))]
@@ -29,9 +30,153 @@ type RtcCntl = crate::peripherals::RTC_CNTL;
 #[cfg_attr(esp32s2, path = "rtc/esp32s2.rs")]
 #[cfg_attr(esp32s3, path = "rtc/esp32s3.rs")]
 mod rtc;
+#[cfg(esp32c3)]
+pub mod wake_stub;
 
 #[cfg(esp32c6)]
 pub use rtc::RtcClock;
+/// The discriminants are the raw codes reported by the ROM. They differ between
+/// the chips, and so do the variants.
+pub use rtc::SocResetReason;
+
+/// Entry point of a deep sleep wake stub, see [`wake_stub`]
+#[cfg(esp32c3)]
+pub type WakeStub = unsafe extern "C" fn();
+
+/// Events that end a sleep, see [`Rtc::sleep_light`] and [`Rtc::sleep_deep`]
+#[cfg(esp32c3)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeupSource {
+    /// Any GPIO listening for a level with `wake_up_from_light_sleep` set, see
+    /// [`Pin::listen_with_options`](crate::gpio::Pin::listen_with_options)
+    ///
+    /// In light sleep the digital GPIO block stays powered, so every GPIO can
+    /// wake the chip. Deep sleep powers it down, use
+    /// [`WakeupSource::RtcGpio`] there.
+    Gpio,
+    /// The RTC timer, after the given time
+    Timer(MicrosDurationU64),
+    /// A level at any of the RTC GPIOs (GPIO0 to GPIO5), `pins` has bit `n`
+    /// set for GPIOn
+    ///
+    /// The pins have to be configured as inputs, with a pull resistor if
+    /// nothing drives them, before entering the sleep.
+    RtcGpio {
+        /// Bit mask of the pins
+        pins: u8,
+        /// Level that wakes the chip
+        level: WakeupLevel,
+    },
+}
+
+/// Level of an RTC GPIO that ends a sleep, see [`WakeupSource::RtcGpio`]
+#[cfg(esp32c3)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeupLevel {
+    /// Wake up while the pin is low
+    Low,
+    /// Wake up while the pin is high
+    High,
+}
+
+/// Reasons a sleep can't be entered, see [`Rtc::sleep_light`] and
+/// [`Rtc::sleep_deep`]
+#[cfg(esp32c3)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepError {
+    /// No wakeup source was given, the chip would sleep forever
+    NoWakeupSource,
+    /// The wakeup source can't end this kind of sleep, or selects no or
+    /// other pins than the RTC GPIOs
+    UnsupportedWakeupSource(WakeupSource),
+}
+
+/// What ended the last deep sleep, see [`get_wakeup_cause`]
+#[cfg(esp32c3)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeupCause {
+    /// The RTC timer
+    Timer,
+    /// An RTC GPIO, with bit `n` set if GPIOn had its level
+    RtcGpio(u8),
+    /// A source this driver doesn't configure
+    Other,
+}
+
+// RTC_CNTL_WAKEUP_ENA bits
+#[cfg(esp32c3)]
+const GPIO_WAKEUP: u32 = 1 << 2;
+#[cfg(esp32c3)]
+const TIMER_WAKEUP: u32 = 1 << 3;
+
+// RTC_CNTL_GPIO_WAKEUP_REG, pin n has its enable bit at 31 - n and its
+// interrupt type at 23 - 3 * n
+#[cfg(esp32c3)]
+const RTC_CNTL_GPIO_WAKEUP_REG: usize = 0x110;
+#[cfg(esp32c3)]
+const GPIO_PIN_CLK_GATE: u32 = 1 << 7;
+#[cfg(esp32c3)]
+const GPIO_WAKEUP_STATUS_CLR: u32 = 1 << 6;
+#[cfg(esp32c3)]
+const GPIO_WAKEUP_STATUS: u32 = 0x3f;
+
+/// Voltage of the VDD_SDIO supply, which powers the flash and PSRAM
+#[cfg(esp32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashVoltage {
+    /// The internal regulator outputs 1.8 V
+    V1_8,
+    /// VDD_SDIO is connected to VDD3P3_RTC through a resistor
+    V3_3,
+}
+
+/// Origin of the flash voltage configuration, see [`Rtc::flash_voltage`]
+#[cfg(esp32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashVoltageSource {
+    /// The MTDI (GPIO12) strapping pin, sampled at reset
+    Strap,
+    /// The `XPD_SDIO_REG`/`SDIO_TIEH` eFuses, which override the strapping
+    /// pin once `SDIO_FORCE` is burned
+    Efuse,
+    /// Forced at runtime in `RTC_CNTL`, see [`Rtc::lock_flash_voltage`]
+    Rtc,
+}
+
+/// Flash voltage configuration, see [`Rtc::flash_voltage`]
+#[cfg(esp32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashVoltageConfig {
+    /// The voltage selected for VDD_SDIO
+    pub voltage: FlashVoltage,
+    /// Whether VDD_SDIO is powered by the chip at all. If not, the flash has
+    /// to be supplied externally.
+    pub enabled: bool,
+    /// Where the configuration comes from
+    pub source: FlashVoltageSource,
+}
+
+// RTC_CNTL_SDIO_CONF_REG bits
+#[cfg(esp32)]
+const SDIO_XPD: u32 = 1 << 31;
+#[cfg(esp32)]
+const SDIO_TIEH: u32 = 1 << 23;
+#[cfg(esp32)]
+const SDIO_FORCE: u32 = 1 << 22;
+#[cfg(esp32)]
+const SDIO_REG_PD_EN: u32 = 1 << 21;
+
+// EFUSE_BLK0_RDATA4_REG bits
+#[cfg(esp32)]
+const EFUSE_XPD_SDIO_REG: u32 = 1 << 14;
+#[cfg(esp32)]
+const EFUSE_SDIO_TIEH: u32 = 1 << 15;
+#[cfg(esp32)]
+const EFUSE_SDIO_FORCE: u32 = 1 << 16;
+
+// GPIO_STRAP_REG bit of MTDI
+#[cfg(esp32)]
+const STRAP_MTDI: u32 = 1 << 5;
 
 extern "C" {
     #[allow(dead_code)]
@@ -42,8 +187,8 @@ extern "C" {
 #[cfg(not(esp32c6))]
 #[allow(unused)]
 #[derive(Debug, Clone, Copy)]
-/// RTC SLOW_CLK frequency values
-pub(crate) enum RtcFastClock {
+/// RTC FAST_CLK frequency values
+pub enum RtcFastClock {
     /// Main XTAL, divided by 4
     RtcFastClockXtalD4 = 0,
     /// Internal fast RC oscillator
@@ -63,11 +208,19 @@ impl Clock for RtcFastClock {
     }
 }
 
+/// Error returned by [`Rtc::set_slow_clock`]
+#[cfg(not(esp32c6))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockError {
+    /// The clock source isn't supported by this driver yet
+    UnsupportedClock,
+}
+
 #[cfg(not(esp32c6))]
 #[allow(unused)]
 #[derive(Debug, Clone, Copy)]
 /// RTC SLOW_CLK frequency values
-pub(crate) enum RtcSlowClock {
+pub enum RtcSlowClock {
     /// Internal slow RC oscillator
     RtcSlowClockRtc     = 0,
     /// External 32 KHz XTAL
@@ -141,6 +294,543 @@ impl<'d> Rtc<'d> {
     pub fn estimate_xtal_frequency(&mut self) -> u32 {
         RtcClock::estimate_xtal_frequency()
     }
+
+    /// Power up the internal RC_FAST oscillator
+    ///
+    /// The oscillator runs at roughly 8 MHz on the ESP32 and ESP32-S2 and
+    /// 17.5 MHz on the other chips, with a large part-to-part and temperature
+    /// dependent deviation of several percent. Use
+    /// [`Rtc::rc_fast_frequency`] to get the actual frequency.
+    ///
+    /// `divided` additionally enables the divide-by-256 output, which can be
+    /// used as RTC_SLOW_CLK source.
+    #[cfg(not(esp32c6))]
+    pub fn enable_rc_fast(&mut self, divided: bool) {
+        RtcClock::enable_8m(true, divided);
+    }
+
+    /// Power down the RC_FAST oscillator and its divider
+    ///
+    /// Parts still selected as RTC_FAST_CLK or RTC_SLOW_CLK source are kept
+    /// running.
+    #[cfg(not(esp32c6))]
+    pub fn disable_rc_fast(&mut self) {
+        let divider_used = matches!(RtcClock::get_slow_freq(), RtcSlowClock::RtcSlowClock8mD256);
+        let osc_used =
+            divider_used || matches!(RtcClock::get_fast_freq(), RtcFastClock::RtcFastClock8m);
+
+        RtcClock::enable_8m(osc_used, divider_used);
+    }
+
+    /// Select the source of RTC_FAST_CLK
+    #[cfg(not(esp32c6))]
+    pub fn set_fast_clock(&mut self, clock: RtcFastClock) {
+        if matches!(clock, RtcFastClock::RtcFastClock8m) {
+            RtcClock::enable_8m(true, RtcClock::is_8m_d256_enabled());
+        }
+
+        RtcClock::set_fast_freq(clock);
+    }
+
+    /// Select the source of RTC_SLOW_CLK and calibrate it
+    ///
+    /// The RTC timer, the RTC watchdog and the sleep timers run from
+    /// RTC_SLOW_CLK. The new clock is calibrated against the XTAL, so
+    /// durations stay (mostly) accurate after switching.
+    ///
+    /// Returns [`ClockError::UnsupportedClock`] for the external 32 kHz
+    /// crystal, which isn't supported yet. The slow clock isn't changed then.
+    #[cfg(not(esp32c6))]
+    pub fn set_slow_clock(&mut self, clock: RtcSlowClock) -> Result<(), ClockError> {
+        if matches!(clock, RtcSlowClock::RtcSlowClock32kXtal) {
+            return Err(ClockError::UnsupportedClock);
+        }
+
+        if matches!(clock, RtcSlowClock::RtcSlowClock8mD256) {
+            RtcClock::enable_8m(true, true);
+        }
+
+        let cal_val = loop {
+            RtcClock::set_slow_freq(clock);
+
+            let res = RtcClock::calibrate(RtcCalSel::RtcCalRtcMux, 1024);
+            if res != 0 {
+                break res;
+            }
+        };
+
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+        rtc_cntl.store1.write(|w| unsafe { w.bits(cal_val) });
+
+        Ok(())
+    }
+
+    /// Measure the frequency of the RC_FAST oscillator
+    ///
+    /// The divided RC_FAST clock is counted against the XTAL for 100 cycles,
+    /// which takes about 1.5 ms (3 ms on the ESP32 and ESP32-S2). The
+    /// oscillator is powered up for the measurement if needed. As it drifts
+    /// with temperature, measure again if it's used over a long period of
+    /// time.
+    #[cfg(not(esp32c6))]
+    pub fn rc_fast_frequency(&mut self) -> HertzU32 {
+        const CAL_CYCLES: u32 = 100;
+
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+        let clk_8m_enabled = rtc_cntl.clk_conf.read().enb_ck8m().bit_is_clear();
+        let clk_8md256_enabled = RtcClock::is_8m_d256_enabled();
+
+        if !clk_8md256_enabled {
+            RtcClock::enable_8m(true, true);
+        }
+
+        // XTAL cycles per RC_FAST/256 cycle, Q13.19
+        let ratio = RtcClock::get_calibration_ratio(RtcCalSel::RtcCal8mD256, CAL_CYCLES) as u64;
+        let xtal_hz = RtcClock::get_xtal_freq().hz() as u64;
+
+        RtcClock::enable_8m(clk_8m_enabled, clk_8md256_enabled);
+
+        HertzU32::Hz((((xtal_hz * 256) << RtcClock::CAL_FRACT) / ratio) as u32)
+    }
+
+    /// The current configuration of the flash supply voltage (VDD_SDIO)
+    ///
+    /// Only reads registers, it is always safe to call. The result tells
+    /// which voltage the flash is running at and why, e.g. to check that a
+    /// board with 3.3 V flash doesn't depend on the level of GPIO12 at reset.
+    #[cfg(esp32)]
+    pub fn flash_voltage(&self) -> FlashVoltageConfig {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+        let efuse = unsafe { &*crate::peripherals::EFUSE::PTR };
+        let gpio = unsafe { &*crate::peripherals::GPIO::PTR };
+
+        let sdio_conf = rtc_cntl.sdio_conf.read().bits();
+        let efuse_conf = efuse.blk0_rdata4.read().bits();
+
+        let (tieh, enabled, source) = if sdio_conf & SDIO_FORCE != 0 {
+            (
+                sdio_conf & SDIO_TIEH != 0,
+                sdio_conf & SDIO_XPD != 0,
+                FlashVoltageSource::Rtc,
+            )
+        } else if efuse_conf & EFUSE_SDIO_FORCE != 0 {
+            (
+                efuse_conf & EFUSE_SDIO_TIEH != 0,
+                efuse_conf & EFUSE_XPD_SDIO_REG != 0,
+                FlashVoltageSource::Efuse,
+            )
+        } else {
+            // a high MTDI selects 1.8 V
+            (
+                gpio.strap.read().bits() & STRAP_MTDI == 0,
+                true,
+                FlashVoltageSource::Strap,
+            )
+        };
+
+        FlashVoltageConfig {
+            voltage: if tieh {
+                FlashVoltage::V3_3
+            } else {
+                FlashVoltage::V1_8
+            },
+            enabled,
+            source,
+        }
+    }
+
+    /// Force the current flash voltage configuration in `RTC_CNTL`
+    ///
+    /// The voltage doesn't change, so this is safe while running from flash.
+    /// It only stops the configuration from depending on the strapping pin
+    /// or eFuses until the next power-on. Does nothing if the chip doesn't
+    /// power VDD_SDIO.
+    #[cfg(esp32)]
+    pub fn lock_flash_voltage(&mut self) {
+        let config = self.flash_voltage();
+
+        if config.enabled {
+            // safety: the voltage stays what the flash is running at
+            unsafe { self.set_flash_voltage(config.voltage) };
+        }
+    }
+
+    /// Force the flash supply voltage (VDD_SDIO) to `voltage`
+    ///
+    /// **Extreme caution is required.** The code is executed from the very
+    /// flash supplied by this voltage: switching a 3.3 V flash to 1.8 V
+    /// crashes the chip immediately, switching a 1.8 V flash (or PSRAM) to
+    /// 3.3 V can permanently damage it. Only use this on boards with
+    /// non-standard flash wiring where the required voltage is known, and
+    /// prefer [`Rtc::lock_flash_voltage`] where it is enough.
+    ///
+    /// The setting lives in `RTC_CNTL`, it persists through deep sleep and
+    /// software resets, but a power-on reset always goes back to the strapping
+    /// pin and eFuses. This never burns eFuses, so a wrong value can't
+    /// prevent booting after a power cycle.
+    ///
+    /// # Safety
+    ///
+    /// The flash, the PSRAM and anything else connected to VDD_SDIO have to
+    /// support `voltage`.
+    #[cfg(esp32)]
+    pub unsafe fn set_flash_voltage(&mut self, voltage: FlashVoltage) {
+        let rtc_cntl = &*RTC_CNTL::PTR;
+
+        rtc_cntl.sdio_conf.modify(|r, w| {
+            let mut bits = r.bits() | SDIO_FORCE | SDIO_XPD | SDIO_REG_PD_EN;
+            match voltage {
+                FlashVoltage::V1_8 => bits &= !SDIO_TIEH,
+                FlashVoltage::V3_3 => bits |= SDIO_TIEH,
+            }
+            w.bits(bits)
+        });
+    }
+
+    /// Latch the current output level of an RTC-capable pin (GPIO0 to GPIO5)
+    ///
+    /// The hold is implemented in the RTC domain, so the pad keeps its level
+    /// during deep sleep and through the reset that follows it (or any other
+    /// reset not affecting the RTC domain). While held, writes to the pin have
+    /// no effect on the pad.
+    ///
+    /// After waking up, firmware has to reconfigure the pin to the same level
+    /// and then call [`Rtc::release_hold`], otherwise the pin stays latched
+    /// forever. [`Rtc::held_pins`] tells which pins are still held at boot.
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn hold_output<P: OutputPin + RTCPin>(&mut self, pin: &mut P) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+        let mask = Self::rtc_pad_mask(pin.number());
+
+        rtc_cntl
+            .pad_hold
+            .modify(|r, w| unsafe { w.bits(r.bits() | mask) });
+    }
+
+    /// Release the hold previously set by [`Rtc::hold_output`]
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn release_hold<P: OutputPin + RTCPin>(&mut self, pin: &mut P) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+        let mask = Self::rtc_pad_mask(pin.number());
+
+        rtc_cntl
+            .pad_hold
+            .modify(|r, w| unsafe { w.bits(r.bits() & !mask) });
+    }
+
+    /// Check whether `pin` is currently latched by [`Rtc::hold_output`]
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn is_held<P: OutputPin + RTCPin>(&self, pin: &P) -> bool {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        rtc_cntl.pad_hold.read().bits() & Self::rtc_pad_mask(pin.number()) != 0
+    }
+
+    /// Bit mask of the GPIOs currently latched by [`Rtc::hold_output`]
+    ///
+    /// Bit `n` is set if GPIOn is held. Holds survive deep sleep and resets
+    /// that don't affect the RTC domain, so this is meant to be checked early
+    /// at boot, before any pins are taken and reconfigured: writes to a held
+    /// pin silently have no effect on the pad.
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn held_pins(&self) -> u32 {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        rtc_cntl.pad_hold.read().bits() & Self::RTC_PAD_HOLD_MASK
+    }
+
+    /// Release the hold of all RTC-capable pins
+    ///
+    /// Convenience for firmware that doesn't care about the levels latched
+    /// before deep sleep or a reset. The pads switch to whatever the GPIO
+    /// configuration drives at the time of the call, so pins that must not
+    /// glitch should be reconfigured first and released individually with
+    /// [`Rtc::release_hold`].
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn clear_all_holds(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        rtc_cntl
+            .pad_hold
+            .modify(|r, w| unsafe { w.bits(r.bits() & !Self::RTC_PAD_HOLD_MASK) });
+    }
+
+    /// Register a function the ROM runs when waking up from deep sleep
+    ///
+    /// The stub runs before the application is loaded, see [`wake_stub`] for
+    /// the constraints it has to follow. Passing `None` removes a previously
+    /// registered stub.
+    #[cfg(esp32c3)]
+    pub fn set_wake_stub(&mut self, stub: Option<WakeStub>) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+        let addr = stub.map(|stub| stub as usize as u32).unwrap_or(0);
+
+        // RTC_ENTRY_ADDR_REG
+        rtc_cntl.store6.write(|w| unsafe { w.bits(addr) });
+    }
+
+    /// Why `cpu` was last reset, see [`get_reset_reason`]
+    pub fn reset_reason(&self, cpu: Cpu) -> Option<SocResetReason> {
+        get_reset_reason(cpu)
+    }
+
+    /// What woke the chip from deep sleep, see [`get_wakeup_cause`]
+    ///
+    /// Only available on the ESP32-C3, the only chip with sleep modes so far.
+    #[cfg(esp32c3)]
+    pub fn wakeup_cause(&self) -> Option<WakeupCause> {
+        get_wakeup_cause()
+    }
+
+    /// Convert `duration` to RTC slow clock cycles
+    ///
+    /// Uses the slow clock calibration done in [`Rtc::new`]. The result can be
+    /// stored in RTC fast memory for use by [`wake_stub::sleep`].
+    #[cfg(esp32c3)]
+    pub fn duration_to_ticks(&self, duration: MicrosDurationU64) -> u64 {
+        slow_clock_ticks(duration)
+    }
+
+    /// Time elapsed since the chip was powered on
+    ///
+    /// Reads the free-running RTC timer, which is clocked by RTC_SLOW_CLK and
+    /// only starts over on a power-on or brownout reset. Software resets,
+    /// watchdog resets and deep sleep don't stop it, so the result covers the
+    /// total time since the last cold boot, not just since the current
+    /// application started.
+    ///
+    /// The ticks are converted with the slow clock calibration done in
+    /// [`Rtc::new`], so the accuracy is that of the calibration, a few percent
+    /// with the internal RC oscillator.
+    #[cfg(any(esp32c2, esp32c3, esp32s2, esp32s3))]
+    pub fn uptime_since_power_on(&self) -> MicrosDurationU64 {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        // latch and read the current RTC time
+        rtc_cntl.time_update.write(|w| w.time_update().set_bit());
+        let ticks = ((rtc_cntl.time_high0.read().timer_value0_high().bits() as u64) << 32)
+            | rtc_cntl.time_low0.read().timer_value0_low().bits() as u64;
+
+        // period of the slow clock in microseconds, Q13.19 fixed point
+        let cal = rtc_cntl.store1.read().bits() as u64;
+
+        // split the multiplication to stay within 64 bits, the timer has 48
+        let frac_mask = (1 << RtcClock::CAL_FRACT) - 1;
+        let micros = (ticks >> RtcClock::CAL_FRACT) * cal
+            + (((ticks & frac_mask) * cal) >> RtcClock::CAL_FRACT);
+
+        MicrosDurationU64::micros(micros)
+    }
+
+    /// Enter deep sleep until one of `wakeup_sources` triggers
+    ///
+    /// Supports [`WakeupSource::Timer`] and [`WakeupSource::RtcGpio`], the
+    /// digital GPIOs of [`WakeupSource::Gpio`] are powered down. Only the RTC
+    /// domain keeps running, RAM and all peripheral state is lost: waking up
+    /// resets the chip, which runs the registered wake stub (if any) and then
+    /// boots the application again. Data that has to survive goes into RTC
+    /// memory, and [`get_wakeup_cause`] tells what ended the sleep.
+    ///
+    /// Only returns if the sleep can't be entered: with
+    /// [`SleepError::NoWakeupSource`] for an empty `wakeup_sources`, and with
+    /// [`SleepError::UnsupportedWakeupSource`] for [`WakeupSource::Gpio`] or
+    /// pins other than the RTC GPIOs. Nothing is configured in that case.
+    #[cfg(esp32c3)]
+    pub fn sleep_deep(&mut self, wakeup_sources: &[WakeupSource]) -> SleepError {
+        if let Err(err) = Self::check_wakeup_sources(wakeup_sources, true) {
+            return err;
+        }
+
+        let mut wakeup_ena = 0;
+        for source in wakeup_sources {
+            match *source {
+                WakeupSource::Timer(duration) => {
+                    let ticks = self.duration_to_ticks(duration);
+                    unsafe { wake_stub::set_sleep_timer(ticks) };
+                    wakeup_ena |= TIMER_WAKEUP;
+                }
+                WakeupSource::RtcGpio { pins, level } => {
+                    Self::set_rtc_gpio_wakeup(pins, level);
+                    wakeup_ena |= GPIO_WAKEUP;
+                }
+                WakeupSource::Gpio => unreachable!(),
+            }
+        }
+
+        unsafe { wake_stub::power_down(wakeup_ena) }
+    }
+
+    #[cfg(esp32c3)]
+    fn check_wakeup_sources(wakeup_sources: &[WakeupSource], deep: bool) -> Result<(), SleepError> {
+        if wakeup_sources.is_empty() {
+            return Err(SleepError::NoWakeupSource);
+        }
+
+        for source in wakeup_sources {
+            let supported = match *source {
+                WakeupSource::Gpio => !deep,
+                WakeupSource::Timer(_) => true,
+                WakeupSource::RtcGpio { pins, .. } => {
+                    pins != 0 && pins as u32 & !Self::RTC_PAD_HOLD_MASK == 0
+                }
+            };
+            if !supported {
+                return Err(SleepError::UnsupportedWakeupSource(*source));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(esp32c3)]
+    fn set_rtc_gpio_wakeup(pins: u8, level: WakeupLevel) {
+        // GPIO_INTR_LOW_LEVEL / GPIO_INTR_HIGH_LEVEL
+        let int_type = match level {
+            WakeupLevel::Low => 4,
+            WakeupLevel::High => 5,
+        };
+        let mut value = GPIO_PIN_CLK_GATE;
+        for pin in 0..6 {
+            if pins & (1 << pin) != 0 {
+                value |= 1 << (31 - pin) | int_type << (23 - 3 * pin);
+            }
+        }
+
+        let reg = (RTC_CNTL::PTR as usize + RTC_CNTL_GPIO_WAKEUP_REG) as *mut u32;
+        unsafe {
+            // clear the status of the previous wakeup
+            reg.write_volatile(value | GPIO_WAKEUP_STATUS_CLR);
+            reg.write_volatile(value);
+        }
+    }
+
+    /// Enter light sleep until one of `wakeup_sources` triggers
+    ///
+    /// Unlike deep sleep, RAM, the CPU state and the peripheral configuration
+    /// are kept: the CPU clock is gated and execution continues after this
+    /// call once the chip wakes up. If a wakeup condition already holds when
+    /// entering, the sleep is rejected and this returns right away, so a GPIO
+    /// level used for waking has to go back to its idle level before sleeping
+    /// again.
+    ///
+    /// Supports all wakeup sources. Use [`get_gpio_wakeup_status`] afterwards
+    /// to find out which GPIO of [`WakeupSource::Gpio`] woke the chip. The
+    /// RTC timer is disarmed again when another source ended the sleep first.
+    ///
+    /// Returns [`SleepError::NoWakeupSource`] without sleeping for an empty
+    /// `wakeup_sources`, and [`SleepError::UnsupportedWakeupSource`] for an
+    /// [`WakeupSource::RtcGpio`] with pins other than the RTC GPIOs.
+    ///
+    /// The CPU and APB clocks are the same after waking up, so drivers keep
+    /// working without being reconfigured. The SYSTIMER stops while sleeping
+    /// if the XTAL is powered down. The time slept is measured with the RTC
+    /// timer, and if the SYSTIMER counted less than half of it, unit 0 is
+    /// moved forward by the difference. [`SystemTimer::now`], its alarms and
+    /// the embassy time driver based on it therefore keep up with the wall
+    /// clock, within the accuracy of the slow clock calibration. The TIMG
+    /// timers are not corrected.
+    ///
+    /// [`SystemTimer::now`]: crate::systimer::SystemTimer::now
+    #[cfg(esp32c3)]
+    pub fn sleep_light(&mut self, wakeup_sources: &[WakeupSource]) -> Result<(), SleepError> {
+        use crate::systimer::SystemTimer;
+
+        Self::check_wakeup_sources(wakeup_sources, false)?;
+
+        let mut wakeup_ena = 0;
+        for source in wakeup_sources {
+            match *source {
+                WakeupSource::Gpio => wakeup_ena |= GPIO_WAKEUP,
+                WakeupSource::Timer(duration) => {
+                    let ticks = self.duration_to_ticks(duration);
+                    unsafe { wake_stub::set_sleep_timer(ticks) };
+                    wakeup_ena |= TIMER_WAKEUP;
+                }
+                WakeupSource::RtcGpio { pins, level } => {
+                    Self::set_rtc_gpio_wakeup(pins, level);
+                    wakeup_ena |= GPIO_WAKEUP;
+                }
+            }
+        }
+
+        let rtc_before = self.uptime_since_power_on();
+        let systimer_before = SystemTimer::now();
+
+        Self::enter_light_sleep(wakeup_ena);
+
+        if wakeup_ena & TIMER_WAKEUP != 0 {
+            // don't leave the alarm armed when another source was first
+            let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+            rtc_cntl
+                .slp_timer1
+                .modify(|_, w| w.main_timer_alarm_en().clear_bit());
+        }
+
+        let slept = (self.uptime_since_power_on() - rtc_before).to_micros();
+        let counted = SystemTimer::ticks_between(systimer_before, SystemTimer::now())
+            / (SystemTimer::TICKS_PER_SECOND / 1_000_000);
+        if counted < slept / 2 {
+            SystemTimer::advance((slept - counted) * (SystemTimer::TICKS_PER_SECOND / 1_000_000));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(esp32c3)]
+    #[procmacros::ram]
+    fn enter_light_sleep(wakeup_ena: u32) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        // keep the digital domain powered, RAM and peripherals are retained
+        rtc_cntl
+            .dig_pwc
+            .modify(|_, w| w.dg_wrap_pd_en().clear_bit());
+
+        rtc_cntl
+            .wakeup_state
+            .modify(|_, w| unsafe { w.wakeup_ena().bits(wakeup_ena) });
+        rtc_cntl.slp_reject_conf.modify(|_, w| unsafe {
+            w.light_slp_reject_en()
+                .set_bit()
+                .sleep_reject_ena()
+                .bits(wakeup_ena)
+        });
+
+        rtc_cntl.int_clr_rtc.write(|w| {
+            w.slp_reject_int_clr()
+                .set_bit()
+                .slp_wakeup_int_clr()
+                .set_bit()
+        });
+
+        rtc_cntl.state0.modify(|_, w| w.sleep_en().set_bit());
+
+        // the CPU stalls here while sleeping
+        loop {
+            let raw = rtc_cntl.int_raw_rtc.read();
+            if raw.slp_wakeup_int_raw().bit_is_set() || raw.slp_reject_int_raw().bit_is_set() {
+                break;
+            }
+        }
+
+        rtc_cntl.int_clr_rtc.write(|w| {
+            w.slp_reject_int_clr()
+                .set_bit()
+                .slp_wakeup_int_clr()
+                .set_bit()
+        });
+    }
+
+    // Hold bits of GPIO0 to GPIO5 in RTC_CNTL_PAD_HOLD
+    #[cfg(any(esp32c2, esp32c3))]
+    const RTC_PAD_HOLD_MASK: u32 = 0x3f;
+
+    #[cfg(any(esp32c2, esp32c3))]
+    fn rtc_pad_mask(gpio_num: u8) -> u32 {
+        // the pad hold bits of GPIO0 to GPIO5 are the first ones, `RTCPin`
+        // guarantees one of these
+        1 << gpio_num
+    }
 }
 
 #[cfg(not(esp32c6))]
@@ -190,6 +880,14 @@ impl RtcClock {
         }
     }
 
+    /// Check whether the 8 MHz / 256 divider is enabled
+    #[cfg(not(esp32c6))]
+    fn is_8m_d256_enabled() -> bool {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        rtc_cntl.clk_conf.read().enb_ck8m_div().bit_is_clear()
+    }
+
     #[cfg(esp32c6)]
     fn enable_8m(clk_8m_en: bool, _d256_en: bool) {
         let pmu = unsafe { &*PMU::PTR };
@@ -209,6 +907,30 @@ impl RtcClock {
     /// This is the value stored in RTC register RTC_XTAL_FREQ_REG by the
     /// bootloader, as passed to rtc_clk_init function.
     fn get_xtal_freq() -> XtalClock {
+        RtcClock::stored_xtal_freq().unwrap_or(XtalClock::RtcXtalFreq40M)
+    }
+
+    /// Detect the main XTAL frequency
+    ///
+    /// Uses the value stored in RTC_XTAL_FREQ_REG by the bootloader if it is
+    /// valid. Otherwise the XTAL is measured against the internal 8 MHz
+    /// oscillator and the result is rounded to the closest crystal frequency
+    /// supported by the chip (26 MHz or 40 MHz on the ESP32 and ESP32-C2, 40
+    /// MHz everywhere else).
+    pub(crate) fn detect_xtal_freq() -> XtalClock {
+        if let Some(xtal_freq) = RtcClock::stored_xtal_freq() {
+            return xtal_freq;
+        }
+
+        match RtcClock::estimate_xtal_frequency() {
+            #[cfg(any(esp32, esp32c2))]
+            0..=32 => XtalClock::RtcXtalFreq26M,
+            _ => XtalClock::RtcXtalFreq40M,
+        }
+    }
+
+    /// Read the XTAL frequency stored in RTC_XTAL_FREQ_REG, if it is valid
+    fn stored_xtal_freq() -> Option<XtalClock> {
         #[cfg(esp32c6)]
         let xtal_freq_reg = unsafe { &*LP_AON::PTR }.store4.read().bits();
         #[cfg(not(esp32c6))]
@@ -223,10 +945,10 @@ impl RtcClock {
         let reg_val_to_clk_val = |val| val & u16::MAX as u32;
 
         if !clk_val_is_valid(xtal_freq_reg) {
-            return XtalClock::RtcXtalFreq40M;
+            return None;
         }
 
-        match reg_val_to_clk_val(xtal_freq_reg) {
+        Some(match reg_val_to_clk_val(xtal_freq_reg) {
             40 => XtalClock::RtcXtalFreq40M,
             #[cfg(any(esp32c3, esp32s3))]
             32 => XtalClock::RtcXtalFreq32M,
@@ -235,7 +957,7 @@ impl RtcClock {
             #[cfg(esp32)]
             24 => XtalClock::RtcXtalFreq24M,
             other => XtalClock::RtcXtalFreqOther(other),
-        }
+        })
     }
 
     /// Get the RTC_SLOW_CLK source
@@ -316,6 +1038,18 @@ impl RtcClock {
         };
     }
 
+    /// Get the currently used RTC_FAST_CLK source
+    #[cfg(not(esp32c6))]
+    fn get_fast_freq() -> RtcFastClock {
+        let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+        if rtc_cntl.clk_conf.read().fast_clk_rtc_sel().bit_is_set() {
+            RtcFastClock::RtcFastClock8m
+        } else {
+            RtcFastClock::RtcFastClockXtalD4
+        }
+    }
+
     #[cfg(esp32c6)]
     fn set_fast_freq(fast_freq: RtcFastClock) {
         todo!()
@@ -533,7 +1267,7 @@ impl RtcClock {
 
         let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
         let clk_8m_enabled = rtc_cntl.clk_conf.read().enb_ck8m().bit_is_clear();
-        let clk_8md256_enabled = rtc_cntl.clk_conf.read().enb_ck8m_div().bit_is_clear();
+        let clk_8md256_enabled = RtcClock::is_8m_d256_enabled();
 
         if !clk_8md256_enabled {
             RtcClock::enable_8m(true, true);
@@ -582,20 +1316,43 @@ impl Default for Rwdt {
 
 /// RTC Watchdog Timer driver
 impl Rwdt {
+    /// Raise an interrupt whenever the watchdog expires, without resetting
+    /// the system
     pub fn listen(&mut self) {
+        self.listen_with(RwdtStageAction::RwdtStageActionOff);
+    }
+
+    /// Raise an interrupt when the watchdog expires, resetting the system
+    /// and the RTC only if it isn't fed within another timeout period
+    ///
+    /// Like [`Wdt::listen`] for the MWDT, this gives a handler the chance to
+    /// log diagnostics about a hang, with the same restrictions on what the
+    /// handler may do. The timeout of the second stage is set by `start`.
+    ///
+    /// [`Wdt::listen`]: crate::timer::Wdt::listen
+    pub fn listen_before_reset(&mut self) {
+        self.listen_with(RwdtStageAction::RwdtStageActionResetRtc);
+    }
+
+    fn listen_with(&mut self, stg1_action: RwdtStageAction) {
         #[cfg(not(esp32c6))]
         let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
         #[cfg(esp32c6)]
         let rtc_cntl = unsafe { &*LP_WDT::PTR };
 
         self.stg0_action = RwdtStageAction::RwdtStageActionInterrupt;
+        self.stg1_action = stg1_action;
 
         self.set_write_protection(false);
 
-        // Configure STAGE0 to trigger an interrupt upon expiration
-        rtc_cntl
-            .wdtconfig0
-            .modify(|_, w| unsafe { w.wdt_stg0().bits(self.stg0_action as u8) });
+        // Configure STAGE0 to trigger an interrupt upon expiration, followed by
+        // STAGE1 if it's enabled
+        rtc_cntl.wdtconfig0.modify(|_, w| unsafe {
+            w.wdt_stg0()
+                .bits(self.stg0_action as u8)
+                .wdt_stg1()
+                .bits(self.stg1_action as u8)
+        });
 
         #[cfg(esp32)]
         rtc_cntl.int_ena.modify(|_, w| w.wdt_int_ena().set_bit());
@@ -614,13 +1371,17 @@ impl Rwdt {
         let rtc_cntl = unsafe { &*LP_WDT::PTR };
 
         self.stg0_action = RwdtStageAction::RwdtStageActionResetRtc;
+        self.stg1_action = RwdtStageAction::RwdtStageActionOff;
 
         self.set_write_protection(false);
 
         // Configure STAGE0 to reset the main system and the RTC upon expiration.
-        rtc_cntl
-            .wdtconfig0
-            .modify(|_, w| unsafe { w.wdt_stg0().bits(self.stg0_action as u8) });
+        rtc_cntl.wdtconfig0.modify(|_, w| unsafe {
+            w.wdt_stg0()
+                .bits(self.stg0_action as u8)
+                .wdt_stg1()
+                .bits(self.stg1_action as u8)
+        });
 
         #[cfg(esp32)]
         rtc_cntl.int_ena.modify(|_, w| w.wdt_int_ena().clear_bit());
@@ -709,22 +1470,40 @@ impl WatchdogEnable for Rwdt {
         self.set_write_protection(false);
 
         unsafe {
+            // STAGE1 only runs after `listen_before_reset`, it gets the same timeout
             #[cfg(esp32)]
-            rtc_cntl
-                .wdtconfig1
-                .modify(|_, w| w.wdt_stg0_hold().bits(timeout_raw));
+            {
+                rtc_cntl
+                    .wdtconfig1
+                    .modify(|_, w| w.wdt_stg0_hold().bits(timeout_raw));
+                rtc_cntl
+                    .wdtconfig2
+                    .modify(|_, w| w.wdt_stg1_hold().bits(timeout_raw));
+            }
 
             #[cfg(esp32c6)]
-            (&*LP_WDT::PTR).config1.modify(|_, w| {
-                w.wdt_stg0_hold()
-                    .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
-            });
+            {
+                (&*LP_WDT::PTR).config1.modify(|_, w| {
+                    w.wdt_stg0_hold()
+                        .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
+                });
+                (&*LP_WDT::PTR).config2.modify(|_, w| {
+                    w.wdt_stg1_hold()
+                        .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
+                });
+            }
 
             #[cfg(not(any(esp32, esp32c6)))]
-            rtc_cntl.wdtconfig1.modify(|_, w| {
-                w.wdt_stg0_hold()
-                    .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
-            });
+            {
+                rtc_cntl.wdtconfig1.modify(|_, w| {
+                    w.wdt_stg0_hold()
+                        .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
+                });
+                rtc_cntl.wdtconfig2.modify(|_, w| {
+                    w.wdt_stg1_hold()
+                        .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
+                });
+            }
 
             rtc_cntl.wdtconfig0.modify(|_, w| {
                 w.wdt_stg0()
@@ -804,9 +1583,115 @@ impl WatchdogDisable for Swd {
     }
 }
 
+/// Why `cpu` was last reset
+///
+/// The raw code is read from the RTC reset state register through the ROM and
+/// mapped to [`SocResetReason`]. The codes are chip specific, so are the
+/// variants: e.g. the ESP32 reports a brownout as `SysBrownOut` (0x0F) like
+/// the ESP32-C3, but the ESP32-C3 additionally distinguishes resets by the
+/// USB Serial/JTAG peripheral. `None` is returned for a code the enum doesn't
+/// know.
+///
+/// A wake from deep sleep is reported as `CoreDeepSleep`. On the ESP32-C3,
+/// `get_wakeup_cause` tells which source ended the sleep. The other chips
+/// don't have it, as sleep modes and their wakeup sources are only implemented
+/// for the ESP32-C3 so far.
 pub fn get_reset_reason(cpu: Cpu) -> Option<SocResetReason> {
     let reason = unsafe { rtc_get_reset_reason(cpu as u32) };
     let reason = SocResetReason::from_repr(reason as usize);
 
     reason
 }
+
+/// Check whether the chip was woken up from deep sleep by the RTC timer
+///
+/// Only the ROM and registers of the RTC domain are accessed, so this can be
+/// called first thing in `main`. Together with [`quick_sleep`] it allows a
+/// fast path for periodic work, e.g. taking a sensor reading every few
+/// seconds:
+///
+/// 1. call `is_timer_wake` before anything else
+/// 2. if it returns `true`, take the peripherals and initialize only what is
+///    needed for the work (the clocks and e.g. the ADC). Skip [`Rtc::new`],
+///    which spends time calibrating the slow clock
+/// 3. keep results in RTC memory (`#[ram(rtc_fast)]`), everything else is lost
+///    while sleeping
+/// 4. call [`quick_sleep`]; the watchdogs enabled by the bootloader are left
+///    running, so the work has to finish well within their timeout
+///
+/// On any other boot do the full initialization, including [`Rtc::new`], and
+/// enter deep sleep with [`Rtc::sleep_deep`].
+#[cfg(esp32c3)]
+pub fn is_timer_wake() -> bool {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    get_reset_reason(Cpu::ProCpu) == Some(SocResetReason::CoreDeepSleep)
+        && rtc_cntl.slp_wakeup_cause.read().wakeup_cause().bits() & TIMER_WAKEUP != 0
+}
+
+/// What woke the chip from deep sleep, or `None` if this boot wasn't a wake
+/// from deep sleep
+///
+/// Only reads registers of the RTC domain, see [`is_timer_wake`].
+#[cfg(esp32c3)]
+pub fn get_wakeup_cause() -> Option<WakeupCause> {
+    if get_reset_reason(Cpu::ProCpu) != Some(SocResetReason::CoreDeepSleep) {
+        return None;
+    }
+
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+    let cause = rtc_cntl.slp_wakeup_cause.read().wakeup_cause().bits();
+
+    Some(if cause & TIMER_WAKEUP != 0 {
+        WakeupCause::Timer
+    } else if cause & GPIO_WAKEUP != 0 {
+        let reg = (RTC_CNTL::PTR as usize + RTC_CNTL_GPIO_WAKEUP_REG) as *const u32;
+        let status = unsafe { reg.read_volatile() } & GPIO_WAKEUP_STATUS;
+        WakeupCause::RtcGpio(status as u8)
+    } else {
+        WakeupCause::Other
+    })
+}
+
+/// GPIOs which woke the chip from light sleep
+///
+/// Bit `n` is set if GPIOn has its wakeup condition pending in the GPIO
+/// interrupt status. The wakeup conditions are levels, so the bits stay set as
+/// long as the level is held or until the interrupt of the pin is cleared.
+#[cfg(esp32c3)]
+pub fn get_gpio_wakeup_status() -> u64 {
+    let gpio = unsafe { &*crate::peripherals::GPIO::PTR };
+
+    gpio.status.read().bits() as u64
+}
+
+/// Enter deep sleep for `duration` without any driver initialization
+///
+/// Uses the slow clock calibration stored by [`Rtc::new`] during an earlier
+/// full boot, see [`is_timer_wake`] for the intended use. If there is none,
+/// the slow clock is calibrated first, which takes a few milliseconds.
+#[cfg(esp32c3)]
+pub fn quick_sleep(duration: MicrosDurationU64) -> ! {
+    unsafe { wake_stub::enter_deep_sleep(slow_clock_ticks(duration)) }
+}
+
+#[cfg(esp32c3)]
+fn slow_clock_ticks(duration: MicrosDurationU64) -> u64 {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+    // period of the slow clock in microseconds, Q13.19 fixed point
+    let mut cal = rtc_cntl.store1.read().bits();
+
+    // nothing was stored since the last power-on, e.g. `Rtc::new` never ran:
+    // calibrate the current slow clock now and store it for the next wake up
+    if cal == 0 {
+        cal = loop {
+            let res = RtcClock::calibrate(RtcCalSel::RtcCalRtcMux, 1024);
+            if res != 0 {
+                break res;
+            }
+        };
+        rtc_cntl.store1.write(|w| unsafe { w.bits(cal) });
+    }
+
+    (duration.to_micros() << 19) / cal as u64
+}
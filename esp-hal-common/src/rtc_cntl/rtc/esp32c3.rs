@@ -263,6 +263,8 @@ fn rtc_sleep_pu() {
 // System Reset: Reset the whole digital system, including RTC sub-system
 // Chip Reset:   Reset the whole chip, including the analog part
 
+/// Reason of the last reset of a CPU of the ESP32-C3, see
+/// [`get_reset_reason`](crate::rtc_cntl::get_reset_reason)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr)]
 pub enum SocResetReason {
     /// Power on reset
@@ -153,6 +153,8 @@ fn modem_clk_domain_active_state_icg_map_preinit() {
 // System Reset: Reset the whole digital system, including RTC sub-system
 // Chip Reset:   Reset the whole chip, including the analog part
 
+/// Reason of the last reset of a CPU of the ESP32-C6, see
+/// [`get_reset_reason`](crate::rtc_cntl::get_reset_reason)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr)]
 pub enum SocResetReason {
     /// Power on reset
@@ -314,6 +316,14 @@ impl RtcClock {
         }
     }
 
+    /// Detect the main XTAL frequency
+    ///
+    /// The ESP32-C6 only supports a 40 MHz XTAL, so this is the value stored
+    /// by the bootloader (or 40 MHz if there is none).
+    pub(crate) fn detect_xtal_freq() -> XtalClock {
+        RtcClock::get_xtal_freq()
+    }
+
     /// Get the RTC_SLOW_CLK source
     fn get_slow_freq() -> RtcSlowClock {
         let lp_clrst = unsafe { &*LP_CLKRST::ptr() };
@@ -2,11 +2,19 @@ use strum::FromRepr;
 
 use crate::{
     clock::XtalClock,
-    peripherals::RTC_CNTL,
+    peripherals::{RTC_CNTL, SYSTEM},
     rtc_cntl::{RtcCalSel, RtcClock, RtcFastClock, RtcSlowClock},
 };
 
-pub(crate) fn init() {}
+pub(crate) fn init() {
+    let system = unsafe { &*SYSTEM::ptr() };
+
+    // If SYSTEM_CPU_WAIT_MODE_FORCE_ON == 0,
+    // the CPU clock will be closed when CPU enter WAITI mode.
+    system
+        .cpu_per_conf
+        .modify(|_, w| w.cpu_wait_mode_force_on().clear_bit());
+}
 
 pub(crate) fn configure_clock() {
     assert!(matches!(
@@ -39,6 +47,8 @@ pub(crate) fn configure_clock() {
 // System Reset: Reset the whole digital system, including RTC sub-system
 // Chip Reset:   Reset the whole chip, including the analog part
 
+/// Reason of the last reset of a CPU of the ESP32-S3, see
+/// [`get_reset_reason`](crate::rtc_cntl::get_reset_reason)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromRepr)]
 pub enum SocResetReason {
     /// Power on reset
@@ -0,0 +1,113 @@
+//! Deep sleep wake stub support
+//!
+//! When waking up from deep sleep the ROM bootloader checks the RTC entry
+//! address register before loading the application from flash. If a wake
+//! stub has been registered with
+//! [`Rtc::set_wake_stub`](super::Rtc::set_wake_stub), the ROM jumps to it right
+//! away. The stub can then decide to go back to deep sleep with [`sleep`] (e.g.
+//! after updating a counter) or return, in which case the normal boot
+//! continues.
+//!
+//! The stub runs before anything has been initialized, which puts some hard
+//! constraints on it:
+//!
+//! - it must be placed in RTC fast memory using `#[ram(rtc_fast)]`, flash is
+//!   not mapped yet
+//! - everything it calls must also be in RTC fast memory, be inlined into it or
+//!   be a ROM function. This includes compiler intrinsics, so avoid 64 bit
+//!   divisions and similar operations
+//! - data it uses must live in RTC fast memory, placed with `#[ram(rtc_fast,
+//!   uninitialized)]` since `.data` / `.bss` are not initialized at this point
+//! - peripherals are in their reset state (apart from the RTC domain) and the
+//!   CPU runs from the XTAL, so keep the stub short
+//!
+//! ```rust,ignore
+//! #[ram(rtc_fast, uninitialized)]
+//! static mut WAKE_COUNT: u32 = 0;
+//!
+//! #[ram(rtc_fast)]
+//! unsafe extern "C" fn wake_stub() {
+//!     WAKE_COUNT += 1;
+//!     if WAKE_COUNT % 10 != 0 {
+//!         wake_stub::sleep(SLEEP_TICKS);
+//!     }
+//! }
+//! ```
+
+use crate::peripherals::RTC_CNTL;
+
+extern "C" {
+    fn set_rtc_memory_crc();
+}
+
+/// Go back to deep sleep from within a wake stub
+///
+/// `ticks` is the sleep duration in RTC slow clock cycles, see
+/// [`Rtc::duration_to_ticks`](super::Rtc::duration_to_ticks). Only the RTC
+/// timer is enabled as wakeup source.
+#[inline(always)]
+pub unsafe fn sleep(ticks: u64) -> ! {
+    enter_deep_sleep(ticks)
+}
+
+#[inline(always)]
+pub(crate) unsafe fn enter_deep_sleep(ticks: u64) -> ! {
+    set_sleep_timer(ticks);
+
+    // wake up on the RTC timer only
+    power_down(super::TIMER_WAKEUP)
+}
+
+// Let the RTC timer end the sleep `ticks` slow clock cycles from now
+#[inline(always)]
+pub(crate) unsafe fn set_sleep_timer(ticks: u64) {
+    let rtc_cntl = &*RTC_CNTL::PTR;
+
+    // latch and read the current RTC time
+    rtc_cntl.time_update.write(|w| w.time_update().set_bit());
+    let now = ((rtc_cntl.time_high0.read().timer_value0_high().bits() as u64) << 32)
+        | rtc_cntl.time_low0.read().timer_value0_low().bits() as u64;
+    let wakeup = now + ticks;
+
+    rtc_cntl
+        .slp_timer0
+        .write(|w| w.slp_val_lo().bits(wakeup as u32));
+    rtc_cntl
+        .int_clr_rtc
+        .write(|w| w.main_timer_int_clr().set_bit());
+    rtc_cntl.slp_timer1.write(|w| {
+        w.slp_val_hi()
+            .bits((wakeup >> 32) as u16)
+            .main_timer_alarm_en()
+            .set_bit()
+    });
+}
+
+// Enter deep sleep with the `RTC_CNTL_WAKEUP_ENA` bits in `wakeup_ena`
+#[inline(always)]
+pub(crate) unsafe fn power_down(wakeup_ena: u32) -> ! {
+    let rtc_cntl = &*RTC_CNTL::PTR;
+
+    rtc_cntl
+        .wakeup_state
+        .modify(|_, w| w.wakeup_ena().bits(wakeup_ena));
+
+    // power down the digital domain but keep the RTC fast memory, which holds
+    // the wake stub and its data
+    rtc_cntl.dig_pwc.modify(|_, w| w.dg_wrap_pd_en().set_bit());
+    rtc_cntl.pwc.modify(|_, w| w.fastmem_pd_en().clear_bit());
+
+    rtc_cntl.int_clr_rtc.write(|w| {
+        w.slp_reject_int_clr()
+            .set_bit()
+            .slp_wakeup_int_clr()
+            .set_bit()
+    });
+
+    // the ROM only jumps to the wake stub if the RTC memory CRC matches
+    set_rtc_memory_crc();
+
+    rtc_cntl.state0.modify(|_, w| w.sleep_en().set_bit());
+
+    loop {}
+}
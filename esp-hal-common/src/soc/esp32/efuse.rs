@@ -107,6 +107,29 @@ impl Efuse {
         }
     }
 
+    /// Returns the ADC reference voltage in millivolts, as measured and burned
+    /// into eFuse during production
+    ///
+    /// Returns `None` for chips that weren't calibrated, which applies to
+    /// early production runs.
+    pub fn get_adc_vref() -> Option<u16> {
+        let efuse = unsafe { &*EFUSE::ptr() };
+
+        // ADC_VREF, bits 8 to 12: offset from 1100 mV in steps of 7 mV, in sign
+        // and magnitude representation
+        let bits = (efuse.blk0_rdata4.read().bits() >> 8) & 0x1f;
+        if bits == 0 {
+            return None;
+        }
+
+        let magnitude = (bits & 0xf) as u16 * 7;
+        if bits & 0x10 != 0 {
+            Some(1100 - magnitude)
+        } else {
+            Some(1100 + magnitude)
+        }
+    }
+
     /// Get status of SPI boot encryption.
     pub fn get_flash_encryption() -> bool {
         let efuse = unsafe { &*EFUSE::ptr() };
@@ -163,3 +163,14 @@ crate::gpio::analog! {
     3
     4
 }
+
+// GPIO0 to GPIO5 are in the RTC domain, e.g. their level can be held during
+// deep sleep
+crate::gpio::rtc_pins! {
+    0
+    1
+    2
+    3
+    4
+    5
+}
@@ -31,6 +31,55 @@ pub(crate) fn gpio_intr_enable(int_enable: bool, nmi_enable: bool) -> u8 {
     int_enable as u8 | ((nmi_enable as u8) << 1)
 }
 
+/// Internal clocks which can be output on a pin, see
+/// [`set_clock_output`](crate::gpio::set_clock_output)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockOutputSource {
+    /// RC_SLOW_CLK, about 136 kHz
+    RcSlow  = 4,
+    /// XTAL_CLK
+    Xtal    = 5,
+    /// 80 MHz clock derived from the PLL, this is also APB_CLK while the CPU
+    /// is running from the PLL
+    PllF80M = 13,
+    /// RC_FAST_CLK, about 17.5 MHz
+    RcFast  = 14,
+}
+
+/// Clock output channel
+///
+/// Up to three clocks can be output at the same time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockOutputChannel {
+    Channel1,
+    Channel2,
+    Channel3,
+}
+
+impl ClockOutputChannel {
+    pub(crate) fn output_signal(&self) -> OutputSignal {
+        match self {
+            ClockOutputChannel::Channel1 => OutputSignal::CLK_OUT1,
+            ClockOutputChannel::Channel2 => OutputSignal::CLK_OUT2,
+            ClockOutputChannel::Channel3 => OutputSignal::CLK_OUT3,
+        }
+    }
+}
+
+pub(crate) fn set_clock_output_source(
+    channel: ClockOutputChannel,
+    source: Option<ClockOutputSource>,
+) {
+    // each channel has a 4 bit field in PIN_CTRL, 0xf disables the output
+    let shift = channel as u32 * 4;
+    let value = source.map(|source| source as u32).unwrap_or(0xf);
+
+    let io_mux = unsafe { &*crate::peripherals::IO_MUX::PTR };
+    io_mux
+        .pin_ctrl
+        .modify(|r, w| unsafe { w.bits((r.bits() & !(0xf << shift)) | (value << shift)) });
+}
+
 /// Peripheral input signals for the GPIO mux
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, PartialEq)]
@@ -198,3 +247,14 @@ crate::gpio::analog! {
     4
     5
 }
+
+// GPIO0 to GPIO5 are in the RTC domain, e.g. their level can be held during
+// deep sleep
+crate::gpio::rtc_pins! {
+    0
+    1
+    2
+    3
+    4
+    5
+}
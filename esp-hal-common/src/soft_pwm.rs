@@ -0,0 +1,211 @@
+//! Software PWM
+//!
+//! When all LEDC, MCPWM and RMT channels are in use (or a chip doesn't have
+//! the peripheral needed), [`SoftPwm`] can still generate a PWM signal on any
+//! output pin. Several channels share one general-purpose timer and a common
+//! period.
+//!
+//! The timer interrupt is used to switch the pins: at the start of a period
+//! all channels with a non-zero duty cycle are driven high, and every channel
+//! is driven low again once its pulse width has elapsed. This needs at most
+//! `N + 1` interrupts per period, but every edge is delayed by the interrupt
+//! latency, which also varies with whatever else is running. Expect jitter in
+//! the order of a few microseconds: this is fine for servos, LEDs and other
+//! signals up to about 1 kHz, but results in a poor signal at higher
+//! frequencies.
+//!
+//! The interrupt handler of the timer has to call [`SoftPwm::on_interrupt`].
+//!
+//! # Example
+//!
+//! The following will drive a servo on GPIO4 with a 1.5 ms pulse every 20 ms
+//!
+//! ```rust,ignore
+//! let servo = io.pins.gpio4.into_push_pull_output();
+//! let mut pwm = SoftPwm::new(timer0, [servo.degrade()], 20u64.millis());
+//! pwm.set_pulse_width(0, 1500u64.micros()).unwrap();
+//! pwm.start();
+//! ```
+
+use embedded_hal::digital::v2::OutputPin;
+use fugit::MicrosDurationU64;
+
+use crate::timer::{Instance, Timer};
+
+/// Software PWM error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Invalid channel index
+    Channel,
+    /// Duty cycle or pulse width is larger than the period
+    Duty,
+}
+
+struct Channel<P> {
+    pin: P,
+    /// Pulse width in timer ticks, applied at the start of the next period
+    duty: u64,
+    /// Pulse width in timer ticks used for the current period
+    active_duty: u64,
+}
+
+/// Timer interrupt driven PWM on up to `N` pins
+pub struct SoftPwm<T, P, const N: usize> {
+    timer: Timer<T>,
+    channels: [Channel<P>; N],
+    /// Period in timer ticks
+    period: u64,
+    /// Counter value at the start of the current period
+    period_start: u64,
+}
+
+impl<T, P, const N: usize> SoftPwm<T, P, N>
+where
+    T: Instance,
+    P: OutputPin,
+{
+    /// Create a new software PWM using `timer`, with all channels at a duty
+    /// cycle of 0%
+    ///
+    /// To use pins of different types, convert them into
+    /// [`AnyPin`](crate::gpio::AnyPin) first.
+    pub fn new<D>(timer: Timer<T>, pins: [P; N], period: D) -> Self
+    where
+        D: Into<MicrosDurationU64>,
+    {
+        let period = timer.duration_to_ticks(period.into());
+        let channels = pins.map(|mut pin| {
+            pin.set_low().ok();
+
+            Channel {
+                pin,
+                duty: 0,
+                active_duty: 0,
+            }
+        });
+
+        Self {
+            timer,
+            channels,
+            period,
+            period_start: 0,
+        }
+    }
+
+    /// Set the duty cycle of a channel in percent
+    ///
+    /// The new duty cycle takes effect at the start of the next period.
+    pub fn set_duty(&mut self, channel: usize, duty_pct: u8) -> Result<(), Error> {
+        if duty_pct > 100 {
+            return Err(Error::Duty);
+        }
+
+        let duty = self.period * duty_pct as u64 / 100;
+        self.channels.get_mut(channel).ok_or(Error::Channel)?.duty = duty;
+
+        Ok(())
+    }
+
+    /// Set the pulse width of a channel
+    ///
+    /// The new pulse width takes effect at the start of the next period.
+    pub fn set_pulse_width<D>(&mut self, channel: usize, width: D) -> Result<(), Error>
+    where
+        D: Into<MicrosDurationU64>,
+    {
+        let duty = self.timer.duration_to_ticks(width.into());
+        if duty > self.period {
+            return Err(Error::Duty);
+        }
+
+        self.channels.get_mut(channel).ok_or(Error::Channel)?.duty = duty;
+
+        Ok(())
+    }
+
+    /// Start generating the PWM signals
+    ///
+    /// This enables the interrupt of the timer, the interrupt itself still
+    /// needs to be enabled in the interrupt controller.
+    pub fn start(&mut self) {
+        self.timer.set_counter_active(false);
+        self.timer.set_alarm_active(false);
+        self.timer.reset_counter();
+        self.timer.set_counter_decrementing(false);
+        self.timer.set_auto_reload(false);
+        self.timer.set_counter_active(true);
+
+        // start the first period right away
+        self.period_start = self.timer.now().wrapping_sub(self.period);
+        self.timer.listen();
+        self.update();
+    }
+
+    /// Stop generating the PWM signals and drive all pins low
+    pub fn stop(&mut self) {
+        self.timer.unlisten();
+        self.timer.set_alarm_active(false);
+        self.timer.set_counter_active(false);
+        self.timer.clear_interrupt();
+
+        for channel in self.channels.iter_mut() {
+            channel.pin.set_low().ok();
+        }
+    }
+
+    /// Switch the pins and schedule the next edge, has to be called from the
+    /// timer's interrupt handler
+    pub fn on_interrupt(&mut self) {
+        self.timer.clear_interrupt();
+        self.update();
+    }
+
+    /// Stop the PWM and return the timer and pins
+    pub fn free(mut self) -> (Timer<T>, [P; N]) {
+        self.stop();
+
+        (self.timer, self.channels.map(|channel| channel.pin))
+    }
+
+    fn update(&mut self) {
+        loop {
+            let now = self.timer.now();
+
+            if now.wrapping_sub(self.period_start) >= self.period {
+                self.period_start = self.period_start.wrapping_add(self.period);
+
+                // if we fell behind by more than a period, e.g. because
+                // interrupts were disabled for too long, restart from now
+                if now.wrapping_sub(self.period_start) >= self.period {
+                    self.period_start = now;
+                }
+
+                for channel in self.channels.iter_mut() {
+                    channel.active_duty = channel.duty;
+                    if channel.active_duty > 0 {
+                        channel.pin.set_high().ok();
+                    }
+                }
+            }
+
+            let elapsed = now.wrapping_sub(self.period_start);
+            let mut next_edge = self.period;
+            for channel in self.channels.iter_mut() {
+                if channel.active_duty <= elapsed {
+                    channel.pin.set_low().ok();
+                } else {
+                    next_edge = next_edge.min(channel.active_duty);
+                }
+            }
+
+            // the alarm only fires for values still ahead of the counter, so
+            // handle edges which have already passed right away
+            let alarm = self.period_start.wrapping_add(next_edge);
+            if alarm > self.timer.now() {
+                self.timer.load_alarm_value(alarm);
+                self.timer.set_alarm_active(true);
+                break;
+            }
+        }
+    }
+}
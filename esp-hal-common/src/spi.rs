@@ -47,11 +47,22 @@
 //! [`SpiBusDevice`] implemented here. These give exclusive access to the
 //! underlying SPI bus by means of a Mutex. This ensures that device
 //! transactions do not interfere with each other.
+//!
+//! ## Timeouts
+//!
+//! Unlike with I2C, a device can't stall an SPI bus: the clock is always
+//! generated by the master, so blocking transfers finish after a time given by
+//! the number of bytes and the configured frequency. They can still hang if
+//! the peripheral stops, e.g. because its clock got gated or the DMA channel
+//! ran out of data. Blocking transfers therefore give up with
+//! [`Error::Timeout`] if a chunk (a FIFO without DMA, 32736 bytes with DMA)
+//! isn't done after twice its duration on the bus plus 100 ms, see
+//! [`Spi::set_timeout`]. The peripheral isn't stopped in that case.
 
 use fugit::HertzU32;
 
 use crate::{
-    clock::Clocks,
+    clock::{ClockDependent, Clocks},
     dma::{DmaError, DmaPeripheral, Rx, Tx},
     gpio::{InputPin, InputSignal, OutputPin, OutputSignal},
     peripheral::{Peripheral, PeripheralRef},
@@ -70,10 +81,15 @@ const EMPTY_WRITE_PAD: u8 = 0x00u8;
 #[allow(unused)]
 const MAX_DMA_SIZE: usize = 32736;
 
+/// Extra time a blocking transfer may take, see [`Spi::set_timeout`]
+const DEFAULT_TIMEOUT_US: u32 = 100_000;
+
 #[derive(Debug, Clone, Copy)]
 pub enum Error {
     DmaError(DmaError),
     MaxDmaTransferSizeExceeded,
+    /// A blocking transfer didn't finish in time, see [`Spi::set_timeout`]
+    Timeout,
     Unknown,
 }
 
@@ -90,6 +106,96 @@ impl embedded_hal_1::spi::Error for Error {
     }
 }
 
+/// Time limit for the busy-waits of blocking transfers
+///
+/// Measured with the counter [`crate::Delay`] is based on: the `SYSTIMER` on
+/// the RISC-V chips and the CPU cycle counter on the Xtensa chips.
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout {
+    counter_hz: u64,
+    bus_hz: u64,
+    timeout_us: Option<u32>,
+}
+
+impl Timeout {
+    fn new(frequency: HertzU32, clocks: &Clocks) -> Self {
+        Self {
+            counter_hz: Self::counter_hz(clocks),
+            bus_hz: frequency.raw() as u64,
+            timeout_us: Some(DEFAULT_TIMEOUT_US),
+        }
+    }
+
+    fn set_frequency(&mut self, frequency: HertzU32, clocks: &Clocks) {
+        self.counter_hz = Self::counter_hz(clocks);
+        self.bus_hz = frequency.raw() as u64;
+    }
+
+    #[cfg(riscv)]
+    fn counter_hz(clocks: &Clocks) -> u64 {
+        // the `SYSTIMER` counts at XTAL_CLK / 2.5, see `Delay::new`
+        clocks.xtal_clock.raw() as u64 * 10 / 25
+    }
+
+    #[cfg(xtensa)]
+    fn counter_hz(clocks: &Clocks) -> u64 {
+        clocks.cpu_clock.raw() as u64
+    }
+
+    #[cfg(riscv)]
+    fn now() -> u64 {
+        crate::systimer::SystemTimer::now()
+    }
+
+    #[cfg(riscv)]
+    fn ticks_between(earlier: u64, later: u64) -> u64 {
+        crate::systimer::SystemTimer::ticks_between(earlier, later)
+    }
+
+    #[cfg(xtensa)]
+    fn now() -> u64 {
+        xtensa_lx::timer::get_cycle_count() as u64
+    }
+
+    #[cfg(xtensa)]
+    fn ticks_between(earlier: u64, later: u64) -> u64 {
+        (later as u32).wrapping_sub(earlier as u32) as u64
+    }
+
+    /// Busy-wait until `done` returns `true`, which is expected to happen
+    /// after `len` bytes were clocked out
+    fn wait(&self, len: usize, mut done: impl FnMut() -> bool) -> Result<(), Error> {
+        let limit = match self.timeout_us {
+            // twice the nominal duration, the actual bus frequency can be
+            // lower than requested
+            Some(us) => {
+                len as u64 * 16 * self.counter_hz / self.bus_hz
+                    + us as u64 * self.counter_hz / 1_000_000
+            }
+            None => {
+                while !done() {}
+                return Ok(());
+            }
+        };
+
+        // the cycle counter wraps after a few seconds, so the elapsed time is
+        // summed up while polling
+        let mut last = Self::now();
+        let mut elapsed = 0;
+        while !done() {
+            let now = Self::now();
+            elapsed += Self::ticks_between(last, now);
+            last = now;
+
+            if elapsed > limit {
+                return Err(Error::Timeout);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum SpiMode {
     Mode0,
@@ -98,8 +204,53 @@ pub enum SpiMode {
     Mode3,
 }
 
+/// Low level SCLK configuration
+///
+/// [`SpiMode`] covers the common case, this exposes the two controller bits
+/// the modes are built from for devices that are described in terms of clock
+/// edges rather than CPOL/CPHA:
+///
+/// | Mode  | `idle_high` (`ck_idle_edge`) | `out_on_rising_edge` (`ck_out_edge`) |
+/// |-------|------------------------------|--------------------------------------|
+/// | Mode0 | `false`                      | `false`                              |
+/// | Mode1 | `false`                      | `true`                               |
+/// | Mode2 | `true`                       | `true`                               |
+/// | Mode3 | `true`                       | `false`                              |
+///
+/// Input data is always sampled on the opposite edge of the one the output
+/// changes on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpiClockConfig {
+    /// Level of SCLK while no transfer is in progress (`ck_idle_edge`)
+    pub idle_high: bool,
+    /// Shift data out on the rising edge of SCLK instead of the falling edge
+    /// (`ck_out_edge`)
+    pub out_on_rising_edge: bool,
+}
+
+impl From<SpiMode> for SpiClockConfig {
+    fn from(mode: SpiMode) -> Self {
+        let (idle_high, out_on_rising_edge) = match mode {
+            SpiMode::Mode0 => (false, false),
+            SpiMode::Mode1 => (false, true),
+            SpiMode::Mode2 => (true, true),
+            SpiMode::Mode3 => (true, false),
+        };
+
+        SpiClockConfig {
+            idle_high,
+            out_on_rising_edge,
+        }
+    }
+}
+
+/// SPI driver
+///
+/// Dropping the driver leaves the peripheral enabled and the pins connected,
+/// use [`Spi::free`] to also stop the peripheral clock.
 pub struct Spi<'d, T> {
     spi: PeripheralRef<'d, T>,
+    timeout: Timeout,
 }
 
 impl<'d, T> Spi<'d, T>
@@ -198,6 +349,97 @@ where
         Self::new_internal(spi, frequency, mode, peripheral_clock_control, clocks)
     }
 
+    /// Constructs an SPI instance with two bidirectional data lines, for
+    /// dual phases in a [`dma::Transaction`]
+    ///
+    /// `sio0` and `sio1` take the place of MOSI and MISO, so single-line
+    /// transfers work as with [`Spi::new`]. Both have to be able to act as
+    /// input and output.
+    #[cfg(not(any(esp32, esp32s2)))]
+    pub fn new_dual<SCK, SIO0, SIO1, CS>(
+        spi: impl Peripheral<P = T> + 'd,
+        sck: impl Peripheral<P = SCK> + 'd,
+        sio0: impl Peripheral<P = SIO0> + 'd,
+        sio1: impl Peripheral<P = SIO1> + 'd,
+        cs: impl Peripheral<P = CS> + 'd,
+        frequency: HertzU32,
+        mode: SpiMode,
+        peripheral_clock_control: &mut PeripheralClockControl,
+        clocks: &Clocks,
+    ) -> Self
+    where
+        SCK: OutputPin,
+        SIO0: OutputPin + InputPin,
+        SIO1: OutputPin + InputPin,
+        CS: OutputPin,
+    {
+        crate::into_ref!(spi, sck, sio0, sio1, cs);
+        sck.set_to_push_pull_output()
+            .connect_peripheral_to_output(spi.sclk_signal());
+
+        connect_sio(&mut *sio0, spi.mosi_signal(), spi.sio0_input_signal());
+        connect_sio(&mut *sio1, spi.sio1_output_signal(), spi.miso_signal());
+
+        cs.set_to_push_pull_output()
+            .connect_peripheral_to_output(spi.cs_signal());
+
+        Self::new_internal(spi, frequency, mode, peripheral_clock_control, clocks)
+    }
+
+    /// Constructs an SPI instance with four bidirectional data lines, for
+    /// dual and quad phases in a [`dma::Transaction`]
+    ///
+    /// `sio0` and `sio1` take the place of MOSI and MISO, `sio2` and `sio3`
+    /// are the lines called WP and HD on SPI flashes. All four have to be able
+    /// to act as input and output. A flash only uses WP and HD as data lines
+    /// once its quad enable (QE) bit is set, before that they are inputs of
+    /// the flash that have to be held high, so pull-ups on them are
+    /// recommended.
+    #[cfg(not(any(esp32, esp32s2)))]
+    pub fn new_quad<SCK, SIO0, SIO1, SIO2, SIO3, CS>(
+        spi: impl Peripheral<P = T> + 'd,
+        sck: impl Peripheral<P = SCK> + 'd,
+        sio0: impl Peripheral<P = SIO0> + 'd,
+        sio1: impl Peripheral<P = SIO1> + 'd,
+        sio2: impl Peripheral<P = SIO2> + 'd,
+        sio3: impl Peripheral<P = SIO3> + 'd,
+        cs: impl Peripheral<P = CS> + 'd,
+        frequency: HertzU32,
+        mode: SpiMode,
+        peripheral_clock_control: &mut PeripheralClockControl,
+        clocks: &Clocks,
+    ) -> Self
+    where
+        SCK: OutputPin,
+        SIO0: OutputPin + InputPin,
+        SIO1: OutputPin + InputPin,
+        SIO2: OutputPin + InputPin,
+        SIO3: OutputPin + InputPin,
+        CS: OutputPin,
+    {
+        crate::into_ref!(spi, sck, sio0, sio1, sio2, sio3, cs);
+        sck.set_to_push_pull_output()
+            .connect_peripheral_to_output(spi.sclk_signal());
+
+        connect_sio(&mut *sio0, spi.mosi_signal(), spi.sio0_input_signal());
+        connect_sio(&mut *sio1, spi.sio1_output_signal(), spi.miso_signal());
+        connect_sio(
+            &mut *sio2,
+            spi.sio2_output_signal(),
+            spi.sio2_input_signal(),
+        );
+        connect_sio(
+            &mut *sio3,
+            spi.sio3_output_signal(),
+            spi.sio3_input_signal(),
+        );
+
+        cs.set_to_push_pull_output()
+            .connect_peripheral_to_output(spi.cs_signal());
+
+        Self::new_internal(spi, frequency, mode, peripheral_clock_control, clocks)
+    }
+
     pub(crate) fn new_internal(
         spi: PeripheralRef<'d, T>,
         frequency: HertzU32,
@@ -207,7 +449,10 @@ where
     ) -> Self {
         spi.enable_peripheral(peripheral_clock_control);
 
-        let mut spi = Self { spi };
+        let mut spi = Self {
+            spi,
+            timeout: Timeout::new(frequency, clocks),
+        };
         spi.spi.setup(frequency, clocks);
         spi.spi.init();
         spi.spi.set_data_mode(mode);
@@ -217,9 +462,74 @@ where
 
     pub fn change_bus_frequency(&mut self, frequency: HertzU32, clocks: &Clocks) {
         self.spi.ch_bus_freq(frequency, clocks);
+        self.timeout.set_frequency(frequency, clocks);
+    }
+
+    /// Set how long blocking transfers wait for the bus
+    ///
+    /// Every chunk of a transfer (a FIFO of 64 bytes, 72 on the ESP32-S2,
+    /// without DMA, 32736 bytes with DMA) may take twice its duration at the
+    /// bus frequency plus `timeout_us`, otherwise the transfer fails with
+    /// [`Error::Timeout`]. The default is 100 ms, `None` waits forever. The
+    /// time is measured like [`crate::Delay`] does, on the Xtensa chips in CPU
+    /// cycles, so changing the CPU clock needs a call to
+    /// [`Spi::change_bus_frequency`] to keep the timeout right.
+    pub fn set_timeout(&mut self, timeout_us: Option<u32>) {
+        self.timeout.timeout_us = timeout_us;
+    }
+
+    /// Switch to another standard SPI mode
+    pub fn change_data_mode(&mut self, mode: SpiMode) {
+        self.spi.set_data_mode(mode);
+    }
+
+    /// Configure the SCLK idle level and output edge directly
+    ///
+    /// See [`SpiClockConfig`] for how this relates to the SPI modes.
+    pub fn set_clock_config(&mut self, config: SpiClockConfig) {
+        self.spi.set_clock_config(config);
+    }
+
+    /// Release the peripheral
+    ///
+    /// Waits for an ongoing transfer to complete and stops the peripheral
+    /// clock. The peripheral isn't reset and the pins stay connected, so SCLK,
+    /// MOSI and CS keep their idle levels and lines shared with other devices
+    /// don't glitch. As the SPI instance and the pins were only borrowed for
+    /// `'d`, they can be reconfigured afterwards, e.g. into inputs, or be used
+    /// to construct a new driver, which enables the clock again.
+    pub fn free(mut self, peripheral_clock_control: &mut PeripheralClockControl) {
+        self.spi.flush().ok();
+        self.spi.disable_peripheral(peripheral_clock_control);
     }
 }
 
+impl<T> ClockDependent for Spi<'_, T>
+where
+    T: Instance,
+{
+    /// A transfer is in progress
+    fn is_busy(&self) -> bool {
+        self.spi.register_block().cmd.read().usr().bit_is_set()
+    }
+
+    /// Set the bus frequency of the last [`Spi::change_bus_frequency`] (or of
+    /// the constructor) again
+    fn clocks_changed(&mut self, clocks: &Clocks) {
+        let frequency = HertzU32::from_raw(self.timeout.bus_hz as u32);
+        self.change_bus_frequency(frequency, clocks);
+    }
+}
+
+/// Connect a bidirectional data line, its direction is controlled by the
+/// output enable of the peripheral
+#[cfg(not(any(esp32, esp32s2)))]
+fn connect_sio<P: OutputPin + InputPin>(pin: &mut P, output: OutputSignal, input: InputSignal) {
+    pin.set_to_push_pull_output()
+        .connect_peripheral_to_output(output);
+    pin.enable_input(true).connect_input_to_peripheral(input);
+}
+
 impl<T> embedded_hal::spi::FullDuplex<u8> for Spi<'_, T>
 where
     T: Instance,
@@ -242,7 +552,7 @@ where
     type Error = Error;
 
     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
-        self.spi.transfer(words)
+        self.spi.transfer(words, &self.timeout)
     }
 }
 
@@ -253,8 +563,8 @@ where
     type Error = Error;
 
     fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-        self.spi.write_bytes(words)?;
-        self.spi.flush()?;
+        self.spi.write_bytes(words, &self.timeout)?;
+        self.spi.wait_idle(FIFO_SIZE, &self.timeout)?;
         Ok(())
     }
 }
@@ -266,11 +576,20 @@ pub mod dma {
 
     #[cfg(any(esp32, esp32s2))]
     use super::Spi3Instance;
-    use super::{Instance, InstanceDma, Spi, Spi2Instance, MAX_DMA_SIZE};
+    use super::{Instance, InstanceDma, Spi, Spi2Instance, Timeout, MAX_DMA_SIZE};
     #[cfg(any(esp32, esp32s2))]
     use crate::dma::Spi3Peripheral;
     use crate::{
-        dma::{Channel, DmaTransfer, DmaTransferRxTx, Rx, Spi2Peripheral, SpiPeripheral, Tx},
+        dma::{
+            Channel,
+            DmaError,
+            DmaTransfer,
+            DmaTransferRxTx,
+            Rx,
+            Spi2Peripheral,
+            SpiPeripheral,
+            Tx,
+        },
         peripheral::PeripheralRef,
     };
 
@@ -281,6 +600,11 @@ pub mod dma {
         RX: Rx,
         P: SpiPeripheral,
     {
+        /// Use `channel` for transfers
+        ///
+        /// Besides DMA transfers, the returned [SpiDma] implements the async
+        /// SPI traits. The bus configuration is kept, see
+        /// [SpiDma::into_blocking] for the way back.
         fn with_dma(self, channel: Channel<TX, RX, P>) -> SpiDma<'d, T, TX, RX, P>;
     }
 
@@ -292,6 +616,11 @@ pub mod dma {
         RX: Rx,
         P: SpiPeripheral,
     {
+        /// Use `channel` for transfers
+        ///
+        /// Besides DMA transfers, the returned [SpiDma] implements the async
+        /// SPI traits. The bus configuration is kept, see
+        /// [SpiDma::into_blocking] for the way back.
         fn with_dma(self, channel: Channel<TX, RX, P>) -> SpiDma<'d, T, TX, RX, P>;
     }
 
@@ -308,6 +637,7 @@ pub mod dma {
             SpiDma {
                 spi: self.spi,
                 channel,
+                timeout: self.timeout,
             }
         }
     }
@@ -326,6 +656,7 @@ pub mod dma {
             SpiDma {
                 spi: self.spi,
                 channel,
+                timeout: self.timeout,
             }
         }
     }
@@ -409,6 +740,10 @@ pub mod dma {
         fn wait(mut self) -> (BUFFER, SpiDma<'d, T, TX, RX, P>) {
             self.spi_dma.spi.flush().ok(); // waiting for the DMA transfer is not enough
 
+            // back to full-duplex in case this was a `Transaction`
+            #[cfg(not(any(esp32, esp32s2)))]
+            self.spi_dma.spi.reset_phases();
+
             // `DmaTransfer` needs to have a `Drop` implementation, because we accept
             // managed buffers that can free their memory on drop. Because of that
             // we can't move out of the `DmaTransfer`'s fields, so we use `ptr::read`
@@ -426,6 +761,96 @@ pub mod dma {
     }
 
     impl<'d, T, TX, RX, P, BUFFER> Drop for SpiDmaTransfer<'d, T, TX, RX, P, BUFFER>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        fn drop(&mut self) {
+            self.spi_dma.spi.flush().ok();
+            #[cfg(not(any(esp32, esp32s2)))]
+            self.spi_dma.spi.reset_phases();
+        }
+    }
+
+    /// An in-progress continuous DMA read, see [SpiDma::dma_read_circular]
+    pub struct SpiDmaStream<'d, T, TX, RX, P, BUFFER>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        spi_dma: SpiDma<'d, T, TX, RX, P>,
+        buffer: BUFFER,
+    }
+
+    impl<'d, T, TX, RX, P, BUFFER> SpiDmaStream<'d, T, TX, RX, P, BUFFER>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Amount of bytes which can be read with [Self::read_stream]
+        pub fn available(&mut self) -> usize {
+            self.keep_clocking();
+            self.spi_dma.channel.rx.available()
+        }
+
+        /// Copy the next chunk of received data to `buf`
+        ///
+        /// Returns the number of bytes copied, which is 0 if no new data is
+        /// available yet, and whether the ring buffer was filled completely
+        /// before, in which case received data got lost. The copied bytes are
+        /// valid either way and reading can continue after an overflow. Data
+        /// is handed out one DMA descriptor at a time, so `buf` should be able
+        /// to hold 4092 bytes. Returns [DmaError::BufferTooSmall] if the
+        /// available chunk doesn't fit.
+        pub fn read_stream(&mut self, buf: &mut [u8]) -> Result<(usize, bool), super::Error> {
+            let overflow = self.spi_dma.channel.rx.is_ring_full();
+
+            let available = self.available();
+            if available == 0 {
+                return Ok((0, overflow));
+            }
+
+            if buf.len() < available {
+                return Err(DmaError::BufferTooSmall.into());
+            }
+
+            let len = self.spi_dma.channel.rx.pop(&mut buf[..available])?;
+
+            Ok((len, overflow))
+        }
+
+        /// Stop reading and return the buffer and the SPI instance
+        ///
+        /// Waits for the current SPI transaction to finish, this can take up to
+        /// 32736 byte times.
+        pub fn stop(mut self) -> (BUFFER, SpiDma<'d, T, TX, RX, P>) {
+            self.spi_dma.spi.flush().ok();
+
+            // see `SpiDmaTransfer::wait`
+            unsafe {
+                let buffer = core::ptr::read(&self.buffer);
+                let payload = core::ptr::read(&self.spi_dma);
+                mem::forget(self);
+                (buffer, payload)
+            }
+        }
+
+        fn keep_clocking(&mut self) {
+            let reg_block = self.spi_dma.spi.register_block();
+
+            if reg_block.cmd.read().usr().bit_is_clear() {
+                self.spi_dma.spi.continue_read_bytes_dma_circular();
+            }
+        }
+    }
+
+    impl<'d, T, TX, RX, P, BUFFER> Drop for SpiDmaStream<'d, T, TX, RX, P, BUFFER>
     where
         T: InstanceDma<TX, RX>,
         TX: Tx,
@@ -437,6 +862,120 @@ pub mod dma {
         }
     }
 
+    /// Phases sent ahead of the data of a single SPI transaction
+    ///
+    /// Many devices expect a command, optionally followed by an address and
+    /// some dummy clock cycles, before the data is transferred, e.g. a SPI
+    /// flash read is the command `0x03` and a 24 bit address followed by the
+    /// data clocked out by the flash. With
+    /// [SpiDma::dma_transaction_read] and [SpiDma::dma_transaction_write] the
+    /// SPI peripheral sequences all of these phases in hardware as one
+    /// transaction: the command and address are taken from registers, the data
+    /// phase is served by DMA, and the CPU isn't involved in between.
+    ///
+    /// The phases always go out in the order command, address, dummy cycles,
+    /// data, without gaps between them and with CS asserted for the whole
+    /// transaction. Only the data phase is half-duplex in one direction,
+    /// nothing is received during the other phases.
+    ///
+    /// ```rust,ignore
+    /// let read = Transaction::new().command(8, 0x03).address(24, 0x1000);
+    /// let transfer = spi.dma_transaction_read(read, buffer).unwrap();
+    /// let (buffer, spi) = transfer.wait();
+    /// ```
+    ///
+    /// Each phase can use one, two or four data lines, see [LineWidth]. E.g.
+    /// the "Fast Read Quad Output" command `0x6b` of most SPI flashes sends
+    /// the command and address on a single line and returns the data on four:
+    ///
+    /// ```rust,ignore
+    /// let read = Transaction::new()
+    ///     .command(8, 0x6b)
+    ///     .address(24, 0x1000)
+    ///     .dummy_cycles(8)
+    ///     .data_width(LineWidth::Quad);
+    /// ```
+    #[cfg(not(any(esp32, esp32s2)))]
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Transaction {
+        pub(super) command: Option<(u8, u16)>,
+        pub(super) address: Option<(u8, u32)>,
+        pub(super) dummy_cycles: u8,
+        pub(super) command_width: LineWidth,
+        pub(super) address_width: LineWidth,
+        pub(super) data_width: LineWidth,
+    }
+
+    /// Number of data lines used by a phase of a [Transaction]
+    ///
+    /// Dual and quad phases need the extra data lines, which are only
+    /// connected by [Spi::new_dual](super::Spi::new_dual) and
+    /// [Spi::new_quad](super::Spi::new_quad). In these modes all data lines
+    /// are bidirectional: the peripheral drives them while sending and
+    /// releases them while receiving, the direction is switched in hardware
+    /// between the phases.
+    #[cfg(not(any(esp32, esp32s2)))]
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub enum LineWidth {
+        /// Standard SPI, sending on MOSI and receiving on MISO
+        #[default]
+        Single,
+        /// Two bits per clock on SIO0 (MOSI) and SIO1 (MISO)
+        Dual,
+        /// Four bits per clock on SIO0 to SIO3
+        Quad,
+    }
+
+    #[cfg(not(any(esp32, esp32s2)))]
+    impl Transaction {
+        /// A transaction consisting of the data phase only
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Send `value` as the command phase, `bits` (1 to 16) long and most
+        /// significant bit first
+        pub fn command(mut self, bits: u8, value: u16) -> Self {
+            assert!((1..=16).contains(&bits), "Command must be 1 to 16 bits");
+
+            self.command = Some((bits, value));
+            self
+        }
+
+        /// Send `value` as the address phase, `bits` (1 to 32) long and most
+        /// significant bit first
+        pub fn address(mut self, bits: u8, value: u32) -> Self {
+            assert!((1..=32).contains(&bits), "Address must be 1 to 32 bits");
+
+            self.address = Some((bits, value));
+            self
+        }
+
+        /// Insert `cycles` clock cycles between the address and the data phase
+        pub fn dummy_cycles(mut self, cycles: u8) -> Self {
+            self.dummy_cycles = cycles;
+            self
+        }
+
+        /// Send the command phase on `width` lines
+        pub fn command_width(mut self, width: LineWidth) -> Self {
+            self.command_width = width;
+            self
+        }
+
+        /// Send the address phase on `width` lines
+        pub fn address_width(mut self, width: LineWidth) -> Self {
+            self.address_width = width;
+            self
+        }
+
+        /// Transfer the data phase on `width` lines
+        pub fn data_width(mut self, width: LineWidth) -> Self {
+            self.data_width = width;
+            self
+        }
+    }
+
     /// A DMA capable SPI instance.
     pub struct SpiDma<'d, T, TX, RX, P>
     where
@@ -446,6 +985,7 @@ pub mod dma {
     {
         pub(crate) spi: PeripheralRef<'d, T>,
         pub(crate) channel: Channel<TX, RX, P>,
+        pub(crate) timeout: Timeout,
     }
 
     impl<'d, T, TX, RX, P> SpiDma<'d, T, TX, RX, P>
@@ -455,6 +995,33 @@ pub mod dma {
         RX: Rx,
         P: SpiPeripheral,
     {
+        /// Stop using DMA, returning the plain [Spi] driver and the DMA channel
+        ///
+        /// Waits for an ongoing transfer to complete first, which can be left
+        /// behind by a cancelled async operation, and masks the DMA interrupts
+        /// used by the async API. The bus configuration (frequency, mode and
+        /// pins) is kept as-is, so the conversion only costs a couple of
+        /// register accesses. Use `with_dma` to convert back.
+        pub fn into_blocking(mut self) -> (Spi<'d, T>, Channel<TX, RX, P>) {
+            self.spi.flush().ok();
+            self.channel.tx.unlisten_eof();
+            self.channel.rx.unlisten_eof();
+
+            (
+                Spi {
+                    spi: self.spi,
+                    timeout: self.timeout,
+                },
+                self.channel,
+            )
+        }
+
+        /// Set how long blocking transfers wait for the bus, see
+        /// [`Spi::set_timeout`]
+        pub fn set_timeout(&mut self, timeout_us: Option<u32>) {
+            self.timeout.timeout_us = timeout_us;
+        }
+
         /// Perform a DMA write.
         ///
         /// This will return a [SpiDmaTransfer] owning the buffer(s) and the SPI
@@ -507,6 +1074,43 @@ pub mod dma {
             })
         }
 
+        /// Continuously read into `words`, used as a ring buffer
+        ///
+        /// This is meant for devices streaming data on MISO as long as they are
+        /// clocked, like some ADCs. MOSI is driven with dummy data. Received
+        /// data is fetched with [SpiDmaStream::read_stream].
+        ///
+        /// The buffer has to be at least 8184 bytes and a multiple of 4 bytes
+        /// long. An SPI transaction can't exceed 32736 bytes, so the clock
+        /// pauses briefly whenever a transaction ends until the next call to
+        /// [SpiDmaStream::read_stream] (or [SpiDmaStream::available]) starts
+        /// the next one. The sustainable sample rate therefore depends on how
+        /// often the stream is read: the bus frequency sets the upper limit
+        /// (the DMA keeps up with the maximum of 80 MHz), but the application
+        /// has to read the data faster than it arrives, otherwise the ring
+        /// buffer overflows. E.g. at 20 MHz an 8 KiB buffer fills in about
+        /// 3.3 ms.
+        pub fn dma_read_circular<RXBUF>(
+            mut self,
+            mut words: RXBUF,
+        ) -> Result<SpiDmaStream<'d, T, TX, RX, P, RXBUF>, super::Error>
+        where
+            RXBUF: WriteBuffer<Word = u8>,
+        {
+            let (ptr, len) = unsafe { words.write_buffer() };
+
+            if len % 4 != 0 {
+                return Err(DmaError::InvalidAlignment.into());
+            }
+
+            self.spi
+                .start_read_bytes_dma_circular(ptr, len, &mut self.channel.rx)?;
+            Ok(SpiDmaStream {
+                spi_dma: self,
+                buffer: words,
+            })
+        }
+
         /// Perform a DMA transfer.
         ///
         /// This will return a [SpiDmaTransfer] owning the buffer(s) and the SPI
@@ -542,6 +1146,82 @@ pub mod dma {
                 tbuffer: words,
             })
         }
+
+        /// Send the phases of `transaction`, then read into `words`
+        ///
+        /// This will return a [SpiDmaTransfer] owning the buffer and the SPI
+        /// instance. `words` can't be empty and can be at most 32736 bytes
+        /// long. See [Transaction] for the ordering of the phases.
+        #[cfg(not(any(esp32, esp32s2)))]
+        pub fn dma_transaction_read<RXBUF>(
+            mut self,
+            transaction: Transaction,
+            mut words: RXBUF,
+        ) -> Result<SpiDmaTransfer<'d, T, TX, RX, P, RXBUF>, super::Error>
+        where
+            RXBUF: WriteBuffer<Word = u8>,
+        {
+            let (ptr, len) = unsafe { words.write_buffer() };
+
+            if len == 0 {
+                return Err(DmaError::BufferTooSmall.into());
+            }
+            if len > MAX_DMA_SIZE {
+                return Err(super::Error::MaxDmaTransferSizeExceeded);
+            }
+
+            self.spi.configure_phases(&transaction, false);
+            if let Err(err) = self
+                .spi
+                .start_read_bytes_dma(ptr, len, &mut self.channel.rx)
+            {
+                self.spi.reset_phases();
+                return Err(err);
+            }
+
+            Ok(SpiDmaTransfer {
+                spi_dma: self,
+                buffer: words,
+            })
+        }
+
+        /// Send the phases of `transaction`, then write `words`
+        ///
+        /// This will return a [SpiDmaTransfer] owning the buffer and the SPI
+        /// instance. `words` can't be empty and can be at most 32736 bytes
+        /// long. See [Transaction] for the ordering of the phases.
+        #[cfg(not(any(esp32, esp32s2)))]
+        pub fn dma_transaction_write<TXBUF>(
+            mut self,
+            transaction: Transaction,
+            words: TXBUF,
+        ) -> Result<SpiDmaTransfer<'d, T, TX, RX, P, TXBUF>, super::Error>
+        where
+            TXBUF: ReadBuffer<Word = u8>,
+        {
+            let (ptr, len) = unsafe { words.read_buffer() };
+
+            if len == 0 {
+                return Err(DmaError::BufferTooSmall.into());
+            }
+            if len > MAX_DMA_SIZE {
+                return Err(super::Error::MaxDmaTransferSizeExceeded);
+            }
+
+            self.spi.configure_phases(&transaction, true);
+            if let Err(err) = self
+                .spi
+                .start_write_bytes_dma(ptr, len, &mut self.channel.tx)
+            {
+                self.spi.reset_phases();
+                return Err(err);
+            }
+
+            Ok(SpiDmaTransfer {
+                spi_dma: self,
+                buffer: words,
+            })
+        }
     }
 
     impl<'d, T, TX, RX, P> embedded_hal::blocking::spi::Transfer<u8> for SpiDma<'d, T, TX, RX, P>
@@ -554,8 +1234,12 @@ pub mod dma {
         type Error = super::Error;
 
         fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
-            self.spi
-                .transfer_in_place_dma(words, &mut self.channel.tx, &mut self.channel.rx)
+            self.spi.transfer_in_place_dma(
+                words,
+                &mut self.channel.tx,
+                &mut self.channel.rx,
+                &self.timeout,
+            )
         }
     }
 
@@ -569,8 +1253,8 @@ pub mod dma {
         type Error = super::Error;
 
         fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-            self.spi.write_bytes_dma(words, &mut self.channel.tx)?;
-            self.spi.flush()?;
+            self.spi
+                .write_bytes_dma(words, &mut self.channel.tx, &self.timeout)?;
             Ok(())
         }
     }
@@ -720,7 +1404,7 @@ pub mod dma {
     mod ehal1 {
         use embedded_hal_1::spi::{SpiBus, SpiBusFlush, SpiBusRead, SpiBusWrite};
 
-        use super::{super::InstanceDma, SpiDma, SpiPeripheral};
+        use super::{super::InstanceDma, SpiDma, SpiPeripheral, MAX_DMA_SIZE};
         use crate::dma::{Rx, Tx};
 
         impl<'d, T, TX, RX, P> embedded_hal_1::spi::ErrorType for SpiDma<'d, T, TX, RX, P>
@@ -742,8 +1426,9 @@ pub mod dma {
         {
             /// See also: [`write_bytes`].
             fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-                self.spi.write_bytes_dma(words, &mut self.channel.tx)?;
-                self.flush()
+                self.spi
+                    .write_bytes_dma(words, &mut self.channel.tx, &self.timeout)?;
+                Ok(())
             }
         }
 
@@ -755,9 +1440,14 @@ pub mod dma {
             P: SpiPeripheral,
         {
             fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-                self.spi
-                    .transfer_dma(&[], words, &mut self.channel.tx, &mut self.channel.rx)?;
-                self.flush()
+                self.spi.transfer_dma(
+                    &[],
+                    words,
+                    &mut self.channel.tx,
+                    &mut self.channel.rx,
+                    &self.timeout,
+                )?;
+                Ok(())
             }
         }
 
@@ -777,9 +1467,14 @@ pub mod dma {
             /// been read. Reading and writing happens
             /// simultaneously.
             fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
-                self.spi
-                    .transfer_dma(write, read, &mut self.channel.tx, &mut self.channel.rx)?;
-                self.flush()
+                self.spi.transfer_dma(
+                    write,
+                    read,
+                    &mut self.channel.tx,
+                    &mut self.channel.rx,
+                    &self.timeout,
+                )?;
+                Ok(())
             }
 
             /// Transfer data in place.
@@ -793,8 +1488,9 @@ pub mod dma {
                     words,
                     &mut self.channel.tx,
                     &mut self.channel.rx,
+                    &self.timeout,
                 )?;
-                self.flush()
+                Ok(())
             }
         }
 
@@ -806,7 +1502,7 @@ pub mod dma {
             P: SpiPeripheral,
         {
             fn flush(&mut self) -> Result<(), Self::Error> {
-                self.spi.flush()
+                self.spi.wait_idle(MAX_DMA_SIZE, &self.timeout)
             }
         }
     }
@@ -856,7 +1552,7 @@ mod ehal1 {
     {
         /// See also: [`write_bytes`].
         fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-            self.spi.write_bytes(words)
+            self.spi.write_bytes(words, &self.timeout)
         }
     }
 
@@ -866,7 +1562,7 @@ mod ehal1 {
     {
         /// See also: [`read_bytes`].
         fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-            self.spi.read_bytes(words)
+            self.spi.read_bytes(words, &self.timeout)
         }
     }
 
@@ -948,7 +1644,7 @@ mod ehal1 {
         T: Instance,
     {
         fn flush(&mut self) -> Result<(), Self::Error> {
-            self.spi.flush()
+            self.spi.wait_idle(FIFO_SIZE, &self.timeout)
         }
     }
 
@@ -1046,6 +1742,16 @@ mod ehal1 {
     }
 }
 
+/// The dual and quad bits of the SPI registers for `width`
+#[cfg(not(any(esp32, esp32s2)))]
+fn line_width_bits(width: dma::LineWidth) -> (bool, bool) {
+    match width {
+        dma::LineWidth::Single => (false, false),
+        dma::LineWidth::Dual => (true, false),
+        dma::LineWidth::Quad => (false, true),
+    }
+}
+
 pub trait InstanceDma<TX, RX>: Instance
 where
     TX: Tx,
@@ -1056,6 +1762,7 @@ where
         words: &'w mut [u8],
         tx: &mut TX,
         rx: &mut RX,
+        timeout: &Timeout,
     ) -> Result<&'w [u8], Error> {
         for chunk in words.chunks_mut(MAX_DMA_SIZE) {
             self.start_transfer_dma(
@@ -1067,8 +1774,8 @@ where
                 rx,
             )?;
 
-            while !tx.is_done() && !rx.is_done() {}
-            self.flush().unwrap();
+            timeout.wait(chunk.len(), || tx.is_done() || rx.is_done())?;
+            self.wait_idle(chunk.len(), timeout)?;
         }
 
         return Ok(words);
@@ -1080,6 +1787,7 @@ where
         read_buffer: &'w mut [u8],
         tx: &mut TX,
         rx: &mut RX,
+        timeout: &Timeout,
     ) -> Result<&'w [u8], Error> {
         let mut idx = 0;
         loop {
@@ -1098,8 +1806,9 @@ where
                 rx,
             )?;
 
-            while !tx.is_done() && !rx.is_done() {}
-            self.flush().unwrap();
+            let len = usize::max(read_len, write_len);
+            timeout.wait(len, || tx.is_done() || rx.is_done())?;
+            self.wait_idle(len, timeout)?;
 
             idx += MAX_DMA_SIZE as isize;
             if idx >= write_buffer.len() as isize && idx >= read_buffer.len() as isize {
@@ -1150,12 +1859,18 @@ where
         Ok(())
     }
 
-    fn write_bytes_dma<'w>(&mut self, words: &'w [u8], tx: &mut TX) -> Result<&'w [u8], Error> {
+    fn write_bytes_dma<'w>(
+        &mut self,
+        words: &'w [u8],
+        tx: &mut TX,
+        timeout: &Timeout,
+    ) -> Result<&'w [u8], Error> {
         for chunk in words.chunks(MAX_DMA_SIZE) {
             self.start_write_bytes_dma(chunk.as_ptr(), chunk.len(), tx)?;
 
-            while !tx.is_done() {}
-            self.flush().unwrap(); // seems "is_done" doesn't work as intended?
+            timeout.wait(chunk.len(), || tx.is_done())?;
+            // seems "is_done" doesn't work as intended?
+            self.wait_idle(chunk.len(), timeout)?;
         }
 
         return Ok(words);
@@ -1191,9 +1906,38 @@ where
         ptr: *mut u8,
         len: usize,
         rx: &mut RX,
+    ) -> Result<(), Error> {
+        self.start_read_bytes_dma_with_mode(ptr, len, len, false, rx)
+    }
+
+    fn start_read_bytes_dma_circular<'w>(
+        &mut self,
+        ptr: *mut u8,
+        len: usize,
+        rx: &mut RX,
+    ) -> Result<(), Error> {
+        self.start_read_bytes_dma_with_mode(ptr, len, MAX_DMA_SIZE, true, rx)
+    }
+
+    /// Start another transaction of a circular read, without touching the DMA
+    /// descriptors
+    fn continue_read_bytes_dma_circular(&mut self) {
+        let reg_block = self.register_block();
+
+        self.update();
+        reg_block.cmd.modify(|_, w| w.usr().set_bit());
+    }
+
+    fn start_read_bytes_dma_with_mode<'w>(
+        &mut self,
+        ptr: *mut u8,
+        len: usize,
+        transaction_len: usize,
+        circular: bool,
+        rx: &mut RX,
     ) -> Result<(), Error> {
         let reg_block = self.register_block();
-        self.configure_datalen(len as u32 * 8);
+        self.configure_datalen(transaction_len as u32 * 8);
 
         rx.is_done();
 
@@ -1201,7 +1945,7 @@ where
         self.update();
 
         reset_dma_before_load_dma_dscr(reg_block);
-        rx.prepare_transfer(false, self.dma_peripheral(), ptr, len)?;
+        rx.prepare_transfer(circular, self.dma_peripheral(), ptr, len)?;
 
         self.clear_dma_interrupts();
         reset_dma_before_usr_cmd(reg_block);
@@ -1211,6 +1955,111 @@ where
         return Ok(());
     }
 
+    /// Enable the phases of `transaction` and make the data phase half-duplex
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn configure_phases(&self, transaction: &dma::Transaction, write: bool) {
+        let reg_block = self.register_block();
+
+        if let Some((bits, value)) = transaction.command {
+            // the command register is sent starting with the low byte
+            let value = (value << (16 - bits)).swap_bytes();
+            reg_block.user2.modify(|_, w| unsafe {
+                w.usr_command_bitlen()
+                    .bits(bits - 1)
+                    .usr_command_value()
+                    .bits(value)
+            });
+        }
+        if let Some((bits, value)) = transaction.address {
+            // the address is sent starting with bit 31
+            reg_block
+                .addr
+                .write(|w| unsafe { w.bits(((value as u64) << (32 - bits)) as u32) });
+            reg_block
+                .user1
+                .modify(|_, w| unsafe { w.usr_addr_bitlen().bits(bits - 1) });
+        }
+        if transaction.dummy_cycles > 0 {
+            reg_block.user1.modify(|_, w| unsafe {
+                w.usr_dummy_cyclelen().bits(transaction.dummy_cycles - 1)
+            });
+        }
+
+        let (command_dual, command_quad) = line_width_bits(transaction.command_width);
+        let (address_dual, address_quad) = line_width_bits(transaction.address_width);
+        let (data_dual, data_quad) = line_width_bits(transaction.data_width);
+        reg_block.ctrl.modify(|_, w| {
+            w.fcmd_dual()
+                .bit(command_dual)
+                .fcmd_quad()
+                .bit(command_quad)
+                .faddr_dual()
+                .bit(address_dual)
+                .faddr_quad()
+                .bit(address_quad)
+                .fread_dual()
+                .bit(data_dual && !write)
+                .fread_quad()
+                .bit(data_quad && !write)
+        });
+
+        reg_block.user.modify(|_, w| {
+            w.doutdin()
+                .clear_bit()
+                .usr_mosi()
+                .bit(write)
+                .usr_miso()
+                .bit(!write)
+                .usr_command()
+                .bit(transaction.command.is_some())
+                .usr_addr()
+                .bit(transaction.address.is_some())
+                .usr_dummy()
+                .bit(transaction.dummy_cycles > 0)
+                .fwrite_dual()
+                .bit(data_dual && write)
+                .fwrite_quad()
+                .bit(data_quad && write)
+        });
+    }
+
+    /// Back to full-duplex transfers without any phases besides the data
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn reset_phases(&self) {
+        self.register_block().user.modify(|_, w| {
+            w.doutdin()
+                .set_bit()
+                .usr_mosi()
+                .set_bit()
+                .usr_miso()
+                .set_bit()
+                .usr_command()
+                .clear_bit()
+                .usr_addr()
+                .clear_bit()
+                .usr_dummy()
+                .clear_bit()
+                .fwrite_dual()
+                .clear_bit()
+                .fwrite_quad()
+                .clear_bit()
+        });
+        self.register_block().ctrl.modify(|_, w| {
+            w.fcmd_dual()
+                .clear_bit()
+                .fcmd_quad()
+                .clear_bit()
+                .faddr_dual()
+                .clear_bit()
+                .faddr_quad()
+                .clear_bit()
+                .fread_dual()
+                .clear_bit()
+                .fread_quad()
+                .clear_bit()
+        });
+    }
+
     fn dma_peripheral(&self) -> DmaPeripheral {
         match self.spi_num() {
             2 => DmaPeripheral::Spi2,
@@ -1344,8 +2193,41 @@ pub trait Instance {
 
     fn cs_signal(&self) -> OutputSignal;
 
+    /// Input of SIO0 (MOSI) in dual and quad mode
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn sio0_input_signal(&self) -> InputSignal;
+
+    /// Output of SIO1 (MISO) in dual and quad mode
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn sio1_output_signal(&self) -> OutputSignal;
+
+    /// Input of SIO2 (WP) in quad mode
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn sio2_input_signal(&self) -> InputSignal;
+
+    /// Output of SIO2 (WP) in quad mode
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn sio2_output_signal(&self) -> OutputSignal;
+
+    /// Input of SIO3 (HD) in quad mode
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn sio3_input_signal(&self) -> InputSignal;
+
+    /// Output of SIO3 (HD) in quad mode
+    #[cfg(not(any(esp32, esp32s2)))]
+    fn sio3_output_signal(&self) -> OutputSignal;
+
     fn enable_peripheral(&self, peripheral_clock_control: &mut PeripheralClockControl);
 
+    fn disable_peripheral(&self, peripheral_clock_control: &mut PeripheralClockControl) {
+        match self.spi_num() {
+            2 => peripheral_clock_control.disable(crate::system::Peripheral::Spi2),
+            #[cfg(spi3)]
+            3 => peripheral_clock_control.disable(crate::system::Peripheral::Spi3),
+            _ => unreachable!(), // will never happen
+        }
+    }
+
     fn spi_num(&self) -> u8;
 
     fn init(&mut self) {
@@ -1480,53 +2362,26 @@ pub trait Instance {
             .write(|w| unsafe { w.bits(reg_val) });
     }
 
-    #[cfg(not(esp32))]
     fn set_data_mode(&mut self, data_mode: SpiMode) -> &mut Self {
-        let reg_block = self.register_block();
-
-        match data_mode {
-            SpiMode::Mode0 => {
-                reg_block.misc.modify(|_, w| w.ck_idle_edge().clear_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().clear_bit());
-            }
-            SpiMode::Mode1 => {
-                reg_block.misc.modify(|_, w| w.ck_idle_edge().clear_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().set_bit());
-            }
-            SpiMode::Mode2 => {
-                reg_block.misc.modify(|_, w| w.ck_idle_edge().set_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().set_bit());
-            }
-            SpiMode::Mode3 => {
-                reg_block.misc.modify(|_, w| w.ck_idle_edge().set_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().clear_bit());
-            }
-        }
-        self
+        self.set_clock_config(data_mode.into())
     }
 
-    #[cfg(esp32)]
-    fn set_data_mode(&mut self, data_mode: SpiMode) -> &mut Self {
+    fn set_clock_config(&mut self, config: SpiClockConfig) -> &mut Self {
         let reg_block = self.register_block();
 
-        match data_mode {
-            SpiMode::Mode0 => {
-                reg_block.pin.modify(|_, w| w.ck_idle_edge().clear_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().clear_bit());
-            }
-            SpiMode::Mode1 => {
-                reg_block.pin.modify(|_, w| w.ck_idle_edge().clear_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().set_bit());
-            }
-            SpiMode::Mode2 => {
-                reg_block.pin.modify(|_, w| w.ck_idle_edge().set_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().set_bit());
-            }
-            SpiMode::Mode3 => {
-                reg_block.pin.modify(|_, w| w.ck_idle_edge().set_bit());
-                reg_block.user.modify(|_, w| w.ck_out_edge().clear_bit());
-            }
-        }
+        #[cfg(not(esp32))]
+        reg_block
+            .misc
+            .modify(|_, w| w.ck_idle_edge().bit(config.idle_high));
+        #[cfg(esp32)]
+        reg_block
+            .pin
+            .modify(|_, w| w.ck_idle_edge().bit(config.idle_high));
+
+        reg_block
+            .user
+            .modify(|_, w| w.ck_out_edge().bit(config.out_on_rising_edge));
+
         self
     }
 
@@ -1594,7 +2449,7 @@ pub trait Instance {
     /// you must ensure that the whole messages was written correctly, use
     /// [`flush`].
     // FIXME: See below.
-    fn write_bytes(&mut self, words: &[u8]) -> Result<(), Error> {
+    fn write_bytes(&mut self, words: &[u8], timeout: &Timeout) -> Result<(), Error> {
         let reg_block = self.register_block();
         let num_chunks = words.len() / FIFO_SIZE;
 
@@ -1644,9 +2499,7 @@ pub trait Instance {
             // THIS IS NOT TRUE FOR EH 0.2.X! MAKE SURE TO FLUSH IN EH 0.2.X TRAIT
             // IMPLEMENTATIONS!
             if i < num_chunks {
-                while reg_block.cmd.read().usr().bit_is_set() {
-                    // wait
-                }
+                self.wait_idle(chunk.len(), timeout)?;
             }
         }
         Ok(())
@@ -1657,12 +2510,12 @@ pub trait Instance {
     /// Sends out a stuffing byte for every byte to read. This function doesn't
     /// perform flushing. If you want to read the response to something you
     /// have written before, consider using [`transfer`] instead.
-    fn read_bytes(&mut self, words: &mut [u8]) -> Result<(), Error> {
+    fn read_bytes(&mut self, words: &mut [u8], timeout: &Timeout) -> Result<(), Error> {
         let empty_array = [EMPTY_WRITE_PAD; FIFO_SIZE];
 
         for chunk in words.chunks_mut(FIFO_SIZE) {
-            self.write_bytes(&empty_array[0..chunk.len()])?;
-            self.flush()?;
+            self.write_bytes(&empty_array[0..chunk.len()], timeout)?;
+            self.wait_idle(chunk.len(), timeout)?;
             self.read_bytes_from_fifo(chunk)?;
         }
         Ok(())
@@ -1710,10 +2563,18 @@ pub trait Instance {
         Ok(())
     }
 
-    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Error> {
+    /// Like [`Instance::flush`], but gives up once the transfer of `len`
+    /// bytes takes longer than `timeout` allows
+    fn wait_idle(&self, len: usize, timeout: &Timeout) -> Result<(), Error> {
+        let reg_block = self.register_block();
+
+        timeout.wait(len, || reg_block.cmd.read().usr().bit_is_clear())
+    }
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8], timeout: &Timeout) -> Result<&'w [u8], Error> {
         for chunk in words.chunks_mut(FIFO_SIZE) {
-            self.write_bytes(chunk)?;
-            self.flush()?;
+            self.write_bytes(chunk, timeout)?;
+            self.wait_idle(chunk.len(), timeout)?;
             self.read_bytes_from_fifo(chunk)?;
         }
 
@@ -1784,6 +2645,36 @@ impl Instance for crate::peripherals::SPI2 {
         OutputSignal::FSPICS0
     }
 
+    #[inline(always)]
+    fn sio0_input_signal(&self) -> InputSignal {
+        InputSignal::FSPID
+    }
+
+    #[inline(always)]
+    fn sio1_output_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIQ
+    }
+
+    #[inline(always)]
+    fn sio2_input_signal(&self) -> InputSignal {
+        InputSignal::FSPIWP
+    }
+
+    #[inline(always)]
+    fn sio2_output_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIWP
+    }
+
+    #[inline(always)]
+    fn sio3_input_signal(&self) -> InputSignal {
+        InputSignal::FSPIHD
+    }
+
+    #[inline(always)]
+    fn sio3_output_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIHD
+    }
+
     #[inline(always)]
     fn enable_peripheral(&self, peripheral_clock_control: &mut PeripheralClockControl) {
         peripheral_clock_control.enable(crate::system::Peripheral::Spi2);
@@ -1898,6 +2789,42 @@ impl Instance for crate::peripherals::SPI2 {
         OutputSignal::FSPICS0
     }
 
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio0_input_signal(&self) -> InputSignal {
+        InputSignal::FSPID
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio1_output_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIQ
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio2_input_signal(&self) -> InputSignal {
+        InputSignal::FSPIWP
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio2_output_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIWP
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio3_input_signal(&self) -> InputSignal {
+        InputSignal::FSPIHD
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio3_output_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIHD
+    }
+
     #[inline(always)]
     fn enable_peripheral(&self, peripheral_clock_control: &mut PeripheralClockControl) {
         peripheral_clock_control.enable(crate::system::Peripheral::Spi2)
@@ -1936,6 +2863,42 @@ impl Instance for crate::peripherals::SPI3 {
         OutputSignal::SPI3_CS0
     }
 
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio0_input_signal(&self) -> InputSignal {
+        InputSignal::SPI3_D
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio1_output_signal(&self) -> OutputSignal {
+        OutputSignal::SPI3_Q
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio2_input_signal(&self) -> InputSignal {
+        InputSignal::SPI3_WP
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio2_output_signal(&self) -> OutputSignal {
+        OutputSignal::SPI3_WP
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio3_input_signal(&self) -> InputSignal {
+        InputSignal::SPI3_HD
+    }
+
+    #[inline(always)]
+    #[cfg(esp32s3)]
+    fn sio3_output_signal(&self) -> OutputSignal {
+        OutputSignal::SPI3_HD
+    }
+
     #[inline(always)]
     fn enable_peripheral(&self, peripheral_clock_control: &mut PeripheralClockControl) {
         peripheral_clock_control.enable(crate::system::Peripheral::Spi3)
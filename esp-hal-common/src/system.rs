@@ -19,6 +19,7 @@ type SystemPeripheral = crate::peripherals::PCR;
 type SystemPeripheral = crate::peripherals::SYSTEM;
 
 /// Peripherals which can be enabled via [PeripheralClockControl]
+#[derive(Clone, Copy)]
 pub enum Peripheral {
     Spi2,
     #[cfg(spi3)]
@@ -55,6 +56,12 @@ pub enum Peripheral {
     Twai0,
     #[cfg(esp32c6)]
     Twai1,
+    #[cfg(esp32c3)]
+    DedicatedGpio,
+    #[cfg(esp32c3)]
+    Uhci0,
+    #[cfg(etm)]
+    Etm,
 }
 
 /// Controls the enablement of peripheral clocks.
@@ -190,8 +197,264 @@ impl PeripheralClockControl {
                 perip_clk_en1.modify(|_, w| w.crypto_aes_clk_en().set_bit());
                 perip_rst_en1.modify(|_, w| w.crypto_aes_rst().clear_bit());
             }
+            #[cfg(esp32c3)]
+            Peripheral::DedicatedGpio => {
+                system
+                    .cpu_peri_clk_en
+                    .modify(|_, w| w.clk_en_dedicated_gpio().set_bit());
+                system
+                    .cpu_peri_rst_en
+                    .modify(|_, w| w.rst_en_dedicated_gpio().clear_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::Uhci0 => {
+                perip_clk_en0.modify(|_, w| w.uhci0_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.uhci0_rst().clear_bit());
+            }
+        }
+    }
+
+    /// Disables the clock of the given peripheral
+    ///
+    /// The peripheral is not reset, its registers keep their values and
+    /// outputs routed to pins keep their last level. Call [`Self::enable`]
+    /// to use it again.
+    pub fn disable(&mut self, peripheral: Peripheral) {
+        let system = unsafe { &*SystemPeripheral::PTR };
+
+        #[cfg(not(esp32))]
+        let perip_clk_en0 = &system.perip_clk_en0;
+        #[cfg(esp32)]
+        let (perip_clk_en0, peri_clk_en) = { (&system.perip_clk_en, &system.peri_clk_en) };
+
+        #[cfg(any(esp32c2, esp32c3, esp32s2, esp32s3))]
+        let perip_clk_en1 = &system.perip_clk_en1;
+
+        match peripheral {
+            Peripheral::Spi2 => {
+                perip_clk_en0.modify(|_, w| w.spi2_clk_en().clear_bit());
+            }
+            #[cfg(spi3)]
+            Peripheral::Spi3 => {
+                perip_clk_en0.modify(|_, w| w.spi3_clk_en().clear_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::I2cExt0 => {
+                perip_clk_en0.modify(|_, w| w.i2c0_ext0_clk_en().clear_bit());
+            }
+            #[cfg(not(esp32))]
+            Peripheral::I2cExt0 => {
+                perip_clk_en0.modify(|_, w| w.i2c_ext0_clk_en().clear_bit());
+            }
+            #[cfg(i2c1)]
+            Peripheral::I2cExt1 => {
+                perip_clk_en0.modify(|_, w| w.i2c_ext1_clk_en().clear_bit());
+            }
+            #[cfg(rmt)]
+            Peripheral::Rmt => {
+                perip_clk_en0.modify(|_, w| w.rmt_clk_en().clear_bit());
+            }
+            Peripheral::Ledc => {
+                perip_clk_en0.modify(|_, w| w.ledc_clk_en().clear_bit());
+            }
+            #[cfg(mcpwm)]
+            Peripheral::Mcpwm0 => {
+                perip_clk_en0.modify(|_, w| w.pwm0_clk_en().clear_bit());
+            }
+            #[cfg(mcpwm)]
+            Peripheral::Mcpwm1 => {
+                perip_clk_en0.modify(|_, w| w.pwm1_clk_en().clear_bit());
+            }
+            #[cfg(any(esp32, esp32s2, esp32s3))]
+            Peripheral::Pcnt => {
+                perip_clk_en0.modify(|_, w| w.pcnt_clk_en().clear_bit());
+            }
+            #[cfg(any(esp32c2, esp32c3))]
+            Peripheral::ApbSarAdc => {
+                perip_clk_en0.modify(|_, w| w.apb_saradc_clk_en().clear_bit());
+            }
+            #[cfg(gdma)]
+            Peripheral::Gdma => {
+                perip_clk_en1.modify(|_, w| w.dma_clk_en().clear_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Dma => {
+                perip_clk_en0.modify(|_, w| w.spi_dma_clk_en().clear_bit());
+            }
+            #[cfg(esp32s2)]
+            Peripheral::Dma => {
+                perip_clk_en0.modify(|_, w| w.spi2_dma_clk_en().clear_bit());
+                perip_clk_en0.modify(|_, w| w.spi3_dma_clk_en().clear_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::I2s0 => {
+                // on ESP32-C3 note that i2s1_clk_en / rst is really I2s0
+                perip_clk_en0.modify(|_, w| w.i2s1_clk_en().clear_bit());
+            }
+            #[cfg(any(esp32s3, esp32, esp32s2))]
+            Peripheral::I2s0 => {
+                perip_clk_en0.modify(|_, w| w.i2s0_clk_en().clear_bit());
+            }
+            #[cfg(any(esp32s3, esp32))]
+            Peripheral::I2s1 => {
+                perip_clk_en0.modify(|_, w| w.i2s1_clk_en().clear_bit());
+            }
+            #[cfg(usb_otg)]
+            Peripheral::Usb => {
+                perip_clk_en0.modify(|_, w| w.usb_clk_en().clear_bit());
+            }
+            #[cfg(any(esp32s3, esp32c3))]
+            Peripheral::Twai => {
+                perip_clk_en0.modify(|_, w| w.twai_clk_en().clear_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Aes => {
+                peri_clk_en.modify(|r, w| unsafe { w.bits(r.bits() & (!1)) });
+            }
+            #[cfg(any(esp32c3, esp32s2, esp32s3))]
+            Peripheral::Aes => {
+                perip_clk_en1.modify(|_, w| w.crypto_aes_clk_en().clear_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::DedicatedGpio => {
+                system
+                    .cpu_peri_clk_en
+                    .modify(|_, w| w.clk_en_dedicated_gpio().clear_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::Uhci0 => {
+                perip_clk_en0.modify(|_, w| w.uhci0_clk_en().clear_bit());
+            }
         }
     }
+
+    /// Resets the given peripheral to its power-on defaults
+    ///
+    /// The reset line of the peripheral is asserted and released again, all
+    /// of its registers return to their reset values and the clock is left
+    /// enabled. Any driver using the peripheral has to configure it again
+    /// afterwards, so this is meant for recovering a wedged peripheral before
+    /// creating a new driver instance. This is safe at runtime for the
+    /// peripherals listed in [Peripheral], none of them is needed by the
+    /// system itself, but a DMA channel or interrupt still serving the
+    /// peripheral has to be stopped first.
+    ///
+    /// The UARTs can't be reset with this. Their clocks aren't managed by
+    /// [PeripheralClockControl], and UART0 carries the console of the ROM and
+    /// the bootloader, whose output would be cut off. A wedged UART is
+    /// recovered by releasing its driver with
+    /// [`Uart::free`](crate::uart::Uart::free) and configuring it again with a
+    /// new one.
+    pub fn reset(&mut self, peripheral: Peripheral) {
+        let system = unsafe { &*SystemPeripheral::PTR };
+
+        #[cfg(not(esp32))]
+        let perip_rst_en0 = &system.perip_rst_en0;
+        #[cfg(esp32)]
+        let (perip_rst_en0, peri_rst_en) = { (&system.perip_rst_en, &system.peri_rst_en) };
+
+        #[cfg(any(esp32c2, esp32c3, esp32s2, esp32s3))]
+        let perip_rst_en1 = &system.perip_rst_en1;
+
+        match peripheral {
+            Peripheral::Spi2 => {
+                perip_rst_en0.modify(|_, w| w.spi2_rst().set_bit());
+            }
+            #[cfg(spi3)]
+            Peripheral::Spi3 => {
+                perip_rst_en0.modify(|_, w| w.spi3_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::I2cExt0 => {
+                perip_rst_en0.modify(|_, w| w.i2c0_ext0_rst().set_bit());
+            }
+            #[cfg(not(esp32))]
+            Peripheral::I2cExt0 => {
+                perip_rst_en0.modify(|_, w| w.i2c_ext0_rst().set_bit());
+            }
+            #[cfg(i2c1)]
+            Peripheral::I2cExt1 => {
+                perip_rst_en0.modify(|_, w| w.i2c_ext1_rst().set_bit());
+            }
+            #[cfg(rmt)]
+            Peripheral::Rmt => {
+                perip_rst_en0.modify(|_, w| w.rmt_rst().set_bit());
+            }
+            Peripheral::Ledc => {
+                perip_rst_en0.modify(|_, w| w.ledc_rst().set_bit());
+            }
+            #[cfg(mcpwm)]
+            Peripheral::Mcpwm0 => {
+                perip_rst_en0.modify(|_, w| w.pwm0_rst().set_bit());
+            }
+            #[cfg(mcpwm)]
+            Peripheral::Mcpwm1 => {
+                perip_rst_en0.modify(|_, w| w.pwm1_rst().set_bit());
+            }
+            #[cfg(any(esp32, esp32s2, esp32s3))]
+            Peripheral::Pcnt => {
+                perip_rst_en0.modify(|_, w| w.pcnt_rst().set_bit());
+            }
+            #[cfg(any(esp32c2, esp32c3))]
+            Peripheral::ApbSarAdc => {
+                perip_rst_en0.modify(|_, w| w.apb_saradc_rst().set_bit());
+            }
+            #[cfg(gdma)]
+            Peripheral::Gdma => {
+                perip_rst_en1.modify(|_, w| w.dma_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Dma => {
+                perip_rst_en0.modify(|_, w| w.spi_dma_rst().set_bit());
+            }
+            #[cfg(esp32s2)]
+            Peripheral::Dma => {
+                perip_rst_en0.modify(|_, w| w.spi2_dma_rst().set_bit());
+                perip_rst_en0.modify(|_, w| w.spi3_dma_rst().set_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::I2s0 => {
+                // on ESP32-C3 note that i2s1_clk_en / rst is really I2s0
+                perip_rst_en0.modify(|_, w| w.i2s1_rst().set_bit());
+            }
+            #[cfg(any(esp32s3, esp32, esp32s2))]
+            Peripheral::I2s0 => {
+                perip_rst_en0.modify(|_, w| w.i2s0_rst().set_bit());
+            }
+            #[cfg(any(esp32s3, esp32))]
+            Peripheral::I2s1 => {
+                perip_rst_en0.modify(|_, w| w.i2s1_rst().set_bit());
+            }
+            #[cfg(usb_otg)]
+            Peripheral::Usb => {
+                perip_rst_en0.modify(|_, w| w.usb_rst().set_bit());
+            }
+            #[cfg(any(esp32s3, esp32c3))]
+            Peripheral::Twai => {
+                perip_rst_en0.modify(|_, w| w.twai_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Aes => {
+                peri_rst_en.modify(|r, w| unsafe { w.bits(r.bits() | 1) });
+            }
+            #[cfg(any(esp32c3, esp32s2, esp32s3))]
+            Peripheral::Aes => {
+                perip_rst_en1.modify(|_, w| w.crypto_aes_rst().set_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::DedicatedGpio => {
+                system
+                    .cpu_peri_rst_en
+                    .modify(|_, w| w.rst_en_dedicated_gpio().set_bit());
+            }
+            #[cfg(esp32c3)]
+            Peripheral::Uhci0 => {
+                perip_rst_en0.modify(|_, w| w.uhci0_rst().set_bit());
+            }
+        }
+
+        self.enable(peripheral);
+    }
 }
 
 #[cfg(esp32c6)]
@@ -257,8 +520,136 @@ impl PeripheralClockControl {
                 system.pcnt_conf.modify(|_, w| w.pcnt_clk_en().set_bit());
                 system.pcnt_conf.modify(|_, w| w.pcnt_rst_en().clear_bit());
             }
+            Peripheral::Etm => {
+                system.etm_conf.modify(|_, w| w.etm_clk_en().set_bit());
+                system.etm_conf.modify(|_, w| w.etm_rst_en().clear_bit());
+            }
         }
     }
+
+    /// Disables the clock of the given peripheral
+    ///
+    /// The peripheral is not reset, its registers keep their values and
+    /// outputs routed to pins keep their last level. Call [`Self::enable`]
+    /// to use it again.
+    pub fn disable(&mut self, peripheral: Peripheral) {
+        let system = unsafe { &*SystemPeripheral::PTR };
+
+        match peripheral {
+            Peripheral::Spi2 => {
+                system.spi2_conf.modify(|_, w| w.spi2_clk_en().clear_bit());
+            }
+            Peripheral::I2cExt0 => {
+                system.i2c_conf.modify(|_, w| w.i2c_clk_en().clear_bit());
+            }
+            Peripheral::Rmt => {
+                system.rmt_conf.modify(|_, w| w.rmt_clk_en().clear_bit());
+            }
+            Peripheral::Ledc => {
+                system.ledc_conf.modify(|_, w| w.ledc_clk_en().clear_bit());
+            }
+            Peripheral::Mcpwm0 | Peripheral::Mcpwm1 => {
+                system.pwm_conf.modify(|_, w| w.pwm_clk_en().clear_bit());
+            }
+            Peripheral::ApbSarAdc => {
+                system
+                    .saradc_conf
+                    .modify(|_, w| w.saradc_reg_clk_en().clear_bit());
+            }
+            Peripheral::Gdma => {
+                system.gdma_conf.modify(|_, w| w.gdma_clk_en().clear_bit());
+            }
+            Peripheral::I2s0 => {
+                system.i2s_conf.modify(|_, w| w.i2s_clk_en().clear_bit());
+            }
+            Peripheral::Twai0 => {
+                system
+                    .twai0_conf
+                    .modify(|_, w| w.twai0_clk_en().clear_bit());
+            }
+            Peripheral::Twai1 => {
+                system
+                    .twai1_conf
+                    .modify(|_, w| w.twai1_clk_en().clear_bit());
+            }
+            Peripheral::Aes => {
+                system.aes_conf.modify(|_, w| w.aes_clk_en().clear_bit());
+            }
+            Peripheral::Pcnt => {
+                system.pcnt_conf.modify(|_, w| w.pcnt_clk_en().clear_bit());
+            }
+            Peripheral::Etm => {
+                system.etm_conf.modify(|_, w| w.etm_clk_en().clear_bit());
+            }
+        }
+    }
+
+    /// Resets the given peripheral to its power-on defaults
+    ///
+    /// The reset line of the peripheral is asserted and released again, all
+    /// of its registers return to their reset values and the clock is left
+    /// enabled. Any driver using the peripheral has to configure it again
+    /// afterwards, so this is meant for recovering a wedged peripheral before
+    /// creating a new driver instance. This is safe at runtime for the
+    /// peripherals listed in [Peripheral], none of them is needed by the
+    /// system itself, but a DMA channel or interrupt still serving the
+    /// peripheral has to be stopped first.
+    ///
+    /// The UARTs can't be reset with this. Their clocks aren't managed by
+    /// [PeripheralClockControl], and UART0 carries the console of the ROM and
+    /// the bootloader, whose output would be cut off. A wedged UART is
+    /// recovered by releasing its driver with
+    /// [`Uart::free`](crate::uart::Uart::free) and configuring it again with a
+    /// new one.
+    pub fn reset(&mut self, peripheral: Peripheral) {
+        let system = unsafe { &*SystemPeripheral::PTR };
+
+        match peripheral {
+            Peripheral::Spi2 => {
+                system.spi2_conf.modify(|_, w| w.spi2_rst_en().set_bit());
+            }
+            Peripheral::I2cExt0 => {
+                system.i2c_conf.modify(|_, w| w.i2c_rst_en().set_bit());
+            }
+            Peripheral::Rmt => {
+                system.rmt_conf.modify(|_, w| w.rmt_rst_en().set_bit());
+            }
+            Peripheral::Ledc => {
+                system.ledc_conf.modify(|_, w| w.ledc_rst_en().set_bit());
+            }
+            Peripheral::Mcpwm0 | Peripheral::Mcpwm1 => {
+                system.pwm_conf.modify(|_, w| w.pwm_rst_en().set_bit());
+            }
+            Peripheral::ApbSarAdc => {
+                system
+                    .saradc_conf
+                    .modify(|_, w| w.saradc_reg_rst_en().set_bit());
+            }
+            Peripheral::Gdma => {
+                system.gdma_conf.modify(|_, w| w.gdma_rst_en().set_bit());
+            }
+            Peripheral::I2s0 => {
+                system.i2s_conf.modify(|_, w| w.i2s_rst_en().set_bit());
+            }
+            Peripheral::Twai0 => {
+                system.twai0_conf.modify(|_, w| w.twai0_rst_en().set_bit());
+            }
+            Peripheral::Twai1 => {
+                system.twai1_conf.modify(|_, w| w.twai1_rst_en().set_bit());
+            }
+            Peripheral::Aes => {
+                system.aes_conf.modify(|_, w| w.aes_rst_en().set_bit());
+            }
+            Peripheral::Pcnt => {
+                system.pcnt_conf.modify(|_, w| w.pcnt_rst_en().set_bit());
+            }
+            Peripheral::Etm => {
+                system.etm_conf.modify(|_, w| w.etm_rst_en().set_bit());
+            }
+        }
+
+        self.enable(peripheral);
+    }
 }
 
 /// Controls the configuration of the chip's clocks.
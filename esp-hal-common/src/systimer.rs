@@ -1,4 +1,4 @@
-use core::{intrinsics::transmute, marker::PhantomData};
+use core::{cmp::Ordering, intrinsics::transmute, marker::PhantomData};
 
 use fugit::MillisDurationU32;
 
@@ -15,7 +15,7 @@ use crate::{
     },
 };
 
-// TODO this only handles unit0 of the systimer
+// TODO the alarms only use unit0 of the systimer
 
 pub struct SystemTimer<'d> {
     _inner: PeripheralRef<'d, SYSTIMER>,
@@ -45,6 +45,94 @@ impl<'d> SystemTimer<'d> {
         }
     }
 
+    /// Freeze the counter while the CPU is halted by a debugger
+    ///
+    /// Without this the counter keeps running at a breakpoint, so after
+    /// resuming every timeout in progress has expired. With it the counter
+    /// (and with it [`Self::now`], the alarms and the embassy time driver)
+    /// doesn't advance while halted, time on the chip falls behind the wall
+    /// clock by the time spent halted instead.
+    ///
+    /// This sets the `TIMER_UNIT0_CORE0_STALL_EN` bit of `SYSTIMER_CONF_REG`,
+    /// and `TIMER_UNIT0_CORE1_STALL_EN` on the ESP32-S3 so that halting
+    /// either core stalls the counter. The ESP32-S2 has no such bit.
+    #[cfg(not(esp32s2))]
+    pub fn stall_on_debug(&mut self, enabled: bool) {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        systimer.conf.modify(|_, w| {
+            #[cfg(esp32s3)]
+            w.timer_unit0_core1_stall_en().bit(enabled);
+
+            w.timer_unit0_core0_stall_en().bit(enabled)
+        });
+    }
+
+    /// Start or stop counter unit 1
+    ///
+    /// The SYSTIMER has a second counter, independent of unit 0 which backs
+    /// [`Self::now`] and the alarms. It is stopped after reset. Stopping it
+    /// keeps its value, starting it again continues from there.
+    #[cfg(not(esp32s2))]
+    pub fn enable_unit1(&mut self, enabled: bool) {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        systimer
+            .conf
+            .modify(|_, w| w.timer_unit1_work_en().bit(enabled));
+    }
+
+    /// Freeze counter unit 1 while the CPU is halted by a debugger, see
+    /// [`Self::stall_on_debug`]
+    ///
+    /// Together with [`Self::stall_on_debug`] the two units can be set up
+    /// differently, e.g. to measure the time spent at breakpoints. The stall
+    /// only applies to a halted CPU: the hardware has no setting to stop a
+    /// unit during light sleep, where both units behave the same.
+    #[cfg(not(esp32s2))]
+    pub fn stall_unit1_on_debug(&mut self, enabled: bool) {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        systimer.conf.modify(|_, w| {
+            #[cfg(esp32s3)]
+            w.timer_unit1_core1_stall_en().bit(enabled);
+
+            w.timer_unit1_core0_stall_en().bit(enabled)
+        });
+    }
+
+    /// Let the alarms generate events for the [ETM](crate::etm)
+    ///
+    /// Without it, the SYSTIMER events like
+    /// [`Event::SystimerAlarm0`](crate::etm::Event::SystimerAlarm0) never
+    /// occur. The alarm interrupts are not affected.
+    #[cfg(etm)]
+    pub fn enable_etm(&mut self, enabled: bool) {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        systimer.conf.modify(|_, w| w.etm_en().bit(enabled));
+    }
+
+    /// The raw value of counter unit 1, see [`Self::enable_unit1`]
+    ///
+    /// Counts at [`Self::TICKS_PER_SECOND`] and wraps at [`Self::BIT_MASK`],
+    /// like unit 0.
+    #[cfg(not(esp32s2))]
+    pub fn now_unit1() -> u64 {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        systimer
+            .unit1_op
+            .modify(|_, w| w.timer_unit1_update().set_bit());
+
+        while !systimer
+            .unit1_op
+            .read()
+            .timer_unit1_value_valid()
+            .bit_is_set()
+        {}
+
+        let value_lo = systimer.unit1_value_lo.read().bits();
+        let value_hi = systimer.unit1_value_hi.read().bits();
+
+        ((value_hi as u64) << 32) | value_lo as u64
+    }
+
     // TODO use fugit types
     pub fn now() -> u64 {
         // This should be safe to access from multiple contexts
@@ -67,11 +155,197 @@ impl<'d> SystemTimer<'d> {
 
         ((value_hi as u64) << 32) | value_lo as u64
     }
+
+    /// Move counter unit 0 forward by `ticks`, e.g. to account for a light
+    /// sleep it didn't count through
+    ///
+    /// The new value is loaded into the counter, a few ticks passing between
+    /// reading and loading it are lost.
+    #[cfg(not(esp32s2))]
+    pub(crate) fn advance(ticks: u64) {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        let value = (Self::now() + ticks) & Self::BIT_MASK;
+
+        systimer
+            .unit0_load_hi
+            .write(|w| unsafe { w.bits((value >> 32) as u32) });
+        systimer
+            .unit0_load_lo
+            .write(|w| unsafe { w.bits(value as u32) });
+        systimer.unit0_load.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// Number of ticks elapsed going from `earlier` to `later`, taking the
+    /// wrap of the counter at [`Self::BIT_MASK`] into account
+    pub fn ticks_between(earlier: u64, later: u64) -> u64 {
+        later.wrapping_sub(earlier) & Self::BIT_MASK
+    }
+
+    /// Compare two timestamps obtained from [`Self::now`] in modular
+    /// arithmetic
+    ///
+    /// The counter wraps at [`Self::BIT_MASK`], so comparing raw tick values
+    /// gives the wrong answer for instants on either side of the wrap. Here a
+    /// difference of more than half the counter range is treated as having
+    /// wrapped, which means the result is only meaningful as long as the two
+    /// instants are less than half the range apart.
+    pub fn wrapping_compare(a: u64, b: u64) -> Ordering {
+        match Self::ticks_between(a, b) {
+            0 => Ordering::Equal,
+            diff if diff <= Self::BIT_MASK / 2 => Ordering::Less,
+            _ => Ordering::Greater,
+        }
+    }
+
+    /// Returns `true` if the current time is at or past `deadline`
+    ///
+    /// See [`Self::wrapping_compare`] for the assumption made about the
+    /// distance between now and the deadline.
+    pub fn has_elapsed(deadline: u64) -> bool {
+        Self::wrapping_compare(Self::now(), deadline) != Ordering::Less
+    }
+
+    /// The current time as a [`SystemTimerInstant`]
+    pub fn now_instant() -> SystemTimerInstant {
+        SystemTimerInstant::from_ticks(Self::now())
+    }
+
+    /// Busy-wait until `deadline`, given in ticks
+    ///
+    /// This spins on the counter without sleeping or using an alarm, so it
+    /// returns within a few ticks of the deadline. Deadlines computed by adding
+    /// to a value from [`Self::now`] may exceed [`Self::BIT_MASK`], they are
+    /// compared in modular arithmetic like in [`Self::wrapping_compare`].
+    ///
+    /// Waiting for successive deadlines `period` apart keeps a fixed cadence
+    /// independent of how long the work in between takes, unlike delaying for
+    /// `period` after the work is done:
+    ///
+    /// ```rust,ignore
+    /// let mut next = SystemTimer::now();
+    /// loop {
+    ///     do_work();
+    ///     next += SystemTimer::TICKS_PER_SECOND / 100;
+    ///     SystemTimer::wait_until(next);
+    /// }
+    /// ```
+    pub fn wait_until(deadline: u64) {
+        while !Self::has_elapsed(deadline) {}
+    }
+
+    /// Busy-wait until `instant`, see [`Self::wait_until`]
+    pub fn wait_until_instant(instant: SystemTimerInstant) {
+        Self::wait_until(instant.ticks());
+    }
 }
 
+/// An instant in [`SystemTimer`] ticks
+#[cfg(esp32s2)]
+pub type SystemTimerInstant = fugit::Instant<u64, 1, 80_000_000>;
+/// An instant in [`SystemTimer`] ticks
+#[cfg(not(esp32s2))]
+pub type SystemTimerInstant = fugit::Instant<u64, 1, 16_000_000>;
+
+/// Fixed-rate loop timing against absolute deadlines
+///
+/// Delaying for the period after every iteration adds the time the work took
+/// to every cycle, so the loop drifts. [`Scheduler::wait_next`] instead waits
+/// for `start + n * period`, which keeps the rate exact no matter how long the
+/// work takes, as long as it takes less than a period:
+///
+/// ```rust,ignore
+/// let mut scheduler = Scheduler::new(1u64.millis());
+/// loop {
+///     control_step();
+///     if let Err(overrun) = scheduler.wait_next() {
+///         println!("missed {} deadlines", overrun.missed);
+///     }
+/// }
+/// ```
+///
+/// Deadlines are computed in wrapping arithmetic and compared with
+/// [`SystemTimer::has_elapsed`], so the schedule continues unaffected when the
+/// counter wraps at [`SystemTimer::BIT_MASK`] (after about 8.9 years at 16
+/// MHz). The period has to be shorter than half of that range.
+#[derive(Debug)]
+pub struct Scheduler {
+    period: u64,
+    next: u64,
+    overruns: u32,
+}
+
+/// The loop didn't keep up with the period of a [`Scheduler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overrun {
+    /// Number of deadlines that had already passed
+    pub missed: u32,
+}
+
+impl Scheduler {
+    /// Start a schedule with the first deadline one `period` from now
+    pub fn new(period: fugit::MicrosDurationU64) -> Self {
+        let period = period.to_micros() * (SystemTimer::TICKS_PER_SECOND / 1_000_000);
+        assert!(period > 0, "The period can't be zero");
+
+        Self {
+            period,
+            next: SystemTimer::now(),
+            overruns: 0,
+        }
+    }
+
+    /// Wait for the next deadline
+    ///
+    /// If the deadline has already passed, this returns right away with the
+    /// number of missed deadlines. The missed iterations are skipped rather
+    /// than caught up with: the schedule moves on to the latest deadline
+    /// passed, so the following deadlines stay on the original grid.
+    pub fn wait_next(&mut self) -> Result<(), Overrun> {
+        let deadline = (self.next + self.period) & SystemTimer::BIT_MASK;
+
+        if SystemTimer::has_elapsed(deadline) {
+            let late = SystemTimer::ticks_between(deadline, SystemTimer::now());
+            let skipped = late / self.period;
+
+            self.next = (deadline + skipped * self.period) & SystemTimer::BIT_MASK;
+            self.overruns = self.overruns.saturating_add(1);
+
+            return Err(Overrun {
+                missed: (skipped + 1) as u32,
+            });
+        }
+
+        SystemTimer::wait_until(deadline);
+        self.next = deadline;
+
+        Ok(())
+    }
+
+    /// The deadline [`Scheduler::wait_next`] last waited for, or the start
+    /// of the schedule
+    pub fn last_deadline(&self) -> u64 {
+        self.next
+    }
+
+    /// Number of calls to [`Scheduler::wait_next`] which found their deadline
+    /// already passed
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+}
+
+/// Alarm mode: fire once when the counter reaches a target value
+///
+/// An alarm is either in `Target` or in [`Periodic`] mode, never both, the
+/// mode is part of its type. Convert between them with
+/// [`Alarm::into_periodic`] and [`Alarm::into_target`].
 #[derive(Debug)]
 pub struct Target;
 
+/// Alarm mode: fire repeatedly at a fixed period
+///
+/// The comparator reloads itself in hardware every time it fires, no CPU
+/// intervention is needed to keep the period, only to clear the interrupt.
 #[derive(Debug)]
 pub struct Periodic; // TODO, also impl e-h timer traits
 
@@ -199,15 +473,35 @@ impl<const CHANNEL: u8> Alarm<Target, CHANNEL> {
 }
 
 impl<const CHANNEL: u8> Alarm<Periodic, CHANNEL> {
+    /// Longest period of [`Self::set_period_ticks`], the period register has
+    /// 26 bits (about 4.2 s at 16 MHz, 0.84 s at the 80 MHz of the ESP32-S2)
+    pub const MAX_PERIOD_TICKS: u32 = (1 << 26) - 1;
+
+    /// Fire at `period`, rounded to whole milliseconds
     pub fn set_period(&self, period: fugit::HertzU32) {
         let time_period: MillisDurationU32 = period.into_duration();
         let cycles = time_period.ticks();
+
+        self.write_period(cycles * (SystemTimer::TICKS_PER_SECOND as u32 / 1000));
+    }
+
+    /// Fire every `ticks` ticks of the counter
+    ///
+    /// Unlike [`Self::set_period`] this isn't limited to whole milliseconds.
+    /// `ticks` must not be 0 or exceed [`Self::MAX_PERIOD_TICKS`].
+    pub fn set_period_ticks(&self, ticks: u32) {
+        assert!(ticks > 0 && ticks <= Self::MAX_PERIOD_TICKS);
+
+        self.write_period(ticks);
+    }
+
+    fn write_period(&self, ticks: u32) {
         self.configure(|tconf, hi, lo| unsafe {
             tconf.write(|w| {
                 w.target0_period_mode()
                     .set_bit()
                     .target0_period()
-                    .bits(cycles * (SystemTimer::TICKS_PER_SECOND as u32 / 1000))
+                    .bits(ticks)
             });
             hi.write(|w| w.timer_target0_hi().bits(0));
             lo.write(|w| w.timer_target0_lo().bits(0));
@@ -20,12 +20,21 @@ use crate::{
     peripherals::{timg0::RegisterBlock, TIMG0},
 };
 
+// TnUPDATE: latches the counter into TnLO/TnHI. Any write does so on the
+// ESP32, the other chips need this bit set.
+const COUNTER_UPDATE: u32 = 1 << 31;
+
+// Written to WDTWPROTECT to unlock the watchdog registers
+const WDT_WRITE_PROTECT_KEY: u32 = 0x50D8_3AA1;
+
 /// Custom timer error type
 #[derive(Debug)]
 pub enum Error {
     TimerActive,
     TimerInactive,
     AlarmInactive,
+    /// The divider is outside of 2 to 65536
+    InvalidDivider,
 }
 
 // A timergroup consisting of up to 2 timers (chip dependent) and a watchdog
@@ -95,9 +104,29 @@ where
 }
 
 /// General-purpose timer
+///
+/// Besides polling it through [`CountDown`], the timer can raise an interrupt
+/// on every expiry: [`Instance::listen`] enables it, the handler calls
+/// [`Instance::clear_interrupt`]. Started with [`CountDown::start`] the
+/// counter reloads itself, but the hardware disables the alarm when it
+/// triggers: for a periodic interrupt the handler also re-enables it with
+/// [`Instance::set_alarm_active`], like [`CountDown::wait`] does. The handler
+/// can be an `#[interrupt]` function or be registered with
+/// [`interrupt::set_handler`](crate::interrupt::set_handler).
+///
+/// The timers raise these peripheral interrupts:
+///
+/// | Timer         | ESP32, -S2, -S3 | ESP32-C3, -C6  | ESP32-C2       |
+/// |---------------|-----------------|----------------|----------------|
+/// | TIMG0 timer 0 | `TG0_T0_LEVEL`  | `TG0_T0_LEVEL` | `TG0_T0_LEVEL` |
+/// | TIMG0 timer 1 | `TG0_T1_LEVEL`  |                |                |
+/// | TIMG1 timer 0 | `TG1_T0_LEVEL`  | `TG1_T0_LEVEL` |                |
+/// | TIMG1 timer 1 | `TG1_T1_LEVEL`  |                |                |
 pub struct Timer<T> {
     timg: T,
     apb_clk_freq: HertzU32,
+    // callback passed to `schedule_once`, cleared once it ran
+    scheduled: Option<fn()>,
 }
 
 /// Timer driver
@@ -111,7 +140,11 @@ where
         //       way to select the XTAL_CLK.
         #[cfg(esp32c6)]
         Self::enable_clock();
-        Self { timg, apb_clk_freq }
+        Self {
+            timg,
+            apb_clk_freq,
+            scheduled: None,
+        }
     }
 
     /// Return the raw interface to the underlying timer instance
@@ -119,6 +152,121 @@ where
         self.timg
     }
 
+    /// Set the prescaler dividing the APB clock for the counter
+    ///
+    /// The counter advances once every `divider` APB clock cycles, so one tick
+    /// lasts `divider / APB clock`: with an 80 MHz APB clock a divider of 80
+    /// gives 1 µs ticks and the largest divider of 65536 about 819 µs ticks.
+    /// The counter is 64 bits wide on the ESP32 and ESP32-S2 and 54 bits wide
+    /// on the ESP32-C2, ESP32-C3, ESP32-C6 and ESP32-S3, where it wraps after
+    /// 2^54 ticks (about 14 years with 25 ns ticks). The divider ranges from
+    /// 2 to 65536, out of reset it is 2.
+    ///
+    /// Durations passed to [`CountDown::start`] or
+    /// [`Timer::schedule_once`] are converted with the divider in effect,
+    /// a smaller divider gives a finer resolution, a larger one longer
+    /// periods. Change it while the timer is stopped.
+    pub fn set_divider(&mut self, divider: u32) -> Result<(), Error> {
+        let bits = match divider {
+            65536 => 0,
+            2..=65535 => divider as u16,
+            _ => return Err(Error::InvalidDivider),
+        };
+        self.timg.set_divider(bits);
+
+        Ok(())
+    }
+
+    /// The frequency the counter advances with, the APB clock divided by the
+    /// divider
+    pub fn tick_frequency(&self) -> HertzU32 {
+        HertzU32::from_raw(self.apb_clk_freq.raw() / self.timg.divider())
+    }
+
+    /// Start the timer as a periodic count down of `ticks` counter ticks
+    ///
+    /// Like [`CountDown::start`], but without converting a duration: the
+    /// counter runs at the APB clock divided by the divider of the timer. Use
+    /// [`CountDown::wait`] to wait for each period.
+    pub fn start_raw(&mut self, ticks: u64) {
+        self.timg.set_counter_active(false);
+        self.timg.set_alarm_active(false);
+
+        self.timg.reset_counter();
+        self.timg.load_alarm_value(ticks);
+
+        self.timg.set_counter_decrementing(false);
+        self.timg.set_auto_reload(true);
+        self.timg.set_counter_active(true);
+        self.timg.set_alarm_active(true);
+    }
+
+    /// Call `callback` once after `duration`, from the timer interrupt
+    ///
+    /// This starts the timer as a one-shot and enables its interrupt, which
+    /// also has to be enabled with [`crate::interrupt::enable`]. The handler
+    /// of that interrupt needs access to the timer (e.g. through a
+    /// `critical_section::Mutex`) and has to call [`Timer::on_interrupt`],
+    /// which runs the callback. Scheduling again before the callback ran
+    /// replaces it and restarts the timer, [`Timer::cancel_scheduled`] drops
+    /// it.
+    ///
+    /// The callback runs in interrupt context: it delays all interrupts of the
+    /// same and lower priorities and must not block, e.g. by waiting for
+    /// something another interrupt or the main code does. It also runs while
+    /// the handler holds the timer, so it can't access the timer itself.
+    pub fn schedule_once(&mut self, duration: MicrosDurationU64, callback: fn()) {
+        self.timg.set_counter_active(false);
+        self.timg.set_alarm_active(false);
+        self.timg.clear_interrupt();
+
+        self.timg.reset_counter();
+        let ticks = self.duration_to_ticks(duration);
+        self.timg.load_alarm_value(ticks);
+
+        self.scheduled = Some(callback);
+
+        self.timg.set_counter_decrementing(false);
+        self.timg.set_auto_reload(false);
+        self.timg.listen();
+        self.timg.set_counter_active(true);
+        self.timg.set_alarm_active(true);
+    }
+
+    /// Drop the callback passed to [`Timer::schedule_once`] if it didn't run
+    /// yet
+    pub fn cancel_scheduled(&mut self) {
+        if self.scheduled.take().is_some() {
+            self.timg.unlisten();
+            self.timg.set_counter_active(false);
+            self.timg.clear_interrupt();
+        }
+    }
+
+    /// Run the callback passed to [`Timer::schedule_once`] if it is due
+    ///
+    /// To be called from the interrupt handler of the timer. Clears the
+    /// interrupt, stops the timer and runs the callback, after which it is
+    /// forgotten.
+    pub fn on_interrupt(&mut self) {
+        if !self.timg.is_interrupt_set() {
+            return;
+        }
+
+        self.timg.clear_interrupt();
+
+        if let Some(callback) = self.scheduled.take() {
+            self.timg.unlisten();
+            self.timg.set_counter_active(false);
+            callback();
+        }
+    }
+
+    /// Convert a duration into ticks of the timer's counter
+    pub(crate) fn duration_to_ticks(&self, duration: MicrosDurationU64) -> u64 {
+        timeout_to_ticks(duration, self.apb_clk_freq, self.timg.divider())
+    }
+
     #[cfg(esp32c6)]
     fn enable_clock() {
         let pcr = unsafe { &*crate::peripherals::PCR::ptr() };
@@ -172,18 +320,36 @@ pub trait Instance {
 
     fn load_alarm_value(&mut self, value: u64);
 
+    /// Enable the interrupt raised when the alarm triggers
+    ///
+    /// The interrupt is level triggered and stays raised until
+    /// [`Instance::clear_interrupt`] is called. See [`Timer`] for the
+    /// peripheral interrupt of each timer.
     fn listen(&mut self);
 
+    /// Disable the alarm interrupt
     fn unlisten(&mut self);
 
+    /// Clear a raised alarm interrupt
     fn clear_interrupt(&mut self);
 
+    /// The current 64-bit counter value, in ticks of the divided clock
+    ///
+    /// The counter can't be read directly: writing the update register
+    /// latches it into the `lo`/`hi` registers, which are read afterwards.
+    /// The latch and both reads happen in a critical section, so an
+    /// interrupt reading the same counter in between can't tear the value.
     fn now(&self) -> u64;
 
+    /// The prescaler dividing the APB clock for the counter, see
+    /// [`Timer::set_divider`]
     fn divider(&self) -> u32;
 
+    /// Write the raw divider field, where 0 divides by 65536 and 1 by 2
     fn set_divider(&mut self, divider: u16);
 
+    /// Whether the alarm interrupt is raised, independent of whether it is
+    /// enabled with [`Instance::listen`]
     fn is_interrupt_set(&self) -> bool;
 }
 
@@ -291,12 +457,20 @@ where
     fn now(&self) -> u64 {
         let reg_block = unsafe { &*TG::register_block() };
 
-        reg_block.t0update.write(|w| unsafe { w.bits(0) });
+        critical_section::with(|_| {
+            reg_block
+                .t0update
+                .write(|w| unsafe { w.bits(COUNTER_UPDATE) });
+            // the counter runs in another clock domain, the latch completes
+            // when the hardware clears the bit again
+            #[cfg(not(esp32))]
+            while reg_block.t0update.read().bits() & COUNTER_UPDATE != 0 {}
 
-        let value_lo = reg_block.t0lo.read().bits() as u64;
-        let value_hi = (reg_block.t0hi.read().bits() as u64) << 32;
+            let value_lo = reg_block.t0lo.read().bits() as u64;
+            let value_hi = (reg_block.t0hi.read().bits() as u64) << 32;
 
-        (value_lo | value_hi) as u64
+            value_lo | value_hi
+        })
     }
 
     fn divider(&self) -> u32 {
@@ -436,12 +610,20 @@ where
     fn now(&self) -> u64 {
         let reg_block = unsafe { &*TG::register_block() };
 
-        reg_block.t1update.write(|w| unsafe { w.bits(0) });
+        critical_section::with(|_| {
+            reg_block
+                .t1update
+                .write(|w| unsafe { w.bits(COUNTER_UPDATE) });
+            // the counter runs in another clock domain, the latch completes
+            // when the hardware clears the bit again
+            #[cfg(not(esp32))]
+            while reg_block.t1update.read().bits() & COUNTER_UPDATE != 0 {}
 
-        let value_lo = reg_block.t1lo.read().bits() as u64;
-        let value_hi = (reg_block.t1hi.read().bits() as u64) << 32;
+            let value_lo = reg_block.t1lo.read().bits() as u64;
+            let value_hi = (reg_block.t1hi.read().bits() as u64) << 32;
 
-        (value_lo | value_hi) as u64
+            value_lo | value_hi
+        })
     }
 
     fn divider(&self) -> u32 {
@@ -500,21 +682,11 @@ where
     where
         Time: Into<Self::Time>,
     {
-        self.timg.set_counter_active(false);
-        self.timg.set_alarm_active(false);
-
-        self.timg.reset_counter();
-
         // TODO: this currently assumes APB_CLK is being used, as we don't yet have a
         //       way to select the XTAL_CLK.
         // TODO: can we cache the divider (only get it on initialization)?
         let ticks = timeout_to_ticks(timeout, self.apb_clk_freq, self.timg.divider());
-        self.timg.load_alarm_value(ticks);
-
-        self.timg.set_counter_decrementing(false);
-        self.timg.set_auto_reload(true);
-        self.timg.set_counter_active(true);
-        self.timg.set_alarm_active(true);
+        self.start_raw(ticks);
     }
 
     fn wait(&mut self) -> nb::Result<(), Void> {
@@ -555,8 +727,42 @@ where
 impl<T> Periodic for Timer<T> where T: Instance {}
 
 /// Watchdog timer
+///
+/// The MWDT of the timer group, started with [`WatchdogEnable::start`], fed
+/// with [`Watchdog::feed`] and stopped with [`WatchdogDisable::disable`]. It
+/// counts the APB clock with a prescaler that is chosen for the timeout,
+/// timeouts up to about 40 days are possible.
+///
+/// By default the watchdog resets the system when it expires. After
+/// [`Wdt::listen`] it instead raises the `TGx_WDT_LEVEL` interrupt first and
+/// only resets the system if it isn't fed within another timeout period. This
+/// gives a handler the chance to log diagnostics about the hang:
+///
+/// - The handler runs while the second stage is counting down, it has to finish
+///   within one timeout period or the log is cut short.
+/// - It must not feed the watchdog, that would restart the first stage and keep
+///   a hung system alive.
+/// - It must not wait for anything the hung code may be holding, e.g. a driver
+///   stored in a `Mutex`. To print, create a new [`Uart`] from a stolen UART
+///   peripheral, which keeps the baud rate configured by the application.
+/// - If the system hangs with interrupts disabled the handler never runs, the
+///   reset still happens.
+///
+/// # Write protection
+///
+/// The watchdog registers are write protected, so a runaway program can't
+/// disable or feed the watchdog by accident. Every write to them has to be
+/// preceded by writing the key `0x50D8_3AA1` to `WDTWPROTECT`, which unlocks
+/// them until anything else is written there, and followed by writing 0 to
+/// lock them again. Writes while locked are ignored without an error, which
+/// makes a forgotten unlock easy to miss: the watchdog keeps running with its
+/// old configuration and resets the system. All methods of this driver unlock
+/// and lock the registers themselves.
+///
+/// [`Uart`]: crate::Uart
 pub struct Wdt<TG> {
     phantom: PhantomData<TG>,
+    stage0_interrupt: bool,
 }
 
 /// Watchdog driver
@@ -570,9 +776,56 @@ where
         Self::enable_clock();
         Self {
             phantom: PhantomData::default(),
+            stage0_interrupt: false,
         }
     }
 
+    /// Raise an interrupt when the watchdog expires, resetting the system
+    /// only if it isn't fed within another timeout period
+    ///
+    /// Takes effect with the next call to `start`.
+    pub fn listen(&mut self) {
+        let reg_block = unsafe { &*TG::register_block() };
+
+        self.stage0_interrupt = true;
+
+        reg_block
+            .int_ena_timers
+            .modify(|_, w| w.wdt_int_ena().set_bit());
+    }
+
+    /// Reset the system right away when the watchdog expires
+    ///
+    /// Takes effect with the next call to `start`.
+    pub fn unlisten(&mut self) {
+        let reg_block = unsafe { &*TG::register_block() };
+
+        self.stage0_interrupt = false;
+
+        reg_block
+            .int_ena_timers
+            .modify(|_, w| w.wdt_int_ena().clear_bit());
+    }
+
+    /// Clear the watchdog interrupt
+    ///
+    /// This doesn't stop the second stage, the system is still reset unless
+    /// the watchdog is fed or disabled.
+    pub fn clear_interrupt(&mut self) {
+        let reg_block = unsafe { &*TG::register_block() };
+
+        reg_block
+            .int_clr_timers
+            .write(|w| w.wdt_int_clr().set_bit());
+    }
+
+    /// Check whether the watchdog interrupt is pending
+    pub fn is_interrupt_set(&self) -> bool {
+        let reg_block = unsafe { &*TG::register_block() };
+
+        reg_block.int_raw_timers.read().wdt_int_raw().bit_is_set()
+    }
+
     #[cfg(esp32c6)]
     fn enable_clock() {
         let pcr = unsafe { &*crate::peripherals::PCR::ptr() };
@@ -587,67 +840,80 @@ where
             .write(|w| unsafe { w.tg1_timer_clk_sel().bits(1) });
     }
 
-    fn set_wdt_enabled(&mut self, enabled: bool) {
+    // Run `f` with the watchdog registers unlocked, see "Write protection"
+    fn unlocked(f: impl FnOnce(&RegisterBlock)) {
         let reg_block = unsafe { &*TG::register_block() };
 
         reg_block
             .wdtwprotect
-            .write(|w| unsafe { w.wdt_wkey().bits(0x50D8_3AA1u32) });
+            .write(|w| unsafe { w.wdt_wkey().bits(WDT_WRITE_PROTECT_KEY) });
 
-        if !enabled {
-            reg_block.wdtconfig0.write(|w| unsafe { w.bits(0) });
-        } else {
-            reg_block.wdtconfig0.write(|w| w.wdt_en().bit(true));
-        }
+        f(reg_block);
 
         reg_block
             .wdtwprotect
             .write(|w| unsafe { w.wdt_wkey().bits(0u32) });
     }
 
-    fn feed(&mut self) {
-        let reg_block = unsafe { &*TG::register_block() };
-
-        reg_block
-            .wdtwprotect
-            .write(|w| unsafe { w.wdt_wkey().bits(0x50D8_3AA1u32) });
-
-        reg_block.wdtfeed.write(|w| unsafe { w.bits(1) });
+    fn set_wdt_enabled(&mut self, enabled: bool) {
+        Self::unlocked(|reg_block| {
+            if !enabled {
+                reg_block.wdtconfig0.write(|w| unsafe { w.bits(0) });
+            } else {
+                reg_block.wdtconfig0.write(|w| w.wdt_en().bit(true));
+            }
+        });
+    }
 
-        reg_block
-            .wdtwprotect
-            .write(|w| unsafe { w.wdt_wkey().bits(0u32) });
+    fn feed(&mut self) {
+        Self::unlocked(|reg_block| {
+            reg_block.wdtfeed.write(|w| unsafe { w.bits(1) });
+        });
     }
 
     fn set_timeout(&mut self, timeout: MicrosDurationU64) {
-        let timeout_raw = (timeout.to_nanos() * 10 / 125) as u32;
+        // The stages count in periods of 12.5 ns times the prescaler, pick the
+        // smallest prescaler that makes the timeout fit into the 32 bit hold
+        // registers
+        let cycles = timeout.to_nanos() * 10 / 125;
+        let prescale = (cycles / (u32::MAX as u64 + 1) + 1).min(u16::MAX as u64);
+        let timeout_raw = (cycles / prescale).min(u32::MAX as u64) as u32;
 
-        let reg_block = unsafe { &*TG::register_block() };
-
-        reg_block
-            .wdtwprotect
-            .write(|w| unsafe { w.wdt_wkey().bits(0x50D8_3AA1u32) });
+        Self::unlocked(|reg_block| self.configure_stages(reg_block, prescale as u16, timeout_raw));
+    }
 
+    fn configure_stages(&self, reg_block: &RegisterBlock, prescale: u16, timeout_raw: u32) {
         reg_block
             .wdtconfig1
-            .write(|w| unsafe { w.wdt_clk_prescale().bits(1) });
+            .write(|w| unsafe { w.wdt_clk_prescale().bits(prescale) });
 
         reg_block
             .wdtconfig2
             .write(|w| unsafe { w.wdt_stg0_hold().bits(timeout_raw) });
 
+        reg_block
+            .wdtconfig3
+            .write(|w| unsafe { w.wdt_stg1_hold().bits(timeout_raw) });
+
+        // stage actions: 1 = interrupt, 3 = reset system
+        let (stage0, stage1) = if self.stage0_interrupt {
+            (1, 3)
+        } else {
+            (3, 0)
+        };
+
         #[cfg_attr(esp32, allow(unused_unsafe))]
         reg_block.wdtconfig0.write(|w| unsafe {
             w.wdt_en()
                 .bit(true)
                 .wdt_stg0()
-                .bits(3)
+                .bits(stage0)
                 .wdt_cpu_reset_length()
                 .bits(1)
                 .wdt_sys_reset_length()
                 .bits(1)
                 .wdt_stg1()
-                .bits(0)
+                .bits(stage1)
                 .wdt_stg2()
                 .bits(0)
                 .wdt_stg3()
@@ -658,10 +924,6 @@ where
         reg_block
             .wdtconfig0
             .modify(|_, w| w.wdt_conf_update_en().set_bit());
-
-        reg_block
-            .wdtwprotect
-            .write(|w| unsafe { w.wdt_wkey().bits(0u32) });
     }
 }
 
@@ -1,14 +1,21 @@
 //! UART driver
+//!
+//! [`Uart`] is generic over the UART peripheral it drives, e.g.
+//! `Uart<'_, UART1>`, so drivers for different instances have different types
+//! and can't be mixed up, e.g. when storing them in statics for use in the
+//! respective interrupt handlers ([`Uart::interrupt`] returns the one to
+//! enable).
 
 use self::config::Config;
 #[cfg(uart2)]
 use crate::peripherals::UART2;
 use crate::{
-    clock::Clocks,
+    clock::{ClockDependent, Clocks},
     gpio::{InputPin, InputSignal, OutputPin, OutputSignal},
     peripheral::{Peripheral, PeripheralRef},
     peripherals::{
         uart0::{fifo::FIFO_SPEC, RegisterBlock},
+        Interrupt,
         UART0,
         UART1,
     },
@@ -16,9 +23,46 @@ use crate::{
 
 const UART_FIFO_SIZE: u16 = 128;
 
+/// Character sent to resume transmission with software flow control
+pub const XON: u8 = 0x11;
+/// Character sent to pause transmission with software flow control
+pub const XOFF: u8 = 0x13;
+
 /// Custom serial error type
 #[derive(Debug)]
-pub enum Error {}
+pub enum Error {
+    InvalidArgument,
+    #[cfg(esp32c3)]
+    DmaError(crate::dma::DmaError),
+    #[cfg(esp32c3)]
+    MaxDmaTransferSizeExceeded,
+}
+
+#[cfg(esp32c3)]
+impl From<crate::dma::DmaError> for Error {
+    fn from(value: crate::dma::DmaError) -> Self {
+        Error::DmaError(value)
+    }
+}
+
+/// Receive errors of a single byte, see [`Uart::read_with_status`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RxStatus {
+    /// The parity bit didn't match the configured parity
+    pub parity_error: bool,
+    /// The stop bit was missing
+    pub framing_error: bool,
+    /// The line was held low for longer than a frame, the byte is the `0x00`
+    /// the receiver stores for a break
+    pub break_detected: bool,
+}
+
+impl RxStatus {
+    /// Returns `true` if any of the errors is set
+    pub fn is_error(&self) -> bool {
+        self.parity_error || self.framing_error || self.break_detected
+    }
+}
 
 /// UART configuration
 pub mod config {
@@ -242,8 +286,19 @@ impl embedded_hal_1::serial::Error for Error {
 }
 
 /// UART driver
+///
+/// Dropping the driver leaves the peripheral running and the pins connected,
+/// use [`Uart::free`] to wait for pending output first.
 pub struct Uart<'d, T> {
     uart: PeripheralRef<'d, T>,
+    overruns: u32,
+    // own address on a multidrop bus, see `set_address_filter`
+    address_filter: Option<u8>,
+    // whether the latest address byte matched the filter
+    addressed: bool,
+    // last rate set with `change_baud`, to derive the divider again when the
+    // clocks change
+    baudrate: Option<u32>,
 }
 
 impl<'d, T> Uart<'d, T>
@@ -261,7 +316,13 @@ where
         P: UartPins,
     {
         crate::into_ref!(uart);
-        let mut serial = Uart { uart };
+        let mut serial = Uart {
+            uart,
+            overruns: 0,
+            address_filter: None,
+            addressed: true,
+            baudrate: None,
+        };
         serial.uart.disable_rx_interrupts();
         serial.uart.disable_tx_interrupts();
 
@@ -287,13 +348,40 @@ where
     /// Create a new UART instance with defaults
     pub fn new(uart: impl Peripheral<P = T> + 'd) -> Self {
         crate::into_ref!(uart);
-        let mut serial = Uart { uart };
+        let mut serial = Uart {
+            uart,
+            overruns: 0,
+            address_filter: None,
+            addressed: true,
+            baudrate: None,
+        };
         serial.uart.disable_rx_interrupts();
         serial.uart.disable_tx_interrupts();
 
         serial
     }
 
+    /// Release the peripheral
+    ///
+    /// Waits until all pending data has been sent and disables the UART
+    /// interrupts. The UART clocks are not under the control of
+    /// [`PeripheralClockControl`](crate::system::PeripheralClockControl), they
+    /// stay enabled, as UART0 is also used by the ROM code for its console
+    /// output. The pins stay connected, and as the UART instance and the pins
+    /// were only borrowed for `'d`, they can be reconfigured afterwards or be
+    /// used to construct a new driver.
+    pub fn free(mut self) {
+        while self.flush_tx().is_err() {}
+
+        self.uart.disable_rx_interrupts();
+        self.uart.disable_tx_interrupts();
+    }
+
+    /// The peripheral interrupt of this UART instance
+    pub fn interrupt(&self) -> Interrupt {
+        self.uart.interrupt()
+    }
+
     /// Writes bytes
     pub fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
         data.iter()
@@ -356,6 +444,131 @@ where
             .modify(|_, w| unsafe { w.rxfifo_full_thrhd().bits(threshold) });
     }
 
+    /// Configures the TX-FIFO empty threshold
+    ///
+    /// The TX-FIFO-EMPTY interrupt fires once fewer than `threshold` bytes are
+    /// left in the TX-FIFO. A low threshold means fewer, larger refills
+    /// (fewer interrupts per transmitted byte) but leaves less time to refill
+    /// before the FIFO runs dry; a high threshold trades a higher interrupt
+    /// rate for more margin against underruns at high baud rates.
+    ///
+    /// Returns [`Error::InvalidArgument`] if `threshold` isn't below the FIFO
+    /// depth, the threshold field of the ESP32 is only 7 bits wide.
+    pub fn set_tx_fifo_empty_threshold(&mut self, threshold: u16) -> Result<(), Error> {
+        if threshold >= UART_FIFO_SIZE {
+            return Err(Error::InvalidArgument);
+        }
+
+        #[cfg(any(esp32, esp32c6))]
+        let threshold: u8 = threshold as u8;
+
+        self.uart
+            .register_block()
+            .conf1
+            .modify(|_, w| unsafe { w.txfifo_empty_thrhd().bits(threshold) });
+
+        Ok(())
+    }
+
+    /// Enable software (XON/XOFF) flow control
+    ///
+    /// Flow control is handled by the hardware: transmission pauses when
+    /// [`XOFF`] is received and resumes on [`XON`]. [`XOFF`] is sent once more
+    /// than `xoff_threshold` bytes are waiting in the RX-FIFO, and [`XON`]
+    /// once fewer than `xon_threshold` are left, so the thresholds leave room
+    /// for the bytes the other side sends before it reacts.
+    ///
+    /// Received [`XON`] and [`XOFF`] characters are removed from the data
+    /// stream. There's no escaping: binary data containing these bytes can't
+    /// be transferred while flow control is enabled, use hardware flow control
+    /// (RTS/CTS) or an encoding avoiding them instead.
+    ///
+    /// Returns [`Error::InvalidArgument`] if `xoff_threshold` isn't below the
+    /// FIFO depth, as the FIFO could never hold more bytes and [`XOFF`] would
+    /// never be sent, or if `xon_threshold` isn't below `xoff_threshold`.
+    pub fn enable_sw_flow_control(
+        &mut self,
+        xon_threshold: u16,
+        xoff_threshold: u16,
+    ) -> Result<(), Error> {
+        if xoff_threshold >= UART_FIFO_SIZE || xon_threshold >= xoff_threshold {
+            return Err(Error::InvalidArgument);
+        }
+
+        let reg_block = self.uart.register_block();
+
+        #[cfg(esp32)]
+        reg_block.swfc_conf.modify(|_, w| unsafe {
+            w.xon_threshold()
+                .bits(xon_threshold as u8)
+                .xoff_threshold()
+                .bits(xoff_threshold as u8)
+                .xon_char()
+                .bits(XON)
+                .xoff_char()
+                .bits(XOFF)
+        });
+
+        #[cfg(esp32c6)]
+        {
+            reg_block.swfc_conf1.modify(|_, w| unsafe {
+                w.xon_threshold()
+                    .bits(xon_threshold as u8)
+                    .xoff_threshold()
+                    .bits(xoff_threshold as u8)
+            });
+            reg_block.swfc_conf0.modify(|_, w| unsafe {
+                w.xon_char()
+                    .bits(XON)
+                    .xoff_char()
+                    .bits(XOFF)
+                    .xonoff_del()
+                    .set_bit()
+                    .sw_flow_con_en()
+                    .set_bit()
+            });
+        }
+
+        #[cfg(not(any(esp32, esp32c6)))]
+        {
+            reg_block.swfc_conf1.modify(|_, w| unsafe {
+                w.xon_threshold().bits(xon_threshold).xon_char().bits(XON)
+            });
+            reg_block.swfc_conf0.modify(|_, w| unsafe {
+                w.xoff_threshold()
+                    .bits(xoff_threshold)
+                    .xoff_char()
+                    .bits(XOFF)
+            });
+        }
+
+        #[cfg(not(esp32c6))]
+        reg_block
+            .flow_conf
+            .modify(|_, w| w.xonoff_del().set_bit().sw_flow_con_en().set_bit());
+
+        self.sync_regs();
+
+        Ok(())
+    }
+
+    /// Disable software (XON/XOFF) flow control
+    pub fn disable_sw_flow_control(&mut self) {
+        #[cfg(esp32c6)]
+        self.uart
+            .register_block()
+            .swfc_conf0
+            .modify(|_, w| w.xonoff_del().clear_bit().sw_flow_con_en().clear_bit());
+
+        #[cfg(not(esp32c6))]
+        self.uart
+            .register_block()
+            .flow_conf
+            .modify(|_, w| w.xonoff_del().clear_bit().sw_flow_con_en().clear_bit());
+
+        self.sync_regs();
+    }
+
     /// Listen for AT-CMD interrupts
     pub fn listen_at_cmd(&mut self) {
         self.uart
@@ -404,6 +617,22 @@ where
             .modify(|_, w| w.rxfifo_full_int_ena().clear_bit());
     }
 
+    /// Listen for TX-FIFO-EMPTY interrupts
+    pub fn listen_tx_fifo_empty(&mut self) {
+        self.uart
+            .register_block()
+            .int_ena
+            .modify(|_, w| w.txfifo_empty_int_ena().set_bit());
+    }
+
+    /// Stop listening for TX-FIFO-EMPTY interrupts
+    pub fn unlisten_tx_fifo_empty(&mut self) {
+        self.uart
+            .register_block()
+            .int_ena
+            .modify(|_, w| w.txfifo_empty_int_ena().clear_bit());
+    }
+
     /// Checks if AT-CMD interrupt is set
     pub fn at_cmd_interrupt_set(&self) -> bool {
         self.uart
@@ -434,6 +663,16 @@ where
             .bit_is_set()
     }
 
+    /// Checks if TX-FIFO-EMPTY interrupt is set
+    pub fn tx_fifo_empty_interrupt_set(&self) -> bool {
+        self.uart
+            .register_block()
+            .int_raw
+            .read()
+            .txfifo_empty_int_raw()
+            .bit_is_set()
+    }
+
     /// Reset AT-CMD interrupt
     pub fn reset_at_cmd_interrupt(&self) {
         self.uart
@@ -458,6 +697,183 @@ where
             .write(|w| w.rxfifo_full_int_clr().set_bit());
     }
 
+    /// Reset TX-FIFO-EMPTY interrupt
+    pub fn reset_tx_fifo_empty_interrupt(&self) {
+        self.uart
+            .register_block()
+            .int_clr
+            .write(|w| w.txfifo_empty_int_clr().set_bit());
+    }
+
+    /// Number of RX FIFO overruns since the driver was created or the count
+    /// was last reset
+    ///
+    /// An overrun happens when a byte arrives while the RX FIFO is full, the
+    /// byte is dropped. The hardware only flags that this happened, not how
+    /// many bytes were lost, so this counts the reads (and calls to this
+    /// function) that found the flag set: at least one byte was lost for each.
+    /// Under sustained overload the FIFO stays full and bytes keep getting
+    /// dropped between two reads, but the count only goes up once per read,
+    /// so it is a lower bound of the lost bytes. Any non-zero value means the
+    /// application doesn't keep up with the incoming data.
+    pub fn overrun_count(&mut self) -> u32 {
+        self.check_overrun();
+        self.overruns
+    }
+
+    /// Reset the overrun count to 0, see [`Self::overrun_count`]
+    pub fn reset_overrun_count(&mut self) {
+        self.check_overrun();
+        self.overruns = 0;
+    }
+
+    /// Read a byte together with the receive errors flagged for it
+    ///
+    /// The RX FIFO only stores the data, the hardware reports parity errors,
+    /// framing errors and breaks through interrupt flags that aren't tied to
+    /// a byte. This reads and clears the flags together with the byte, so
+    /// they can only be attributed to it if it was the only one in the FIFO:
+    /// every byte has to be read before the next one arrives. Set the RX FIFO
+    /// full threshold to 1 with [`Self::set_rx_fifo_full_threshold`] and read
+    /// from the interrupt handler of [`Self::listen_rx_fifo_full`]. That costs
+    /// an interrupt per byte, which has to be handled within one character
+    /// time (87 us at 115200 baud). Bytes read while more were waiting report
+    /// the errors of any of them.
+    ///
+    /// Bytes with a parity or framing error are still stored in the FIFO, as
+    /// is a `0x00` for a break. Mixing this with the other read functions
+    /// loses the flags of the bytes they read.
+    pub fn read_with_status(&mut self) -> nb::Result<(u8, RxStatus), Error> {
+        if self.uart.get_rx_fifo_count() == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let reg_block = self.uart.register_block();
+        let raw = reg_block.int_raw.read();
+        let status = RxStatus {
+            parity_error: raw.parity_err_int_raw().bit_is_set(),
+            framing_error: raw.frm_err_int_raw().bit_is_set(),
+            break_detected: raw.brk_det_int_raw().bit_is_set(),
+        };
+        reg_block.int_clr.write(|w| {
+            w.parity_err_int_clr()
+                .set_bit()
+                .frm_err_int_clr()
+                .set_bit()
+                .brk_det_int_clr()
+                .set_bit()
+        });
+
+        let byte = self.read_byte()?;
+
+        Ok((byte, status))
+    }
+
+    /// Write a byte with a 9th bit, e.g. to mark it as an address on a
+    /// multidrop bus
+    ///
+    /// None of the ESP32 UARTs has a 9-bit mode or mark/space parity, on all
+    /// chips the 9th bit is sent as the parity bit: for every byte, the parity
+    /// is switched to whichever of even or odd results in a parity bit equal
+    /// to `ninth_bit`. The parity can only change while nothing is being sent,
+    /// so this waits for the TX FIFO to drain and the transmitter to become
+    /// idle before and after the byte, which leaves gaps between the bytes.
+    ///
+    /// Receiver and transmitter share the parity setting. It is even again
+    /// when this returns, as expected by [`Self::read_with_ninth_bit`], but
+    /// bytes received while sending are checked against the parity of the
+    /// byte being sent: use it on half-duplex buses like RS-485 only.
+    pub fn write_with_ninth_bit(&mut self, byte: u8, ninth_bit: bool) -> Result<(), Error> {
+        nb::block!(self.flush_tx())?;
+
+        // even parity sends a 1 if the number of ones in the data is odd
+        let odd_ones = byte.count_ones() % 2 == 1;
+        if odd_ones == ninth_bit {
+            self.change_parity(config::Parity::ParityEven);
+        } else {
+            self.change_parity(config::Parity::ParityOdd);
+        }
+        self.sync_regs();
+
+        nb::block!(self.write_byte(byte))?;
+        nb::block!(self.flush_tx())?;
+
+        self.change_parity(config::Parity::ParityEven);
+        self.sync_regs();
+
+        Ok(())
+    }
+
+    /// Read a byte together with its 9th bit, see
+    /// [`Self::write_with_ninth_bit`]
+    ///
+    /// The UART has to be configured for 8 data bits and even parity. The 9th
+    /// bit is recovered from the received parity bit, a parity error means
+    /// it differs from the even parity of the data. This relies on
+    /// [`Self::read_with_status`] and has the same restriction: the parity
+    /// error can only be attributed to the right byte if each byte is read
+    /// before the next one arrives.
+    ///
+    /// The hardware can't filter on the 9th bit, the receiver stores and
+    /// interrupts for every byte. Use [`Self::set_address_filter`] and
+    /// [`Self::read_addressed`] to only get the data for the own address.
+    pub fn read_with_ninth_bit(&mut self) -> nb::Result<(u8, bool), Error> {
+        let (byte, status) = self.read_with_status()?;
+        let odd_ones = byte.count_ones() % 2 == 1;
+
+        Ok((byte, odd_ones != status.parity_error))
+    }
+
+    /// Only return the data addressed to `address` from
+    /// [`Self::read_addressed`], or all data for `None`
+    ///
+    /// On a multidrop bus a frame starts with the address of the node, sent
+    /// with the 9th bit set (see [`Self::write_with_ninth_bit`]), followed by
+    /// data bytes with the 9th bit clear. With a filter, the data bytes are
+    /// dropped until an address byte matches `address`, and again after an
+    /// address byte for another node.
+    ///
+    /// None of the UARTs of the ESP32, ESP32-C2, ESP32-C3, ESP32-C6, ESP32-S2
+    /// and ESP32-S3 can match addresses in hardware: there is no 9-bit mode,
+    /// and the AT command detection of [`Self::set_at_cmd`] only recognizes a
+    /// character repeated between idle periods, without keeping any byte out
+    /// of the RX FIFO. The filter is applied in software when reading, so
+    /// every byte on the bus is still stored and raises the RX interrupts,
+    /// but frames for other nodes never reach the application.
+    pub fn set_address_filter(&mut self, address: Option<u8>) {
+        self.address_filter = address;
+        self.addressed = address.is_none();
+    }
+
+    /// Read the next data byte that passes the address filter, see
+    /// [`Self::set_address_filter`]
+    ///
+    /// Address bytes and data for other nodes are consumed and dropped. As
+    /// this uses [`Self::read_with_ninth_bit`], each byte has to be read
+    /// before the next one arrives.
+    pub fn read_addressed(&mut self) -> nb::Result<u8, Error> {
+        loop {
+            let (byte, is_address) = self.read_with_ninth_bit()?;
+
+            if is_address {
+                self.addressed = self.address_filter.map_or(true, |own| own == byte);
+            } else if self.addressed {
+                return Ok(byte);
+            }
+        }
+    }
+
+    fn check_overrun(&mut self) {
+        let reg_block = self.uart.register_block();
+
+        if reg_block.int_raw.read().rxfifo_ovf_int_raw().bit_is_set() {
+            reg_block
+                .int_clr
+                .write(|w| w.rxfifo_ovf_int_clr().set_bit());
+            self.overruns = self.overruns.saturating_add(1);
+        }
+    }
+
     fn write_byte(&mut self, word: u8) -> nb::Result<(), Error> {
         if self.uart.get_tx_fifo_count() < UART_FIFO_SIZE {
             self.uart
@@ -472,7 +888,9 @@ where
     }
 
     fn flush_tx(&self) -> nb::Result<(), Error> {
-        if self.uart.is_tx_idle() {
+        // a byte just written can still be in the FIFO while the transmitter
+        // reads as idle
+        if self.uart.get_tx_fifo_count() == 0 && self.uart.is_tx_idle() {
             Ok(())
         } else {
             Err(nb::Error::WouldBlock)
@@ -480,6 +898,8 @@ where
     }
 
     fn read_byte(&mut self) -> nb::Result<u8, Error> {
+        self.check_overrun();
+
         #[allow(unused_variables)]
         let offset = 0;
 
@@ -559,10 +979,62 @@ where
         self
     }
 
+    /// The baud rate actually generated, which can differ slightly from the
+    /// configured one as the divider has a resolution of 1/16
+    pub fn baudrate(&self, clocks: &Clocks) -> u32 {
+        let clkdiv = self.uart.register_block().clkdiv.read();
+        let divider = ((clkdiv.clkdiv().bits() as u64) << 4) | clkdiv.frag().bits() as u64;
+
+        ((self.sclk_frequency(clocks) as u64 * 16 + divider / 2) / divider) as u32
+    }
+
+    /// Deviation of the generated baud rate from `requested`, in percent
+    ///
+    /// Transfers become unreliable when the combined error of both ends
+    /// exceeds about 2%.
+    pub fn baudrate_error(&self, requested: u32, clocks: &Clocks) -> f32 {
+        (self.baudrate(clocks) as f32 - requested as f32) * 100.0 / requested as f32
+    }
+
+    // Integer and fractional (1/16) part of the divider generating `baudrate`
+    // from `sclk`, rounded to the closest achievable rate
+    fn baud_divider(sclk: u32, baudrate: u32) -> (u32, u8) {
+        let divider = (sclk as u64 * 16 + baudrate as u64 / 2) / baudrate as u64;
+
+        ((divider >> 4) as u32, (divider & 0xf) as u8)
+    }
+
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    fn sclk_frequency(&self, clocks: &Clocks) -> u32 {
+        let clk_conf = self.uart.register_block().clk_conf.read();
+
+        clocks.apb_clock.to_Hz() / (clk_conf.sclk_div_num().bits() as u32 + 1)
+    }
+
+    #[cfg(esp32c6)]
+    fn sclk_frequency(&self, clocks: &Clocks) -> u32 {
+        let pcr = unsafe { &*esp32c6::PCR::PTR };
+
+        let div_num = match self.uart.uart_number() {
+            0 => pcr.uart0_sclk_conf.read().uart0_sclk_div_num().bits(),
+            1 => pcr.uart1_sclk_conf.read().uart1_sclk_div_num().bits(),
+            _ => unreachable!(), // ESP32-C6 only has 2 UART instances
+        };
+
+        clocks.apb_clock.to_Hz() / (div_num as u32 + 1)
+    }
+
+    #[cfg(any(esp32, esp32s2))]
+    fn sclk_frequency(&self, clocks: &Clocks) -> u32 {
+        clocks.apb_clock.to_Hz()
+    }
+
+    /// Change the baud rate, using the closest rate the divider can generate
     #[cfg(any(esp32c2, esp32c3, esp32s3))]
-    fn change_baud(&self, baudrate: u32, clocks: &Clocks) {
-        // we force the clock source to be APB and don't use the decimal part of the
-        // divider
+    pub fn change_baud(&mut self, baudrate: u32, clocks: &Clocks) {
+        self.baudrate = Some(baudrate);
+
+        // we force the clock source to be APB
         let clk = clocks.apb_clock.to_Hz();
         let max_div = 0b1111_1111_1111 - 1;
         let clk_div = ((clk) + (max_div * baudrate) - 1) / (max_div * baudrate);
@@ -582,20 +1054,20 @@ where
                 .bit(true)
         });
 
-        let clk = clk / clk_div;
-        let divider = clk / baudrate;
-        let divider = divider as u16;
+        let (divider, frag) = Self::baud_divider(clk / clk_div, baudrate);
 
         self.uart
             .register_block()
             .clkdiv
-            .write(|w| unsafe { w.clkdiv().bits(divider).frag().bits(0) });
+            .write(|w| unsafe { w.clkdiv().bits(divider as u16).frag().bits(frag) });
     }
 
+    /// Change the baud rate, using the closest rate the divider can generate
     #[cfg(esp32c6)]
-    fn change_baud(&self, baudrate: u32, clocks: &Clocks) {
-        // we force the clock source to be APB and don't use the decimal part of the
-        // divider
+    pub fn change_baud(&mut self, baudrate: u32, clocks: &Clocks) {
+        self.baudrate = Some(baudrate);
+
+        // we force the clock source to be APB
         let clk = clocks.apb_clock.to_Hz();
         let max_div = 0b1111_1111_1111 - 1;
         let clk_div = ((clk) + (max_div * baudrate) - 1) / (max_div * baudrate);
@@ -641,32 +1113,32 @@ where
             _ => unreachable!(), // ESP32-C6 only has 2 UART instances
         }
 
-        let clk = clk / clk_div;
-        let divider = clk / baudrate;
-        let divider = divider as u16;
+        let (divider, frag) = Self::baud_divider(clk / clk_div, baudrate);
 
         self.uart
             .register_block()
             .clkdiv
-            .write(|w| unsafe { w.clkdiv().bits(divider).frag().bits(0) });
+            .write(|w| unsafe { w.clkdiv().bits(divider as u16).frag().bits(frag) });
     }
 
+    /// Change the baud rate, using the closest rate the divider can generate
     #[cfg(any(esp32, esp32s2))]
-    fn change_baud(&self, baudrate: u32, clocks: &Clocks) {
-        // we force the clock source to be APB and don't use the decimal part of the
-        // divider
+    pub fn change_baud(&mut self, baudrate: u32, clocks: &Clocks) {
+        self.baudrate = Some(baudrate);
+
+        // we force the clock source to be APB
         let clk = clocks.apb_clock.to_Hz();
 
         self.uart
             .register_block()
             .conf0
             .modify(|_, w| w.tick_ref_always_on().bit(true));
-        let divider = clk / baudrate;
+        let (divider, frag) = Self::baud_divider(clk, baudrate);
 
         self.uart
             .register_block()
             .clkdiv
-            .write(|w| unsafe { w.clkdiv().bits(divider).frag().bits(0) });
+            .write(|w| unsafe { w.clkdiv().bits(divider).frag().bits(frag) });
     }
 
     #[cfg(esp32c6)] // TODO introduce a cfg symbol for this
@@ -744,7 +1216,7 @@ pub trait Instance {
         });
     }
 
-    fn get_tx_fifo_count(&mut self) -> u16 {
+    fn get_tx_fifo_count(&self) -> u16 {
         self.register_block()
             .status
             .read()
@@ -782,6 +1254,8 @@ pub trait Instance {
 
     fn tx_signal(&self) -> OutputSignal;
 
+    fn interrupt(&self) -> Interrupt;
+
     fn rx_signal(&self) -> InputSignal;
 
     fn cts_signal(&self) -> InputSignal;
@@ -800,6 +1274,10 @@ impl Instance for UART0 {
         0
     }
 
+    fn interrupt(&self) -> Interrupt {
+        Interrupt::UART0
+    }
+
     fn tx_signal(&self) -> OutputSignal {
         OutputSignal::U0TXD
     }
@@ -828,6 +1306,10 @@ impl Instance for UART1 {
         1
     }
 
+    fn interrupt(&self) -> Interrupt {
+        Interrupt::UART1
+    }
+
     fn tx_signal(&self) -> OutputSignal {
         OutputSignal::U1TXD
     }
@@ -857,6 +1339,10 @@ impl Instance for UART2 {
         2
     }
 
+    fn interrupt(&self) -> Interrupt {
+        Interrupt::UART2
+    }
+
     fn tx_signal(&self) -> OutputSignal {
         OutputSignal::U2TXD
     }
@@ -874,6 +1360,23 @@ impl Instance for UART2 {
     }
 }
 
+impl<T> ClockDependent for Uart<'_, T>
+where
+    T: Instance,
+{
+    /// A byte is being sent or received, or waits in the TX FIFO
+    fn is_busy(&self) -> bool {
+        self.flush_tx().is_err() || !self.uart.is_rx_idle()
+    }
+
+    /// Set the baud rate of the last [`Uart::change_baud`] again
+    fn clocks_changed(&mut self, clocks: &Clocks) {
+        if let Some(baudrate) = self.baudrate {
+            self.change_baud(baudrate, clocks);
+        }
+    }
+}
+
 #[cfg(feature = "ufmt")]
 impl<T> ufmt_write::uWrite for Uart<'_, T>
 where
@@ -957,3 +1460,319 @@ where
         self.flush_tx()
     }
 }
+
+/// Length-prefixed packets
+///
+/// Many binary protocols send every packet as its length followed by that many
+/// bytes of payload. [`PacketReader`](packet::PacketReader) receives such
+/// packets into a buffer provided by the application, which also bounds the
+/// largest packet accepted.
+///
+/// Waiting for the first byte of a packet doesn't time out. Once a packet has
+/// started, every further byte has to arrive within the inter-byte timeout,
+/// measured with a timer implementing `CountDown`. On a timeout the partial
+/// packet is dropped, so the next read starts over with a length prefix and
+/// the reader gets back in sync with the sender after a lost byte.
+///
+/// ```rust,ignore
+/// let mut buffer = [0u8; 64];
+/// let mut reader = PacketReader::new(&mut buffer, LengthPrefix::U8, 10u64.millis());
+///
+/// let packet = reader.read_packet(&mut serial, &mut timer)?;
+/// ```
+pub mod packet {
+    use embedded_hal::timer::CountDown;
+    use fugit::MicrosDurationU64;
+
+    use super::{Instance, Uart};
+
+    /// Encoding of the length sent ahead of the payload
+    #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+    pub enum LengthPrefix {
+        /// A single byte, packets up to 255 bytes
+        U8,
+        /// Two bytes, least significant first
+        U16Le,
+        /// Two bytes, most significant first
+        U16Be,
+    }
+
+    impl LengthPrefix {
+        fn size(&self) -> usize {
+            match self {
+                LengthPrefix::U8 => 1,
+                LengthPrefix::U16Le | LengthPrefix::U16Be => 2,
+            }
+        }
+
+        fn decode(&self, bytes: [u8; 2]) -> usize {
+            match self {
+                LengthPrefix::U8 => bytes[0] as usize,
+                LengthPrefix::U16Le => u16::from_le_bytes(bytes) as usize,
+                LengthPrefix::U16Be => u16::from_be_bytes(bytes) as usize,
+            }
+        }
+    }
+
+    /// Packet reception errors
+    #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+    pub enum Error {
+        /// The next byte didn't arrive within the inter-byte timeout, after
+        /// `received` bytes of the packet (including the length prefix)
+        Timeout { received: usize },
+        /// The length prefix announced more bytes than fit into the buffer
+        ///
+        /// The payload has been read and discarded.
+        TooLong { length: usize },
+    }
+
+    /// Receives length-prefixed packets, see the [module docs](self)
+    pub struct PacketReader<'b> {
+        buffer: &'b mut [u8],
+        prefix: LengthPrefix,
+        timeout: MicrosDurationU64,
+    }
+
+    impl<'b> PacketReader<'b> {
+        /// Create a reader receiving packets of up to `buffer.len()` bytes
+        pub fn new(buffer: &'b mut [u8], prefix: LengthPrefix, timeout: MicrosDurationU64) -> Self {
+            Self {
+                buffer,
+                prefix,
+                timeout,
+            }
+        }
+
+        /// Largest payload accepted
+        pub fn max_packet_size(&self) -> usize {
+            self.buffer.len()
+        }
+
+        /// Wait for the next packet and return its payload
+        ///
+        /// `timer` is restarted for every byte after the first one and used to
+        /// detect the inter-byte timeout.
+        pub fn read_packet<T, C>(
+            &mut self,
+            uart: &mut Uart<'_, T>,
+            timer: &mut C,
+        ) -> Result<&[u8], Error>
+        where
+            T: Instance,
+            C: CountDown<Time = MicrosDurationU64>,
+        {
+            let mut prefix = [0u8; 2];
+            prefix[0] = loop {
+                if let Ok(byte) = uart.read_byte() {
+                    break byte;
+                }
+            };
+            let mut received = 1;
+
+            for byte in prefix[1..self.prefix.size()].iter_mut() {
+                *byte = self.next_byte(uart, timer, received)?;
+                received += 1;
+            }
+
+            let length = self.prefix.decode(prefix);
+
+            if length > self.buffer.len() {
+                // skip the payload to stay in sync with the sender
+                for _ in 0..length {
+                    self.next_byte(uart, timer, received)?;
+                    received += 1;
+                }
+
+                return Err(Error::TooLong { length });
+            }
+
+            for index in 0..length {
+                self.buffer[index] = self.next_byte(uart, timer, received)?;
+                received += 1;
+            }
+
+            Ok(&self.buffer[..length])
+        }
+
+        fn next_byte<T, C>(
+            &self,
+            uart: &mut Uart<'_, T>,
+            timer: &mut C,
+            received: usize,
+        ) -> Result<u8, Error>
+        where
+            T: Instance,
+            C: CountDown<Time = MicrosDurationU64>,
+        {
+            timer.start(self.timeout);
+
+            loop {
+                if let Ok(byte) = uart.read_byte() {
+                    return Ok(byte);
+                }
+
+                if timer.wait().is_ok() {
+                    return Err(Error::Timeout { received });
+                }
+            }
+        }
+    }
+}
+
+/// Receiving through DMA
+///
+/// The UART peripherals have no DMA access of their own, received data is
+/// moved to memory by the UHCI0 peripheral, which is connected to a GDMA
+/// channel. [`UartDma::read_dma_exact`] programs UHCI0 to end the transfer
+/// after exactly the number of bytes requested, which suits protocols with
+/// fixed-length frames: the CPU is not involved until the whole frame is in
+/// the buffer.
+///
+/// The size of a single transfer is limited to [`MAX_DMA_SIZE`] bytes by the
+/// width of the UHCI0 length threshold. Every DMA descriptor covers up to 4092
+/// bytes and takes 3 words, so the RX descriptor list handed to the channel
+/// needs `3 * ceil(len / 4092)` words for a transfer of `len` bytes.
+///
+/// The `DMA_CH*` interrupt of the channel used has to be enabled for the
+/// transfers to complete.
+///
+/// ```rust,ignore
+/// let mut serial = Uart::new(peripherals.UART1).with_dma(
+///     peripherals.UHCI0,
+///     dma.channel0.configure(false, tx_descriptors, rx_descriptors, DmaPriority::Priority0),
+///     &mut system.peripheral_clock_control,
+/// );
+///
+/// let mut frame = [0u8; 32];
+/// serial.read_dma_exact(&mut frame).await.unwrap();
+/// ```
+#[cfg(all(esp32c3, feature = "async"))]
+pub mod dma {
+    use super::{Error, Instance, Uart};
+    use crate::{
+        dma::{Channel, DmaPeripheral, Rx, RxPrivate, Tx, TxPrivate, UhciPeripheral},
+        peripheral::{Peripheral, PeripheralRef},
+        peripherals::UHCI0,
+        system::{self, PeripheralClockControl},
+    };
+
+    /// Largest number of bytes a single transfer can receive
+    pub const MAX_DMA_SIZE: usize = 8191;
+
+    pub trait WithDmaUart<'d, T, TX, RX, P>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        /// Receive through UHCI0 and `channel`
+        fn with_dma(
+            self,
+            uhci: impl Peripheral<P = UHCI0> + 'd,
+            channel: Channel<TX, RX, P>,
+            peripheral_clock_control: &mut PeripheralClockControl,
+        ) -> UartDma<'d, T, TX, RX, P>;
+    }
+
+    impl<'d, T, TX, RX, P> WithDmaUart<'d, T, TX, RX, P> for Uart<'d, T>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        fn with_dma(
+            self,
+            uhci: impl Peripheral<P = UHCI0> + 'd,
+            mut channel: Channel<TX, RX, P>,
+            peripheral_clock_control: &mut PeripheralClockControl,
+        ) -> UartDma<'d, T, TX, RX, P> {
+            crate::into_ref!(uhci);
+            peripheral_clock_control.enable(system::Peripheral::Uhci0);
+
+            channel.tx.init_channel(); // no need to call this for both, TX and RX
+
+            let uart_number = self.uart.uart_number();
+            uhci.conf0.write(|w| unsafe {
+                // no framing, only end a transfer once the requested length
+                // has been received
+                w.bits(0)
+                    .clk_en()
+                    .set_bit()
+                    .len_eof_en()
+                    .set_bit()
+                    .uart0_ce()
+                    .bit(uart_number == 0)
+                    .uart1_ce()
+                    .bit(uart_number == 1)
+            });
+            uhci.escape_conf.write(|w| unsafe { w.bits(0) });
+
+            UartDma {
+                uart: self,
+                uhci,
+                channel,
+            }
+        }
+    }
+
+    /// UART driver receiving through DMA
+    pub struct UartDma<'d, T, TX, RX, P>
+    where
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        uart: Uart<'d, T>,
+        uhci: PeripheralRef<'d, UHCI0>,
+        channel: Channel<TX, RX, P>,
+    }
+
+    impl<'d, T, TX, RX, P> UartDma<'d, T, TX, RX, P>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        /// Receive exactly `buf.len()` bytes
+        ///
+        /// Completes once the last byte has been written to `buf`. Returns
+        /// [`Error::InvalidArgument`] if `buf` is empty and
+        /// [`Error::MaxDmaTransferSizeExceeded`] if it is longer than
+        /// [`MAX_DMA_SIZE`] bytes.
+        pub async fn read_dma_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+            if buf.is_empty() {
+                return Err(Error::InvalidArgument);
+            }
+            if buf.len() > MAX_DMA_SIZE {
+                return Err(Error::MaxDmaTransferSizeExceeded);
+            }
+
+            self.uhci.conf0.modify(|_, w| w.rx_rst().set_bit());
+            self.uhci.conf0.modify(|_, w| w.rx_rst().clear_bit());
+            self.uhci
+                .pkt_thres
+                .write(|w| unsafe { w.pkt_thrs().bits(buf.len() as u16) });
+
+            self.channel.rx.prepare_transfer(
+                false,
+                DmaPeripheral::Uhci0,
+                buf.as_mut_ptr(),
+                buf.len(),
+            )?;
+
+            crate::dma::asynch::DmaRxFuture::new(&mut self.channel.rx).await;
+
+            Ok(())
+        }
+
+        /// Stop using DMA, returning the UART driver, UHCI0 and the channel
+        pub fn free(self) -> (Uart<'d, T>, PeripheralRef<'d, UHCI0>, Channel<TX, RX, P>) {
+            self.uhci.conf0.write(|w| unsafe { w.bits(0) });
+
+            (self.uart, self.uhci, self.channel)
+        }
+    }
+}
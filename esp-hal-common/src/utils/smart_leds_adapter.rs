@@ -13,7 +13,7 @@
 
 use core::slice::IterMut;
 
-use fugit::NanosDuration;
+use fugit::{HertzU32, NanosDuration, NanosDurationU32};
 use smart_leds_trait::{SmartLedsWrite, RGB8};
 
 #[cfg(any(esp32, esp32s2))]
@@ -24,21 +24,9 @@ use crate::{
     pulse_control::{ConfiguredChannel, OutputChannel, PulseCode, RepeatMode, TransmissionError},
 };
 
-// Specifies what clock frequency we're using for the RMT peripheral (if
-// properly configured)
-//
-// TODO: Factor in clock configuration, this needs to be revisited once #24 and
-// #44 have been addressed.
-#[cfg(esp32c3)]
-const SOURCE_CLK_FREQ: u32 = 40_000_000;
-#[cfg(esp32c6)]
-const SOURCE_CLK_FREQ: u32 = 40_000_000;
-#[cfg(esp32s2)]
-const SOURCE_CLK_FREQ: u32 = 40_000_000;
-#[cfg(esp32)]
-const SOURCE_CLK_FREQ: u32 = 40_000_000;
-#[cfg(esp32s3)]
-const SOURCE_CLK_FREQ: u32 = 40_000_000;
+// Tick length of the RMT channel, fine enough to stay well within the
+// +-150 ns the LEDs tolerate on every pulse
+const SK68XX_RESOLUTION: NanosDurationU32 = NanosDurationU32::from_ticks(25);
 
 const SK68XX_CODE_PERIOD: u32 = 1200;
 const SK68XX_T0H_NS: u32 = 320;
@@ -46,15 +34,6 @@ const SK68XX_T0L_NS: u32 = SK68XX_CODE_PERIOD - SK68XX_T0H_NS;
 const SK68XX_T1H_NS: u32 = 640;
 const SK68XX_T1L_NS: u32 = SK68XX_CODE_PERIOD - SK68XX_T1H_NS;
 
-const SK68XX_T0H_CYCLES: NanosDuration<u32> =
-    NanosDuration::<u32>::from_ticks((SK68XX_T0H_NS * (SOURCE_CLK_FREQ / 1_000_000)) / 500);
-const SK68XX_T0L_CYCLES: NanosDuration<u32> =
-    NanosDuration::<u32>::from_ticks((SK68XX_T0L_NS * (SOURCE_CLK_FREQ / 1_000_000)) / 500);
-const SK68XX_T1H_CYCLES: NanosDuration<u32> =
-    NanosDuration::<u32>::from_ticks((SK68XX_T1H_NS * (SOURCE_CLK_FREQ / 1_000_000)) / 500);
-const SK68XX_T1L_CYCLES: NanosDuration<u32> =
-    NanosDuration::<u32>::from_ticks((SK68XX_T1L_NS * (SOURCE_CLK_FREQ / 1_000_000)) / 500);
-
 /// All types of errors that can happen during the conversion and transmission
 /// of LED commands
 #[derive(Debug)]
@@ -87,6 +66,8 @@ macro_rules! smartLedAdapter {
 pub struct SmartLedsAdapter<CHANNEL, const BUFFER_SIZE: usize> {
     channel: CHANNEL,
     rmt_buffer: [u32; BUFFER_SIZE],
+    // Pulse codes for a 0 and a 1 bit
+    pulses: [u32; 2],
 }
 
 impl<'d, CHANNEL, const BUFFER_SIZE: usize> SmartLedsAdapter<CHANNEL, BUFFER_SIZE>
@@ -94,67 +75,79 @@ where
     CHANNEL: ConfiguredChannel,
 {
     /// Create a new adapter object that drives the pin using the RMT channel.
+    ///
+    /// `source_frequency` is the clock feeding the channel divider, see
+    /// [`divider_for_resolution`](crate::pulse_control::divider_for_resolution).
+    /// On the ESP32 and ESP32-S2 the channel is switched to the APB clock, so
+    /// this is `clocks.apb_clock`.
     pub fn new<UnconfiguredChannel, O: OutputPin + 'd>(
         mut channel: UnconfiguredChannel,
         pin: impl Peripheral<P = O> + 'd,
+        source_frequency: HertzU32,
     ) -> SmartLedsAdapter<CHANNEL, BUFFER_SIZE>
     where
         UnconfiguredChannel: OutputChannel<ConfiguredChannel<'d, O> = CHANNEL>,
     {
-        #[cfg(any(esp32c3, esp32c6, esp32s3))]
+        #[cfg(any(esp32, esp32s2))]
+        channel.set_clock_source(ClockSource::APB);
+
+        let tick_frequency = channel.set_resolution(source_frequency, SK68XX_RESOLUTION);
         channel
             .set_idle_output_level(false)
             .set_carrier_modulation(false)
-            .set_channel_divider(1)
             .set_idle_output(true);
 
-        #[cfg(any(esp32, esp32s2))]
-        channel
-            .set_idle_output_level(false)
-            .set_carrier_modulation(false)
-            .set_channel_divider(1)
-            .set_idle_output(true)
-            .set_clock_source(ClockSource::APB);
+        let zero_pulse = PulseCode {
+            level1: true,
+            length1: Self::ticks(SK68XX_T0H_NS, tick_frequency),
+            level2: false,
+            length2: Self::ticks(SK68XX_T0L_NS, tick_frequency),
+        }
+        .into();
+        let one_pulse = PulseCode {
+            level1: true,
+            length1: Self::ticks(SK68XX_T1H_NS, tick_frequency),
+            level2: false,
+            length2: Self::ticks(SK68XX_T1L_NS, tick_frequency),
+        }
+        .into();
 
         let channel = channel.assign_pin(pin);
         Self {
             channel,
             rmt_buffer: [0; BUFFER_SIZE],
+            pulses: [zero_pulse, one_pulse],
         }
     }
 
+    // Number of channel clock ticks closest to `ns` nanoseconds
+    fn ticks(ns: u32, tick_frequency: HertzU32) -> NanosDuration<u32> {
+        let ticks = (ns as u64 * tick_frequency.raw() as u64 + 500_000_000) / 1_000_000_000;
+        NanosDuration::<u32>::from_ticks(ticks as u32)
+    }
+
     fn convert_rgb_to_pulse(
+        pulses: [u32; 2],
         value: RGB8,
         mut_iter: &mut IterMut<u32>,
     ) -> Result<(), LedAdapterError> {
-        SmartLedsAdapter::<CHANNEL, BUFFER_SIZE>::convert_rgb_channel_to_pulses(value.g, mut_iter)?;
-        SmartLedsAdapter::<CHANNEL, BUFFER_SIZE>::convert_rgb_channel_to_pulses(value.r, mut_iter)?;
-        SmartLedsAdapter::<CHANNEL, BUFFER_SIZE>::convert_rgb_channel_to_pulses(value.b, mut_iter)?;
+        Self::convert_rgb_channel_to_pulses(pulses, value.g, mut_iter)?;
+        Self::convert_rgb_channel_to_pulses(pulses, value.r, mut_iter)?;
+        Self::convert_rgb_channel_to_pulses(pulses, value.b, mut_iter)?;
 
         Ok(())
     }
 
     fn convert_rgb_channel_to_pulses(
+        pulses: [u32; 2],
         channel_value: u8,
         mut_iter: &mut IterMut<u32>,
     ) -> Result<(), LedAdapterError> {
         for position in [128, 64, 32, 16, 8, 4, 2, 1] {
             *mut_iter.next().ok_or(LedAdapterError::BufferSizeExceeded)? =
                 match channel_value & position {
-                    0 => PulseCode {
-                        level1: true,
-                        length1: SK68XX_T0H_CYCLES,
-                        level2: false,
-                        length2: SK68XX_T0L_CYCLES,
-                    }
-                    .into(),
-                    _ => PulseCode {
-                        level1: true,
-                        length1: SK68XX_T1H_CYCLES,
-                        level2: false,
-                        length2: SK68XX_T1L_CYCLES,
-                    }
-                    .into(),
+                    0 => pulses[0],
+                    _ => pulses[1],
                 }
         }
 
@@ -185,6 +178,7 @@ where
         // the iterator provides more elements than the buffer can take.
         for item in iterator {
             SmartLedsAdapter::<CHANNEL, BUFFER_SIZE>::convert_rgb_to_pulse(
+                self.pulses,
                 item.into(),
                 &mut seq_iter,
             )?;
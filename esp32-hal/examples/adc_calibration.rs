@@ -0,0 +1,81 @@
+//! Reads a known voltage in millivolts using the ADC calibration
+//!
+//! Connect a reference voltage of 1000 mV (e.g. from a precision voltage
+//! reference or a voltage divider measured with a voltmeter) to GPIO34. The
+//! calibrated reading is compared against it with a tolerance of 50 mV.
+//!
+//! If the chip has no reference voltage in eFuse, the internal reference is
+//! output on GPIO25 for a few seconds first. Measure it and pass it to
+//! `AdcCalibration::from_vref` to calibrate such chips.
+
+#![no_std]
+#![no_main]
+
+use esp32_hal::{
+    adc::{AdcCalibration, AdcConfig, Attenuation, ADC, ADC1, ADC2},
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const REFERENCE_MILLIVOLTS: u16 = 1000;
+const TOLERANCE_MILLIVOLTS: u16 = 50;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.DPORT.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let analog = peripherals.SENS.split();
+    let mut delay = Delay::new(&clocks);
+
+    match AdcCalibration::from_efuse() {
+        Some(calibration) => println!("Vref from eFuse: {} mV", calibration.vref()),
+        None => {
+            let mut adc2_config = AdcConfig::new();
+            let mut pin25 =
+                adc2_config.enable_pin(io.pins.gpio25.into_analog(), Attenuation::Attenuation0dB);
+            let mut adc2 = ADC::<ADC2>::adc(analog.adc2, adc2_config).unwrap();
+
+            println!("No Vref in eFuse, measure it at GPIO25 now");
+            adc2.enable_vref_output(&mut pin25);
+            delay.delay_ms(10_000u32);
+            adc2.disable_vref_output();
+        }
+    }
+
+    let mut adc1_config = AdcConfig::new();
+    let mut pin34 =
+        adc1_config.enable_pin(io.pins.gpio34.into_analog(), Attenuation::Attenuation11dB);
+    let mut adc1 = ADC::<ADC1>::adc(analog.adc1, adc1_config).unwrap();
+    adc1.set_oversampling(64);
+
+    println!("Using Vref of {} mV", adc1.calibration().vref());
+
+    loop {
+        let millivolts = nb::block!(adc1.read_millivolts(&mut pin34)).unwrap();
+        println!(
+            "GPIO34: {} mV (expected {} mV)",
+            millivolts, REFERENCE_MILLIVOLTS
+        );
+        assert!(millivolts.abs_diff(REFERENCE_MILLIVOLTS) <= TOLERANCE_MILLIVOLTS);
+
+        delay.delay_ms(1500u32);
+    }
+}
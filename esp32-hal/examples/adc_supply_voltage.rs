@@ -0,0 +1,63 @@
+//! Monitors the supply voltage and warns when it drops too low
+//!
+//! The ESP32 can't measure its own supply voltage internally, so it is divided
+//! down by two equal resistors (e.g. 100 kΩ each) and read on GPIO34.
+//!
+//! The following wiring is assumed:
+//! - VBAT => 100 kΩ => GPIO34 => 100 kΩ => GND
+
+#![no_std]
+#![no_main]
+
+use esp32_hal::{
+    adc::{AdcConfig, Attenuation, ADC, ADC1},
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+// ratio of the divider, (R_top + R_bottom) / R_bottom
+const DIVIDER: u32 = 2;
+const LOW_MILLIVOLTS: u32 = 3300;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.DPORT.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let analog = peripherals.SENS.split();
+
+    let mut adc1_config = AdcConfig::new();
+    let mut pin34 =
+        adc1_config.enable_pin(io.pins.gpio34.into_analog(), Attenuation::Attenuation11dB);
+    let mut adc1 = ADC::<ADC1>::adc(analog.adc1, adc1_config).unwrap();
+    adc1.set_oversampling(64);
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let millivolts = nb::block!(adc1.read_millivolts(&mut pin34)).unwrap() as u32 * DIVIDER;
+        println!("Supply: {} mV", millivolts);
+        if millivolts < LOW_MILLIVOLTS {
+            println!("Warning: supply below {} mV", LOW_MILLIVOLTS);
+        }
+
+        delay.delay_ms(1000u32);
+    }
+}
@@ -0,0 +1,59 @@
+//! Converts between time and CPU cycles
+//!
+//! Prints a few conversions at the configured CPU frequency and measures a
+//! 1 ms delay using the cycle counter. Change `CPU_CLOCK` to try 80 MHz or
+//! 160 MHz.
+
+#![no_std]
+#![no_main]
+
+use esp32_hal::{
+    clock::{ClockControl, CpuClock},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    xtensa_lx,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const CPU_CLOCK: CpuClock = CpuClock::Clock240MHz;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.DPORT.split();
+    let clocks = ClockControl::configure(system.clock_control, CPU_CLOCK).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    println!("CPU clock: {} MHz", clocks.cpu_clock.to_MHz());
+    println!("1 us = {} cycles", clocks.cpu_cycles_for_us(1));
+    println!("1 ms = {} cycles", clocks.cpu_cycles_for_us(1000));
+    println!("100 ns = {} cycles", clocks.cpu_cycles_for_ns(100));
+    println!("1000 cycles = {} us", clocks.us_for_cpu_cycles(1000));
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let start = xtensa_lx::timer::get_cycle_count();
+        delay.delay_us(1000u32);
+        let cycles = xtensa_lx::timer::get_cycle_count().wrapping_sub(start);
+
+        println!(
+            "delay_us(1000) took {} cycles = {} us",
+            cycles,
+            clocks.us_for_cpu_cycles(cycles)
+        );
+
+        delay.delay_ms(1000u32);
+    }
+}
@@ -0,0 +1,78 @@
+//! Reports the flash supply voltage (VDD_SDIO) configuration
+//!
+//! Checks the reported configuration against the MTDI (GPIO12) strap and the
+//! VDD_SDIO eFuses, read directly from their registers, then forces it with
+//! `Rtc::lock_flash_voltage` and checks that only the source changed. The
+//! voltage itself isn't changed.
+
+#![no_std]
+#![no_main]
+
+use esp32_hal::{
+    clock::ClockControl,
+    peripherals::{Peripherals, EFUSE, GPIO},
+    prelude::*,
+    rtc_cntl::{FlashVoltage, FlashVoltageSource},
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.DPORT.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let config = rtc.flash_voltage();
+    println!("Flash voltage: {:?}", config);
+
+    // EFUSE_BLK0_RDATA4_REG: XPD_SDIO_REG (14), SDIO_TIEH (15), SDIO_FORCE (16)
+    let efuse = unsafe { (*EFUSE::PTR).blk0_rdata4.read().bits() };
+    // GPIO_STRAP_REG: MTDI (5)
+    let strap = unsafe { (*GPIO::PTR).strap.read().bits() };
+
+    let (source, voltage) = if efuse & (1 << 16) != 0 {
+        let voltage = if efuse & (1 << 15) != 0 {
+            FlashVoltage::V3_3
+        } else {
+            FlashVoltage::V1_8
+        };
+        (FlashVoltageSource::Efuse, voltage)
+    } else if strap & (1 << 5) != 0 {
+        (FlashVoltageSource::Strap, FlashVoltage::V1_8)
+    } else {
+        (FlashVoltageSource::Strap, FlashVoltage::V3_3)
+    };
+    println!("Strap and eFuses select {:?} from {:?}", voltage, source);
+
+    // the code runs from flash, which is powered by the chip on all modules
+    assert!(config.enabled);
+    // nothing forced the configuration since the last power-on, unless this
+    // example ran before and the chip was only reset
+    if config.source != FlashVoltageSource::Rtc {
+        assert_eq!(config.source, source);
+    }
+    assert_eq!(config.voltage, voltage);
+
+    rtc.lock_flash_voltage();
+    let locked = rtc.flash_voltage();
+    println!("Locked: {:?}", locked);
+
+    assert_eq!(locked.source, FlashVoltageSource::Rtc);
+    assert_eq!(locked.voltage, config.voltage);
+    assert!(locked.enabled);
+
+    println!("Flash voltage reported as expected");
+
+    loop {}
+}
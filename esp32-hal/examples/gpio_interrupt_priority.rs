@@ -0,0 +1,111 @@
+//! GPIO interrupt priority
+//!
+//! A timer interrupt at priority 1 fires every second and keeps the CPU busy
+//! for a while. The GPIO interrupt is configured at priority 3, so pressing
+//! the boot button while the timer handler is running preempts it, which is
+//! reported on the console.
+
+#![no_std]
+#![no_main]
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use critical_section::Mutex;
+use esp32_hal::{
+    clock::ClockControl,
+    gpio::{Event, Gpio0, Input, PullDown, IO},
+    interrupt,
+    peripherals::{self, Peripherals, TIMG0},
+    prelude::*,
+    timer::{Timer, Timer0, TimerGroup},
+    xtensa_lx,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+static BUTTON: Mutex<RefCell<Option<Gpio0<Input<PullDown>>>>> = Mutex::new(RefCell::new(None));
+static TIMER0: Mutex<RefCell<Option<Timer<Timer0<TIMG0>>>>> = Mutex::new(RefCell::new(None));
+
+/// Set while the low priority timer handler is busy
+static BUSY: AtomicBool = AtomicBool::new(false);
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.DPORT.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut timer0 = timer_group0.timer0;
+    let mut wdt = timer_group0.wdt;
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let mut io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut button = io.pins.gpio0.into_pull_down_input();
+    button.listen(Event::FallingEdge);
+
+    critical_section::with(|cs| BUTTON.borrow_ref_mut(cs).replace(button));
+
+    io.set_interrupt_priority(interrupt::Priority::Priority3)
+        .unwrap();
+
+    timer0.start(1u64.secs());
+    timer0.listen();
+
+    critical_section::with(|cs| TIMER0.borrow_ref_mut(cs).replace(timer0));
+
+    interrupt::enable(
+        peripherals::Interrupt::TG0_T0_LEVEL,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    loop {}
+}
+
+#[interrupt]
+fn TG0_T0_LEVEL() {
+    critical_section::with(|cs| {
+        let mut timer0 = TIMER0.borrow_ref_mut(cs);
+        let timer0 = timer0.as_mut().unwrap();
+
+        timer0.clear_interrupt();
+        timer0.start(1u64.secs());
+    });
+
+    // busy wait outside of the critical section, so higher priority interrupts
+    // can preempt this handler
+    BUSY.store(true, Ordering::Relaxed);
+    let start = xtensa_lx::timer::get_cycle_count();
+    while xtensa_lx::timer::get_cycle_count().wrapping_sub(start) < 80_000_000 {}
+    BUSY.store(false, Ordering::Relaxed);
+}
+
+#[interrupt]
+fn GPIO() {
+    if BUSY.load(Ordering::Relaxed) {
+        println!(
+            "GPIO interrupt preempted the timer handler, running at level {}",
+            xtensa_lx::interrupt::get_level()
+        );
+    } else {
+        println!("GPIO interrupt");
+    }
+
+    critical_section::with(|cs| {
+        BUTTON
+            .borrow_ref_mut(cs)
+            .as_mut()
+            .unwrap()
+            .clear_interrupt();
+    });
+}
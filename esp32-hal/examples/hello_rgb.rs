@@ -56,7 +56,7 @@ fn main() -> ! {
     // -> We need to use the macro `smartLedAdapter!` with the number of addressed
     // LEDs here to initialize the internal LED pulse buffer to the correct
     // size!
-    let mut led = <smartLedAdapter!(12)>::new(pulse.channel0, io.pins.gpio33);
+    let mut led = <smartLedAdapter!(12)>::new(pulse.channel0, io.pins.gpio33, clocks.apb_clock);
 
     // Initialize the Delay peripheral, and use it to toggle the LED state in a
     // loop.
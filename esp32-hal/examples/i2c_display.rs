@@ -29,7 +29,6 @@ use esp32_hal::{
     Rtc,
 };
 use esp_backtrace as _;
-use nb::block;
 use ssd1306::{prelude::*, I2CDisplayInterface, Ssd1306};
 
 #[entry]
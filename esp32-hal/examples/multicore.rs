@@ -18,7 +18,6 @@ use esp32_hal::{
 };
 use esp_backtrace as _;
 use esp_println::println;
-use nb::block;
 
 #[entry]
 fn main() -> ! {
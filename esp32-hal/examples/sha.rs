@@ -15,7 +15,6 @@ use esp32_hal::{
 };
 use esp_backtrace as _;
 use esp_println::println;
-use nb::block;
 use sha2::{Digest, Sha512};
 
 #[entry]
@@ -2,8 +2,6 @@ use std::{env, fs::File, io::Write, path::PathBuf};
 
 #[cfg(feature = "direct-boot")]
 fn main() {
-    check_features();
-
     // Put the linker script somewhere the linker can find it
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
 
@@ -38,8 +36,6 @@ fn main() {
 
 #[cfg(not(feature = "direct-boot"))]
 fn main() {
-    check_features();
-
     // Put the linker script somewhere the linker can find it
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
     File::create(out.join("memory.x"))
@@ -66,12 +62,6 @@ fn main() {
     add_defaults();
 }
 
-fn check_features() {
-    if cfg!(feature = "xtal40mhz") && cfg!(feature = "xtal26mhz") {
-        panic!("Only one xtal speed feature can be selected");
-    }
-}
-
 fn add_defaults() {
     let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
 
@@ -21,7 +21,6 @@ use esp32c2_hal::{
 };
 use esp_backtrace as _;
 use esp_println::println;
-use nb::block;
 
 #[entry]
 fn main() -> ! {
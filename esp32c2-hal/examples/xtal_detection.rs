@@ -0,0 +1,45 @@
+//! Prints the detected XTAL frequency and compares it against a measurement
+//! using the internal 8 MHz oscillator.
+//!
+//! Afterwards a line is printed every second using `Delay`, which is derived
+//! from the detected XTAL frequency. The timestamps shown by the serial
+//! monitor should be one second apart with both 26 MHz and 40 MHz crystals,
+//! the same build works on either.
+
+#![no_std]
+#![no_main]
+
+use esp32c2_hal::{clock::ClockControl, peripherals::Peripherals, prelude::*, Delay, Rtc};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+
+    println!(
+        "Detected XTAL frequency: {} MHz",
+        clocks.xtal_clock.to_MHz()
+    );
+    println!(
+        "Measured XTAL frequency: {} MHz",
+        rtc.estimate_xtal_frequency()
+    );
+
+    let mut delay = Delay::new(&clocks);
+    let mut seconds = 0u32;
+
+    loop {
+        delay.delay_ms(1000u32);
+        seconds += 1;
+        println!("{} s", seconds);
+    }
+}
@@ -0,0 +1,89 @@
+//! Low-side current sensing with a differential ADC reading
+//!
+//! Measures the voltage across a shunt resistor between a load and GND and
+//! prints the resulting current. Reading both ends of the shunt instead of
+//! assuming its low end is at 0 V cancels out any offset on the ground path.
+//!
+//! The following wiring is assumed:
+//! - A 0.1 Ω shunt resistor between the low side of the load and GND
+//! - GPIO2 => the end of the shunt connected to the load
+//! - GPIO3 => the end of the shunt connected to GND
+//!
+//! With 0 dB attenuation the inputs range up to about 750 mV, so currents up
+//! to about 7 A can be measured. The conversion uses the nominal full scale
+//! and is not calibrated.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    adc::{AdcConfig, Attenuation, ADC, ADC1},
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const SHUNT_MILLIOHM: i32 = 100;
+const FULL_SCALE_MV: i32 = 750;
+const MAX_READING: i32 = 4095;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let analog = peripherals.APB_SARADC.split();
+
+    // both ends need the same attenuation to share a scale
+    let mut adc1_config = AdcConfig::new();
+    let mut shunt_high =
+        adc1_config.enable_pin(io.pins.gpio2.into_analog(), Attenuation::Attenuation0dB);
+    let mut shunt_low =
+        adc1_config.enable_pin(io.pins.gpio3.into_analog(), Attenuation::Attenuation0dB);
+
+    let mut adc1 = ADC::<ADC1>::adc(
+        &mut system.peripheral_clock_control,
+        analog.adc1,
+        adc1_config,
+    )
+    .unwrap();
+    adc1.set_oversampling(16);
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let difference = adc1
+            .read_differential(&mut shunt_high, &mut shunt_low)
+            .unwrap() as i32;
+
+        let microvolt = difference * FULL_SCALE_MV * 1000 / MAX_READING;
+        let milliampere = microvolt / SHUNT_MILLIOHM;
+
+        println!(
+            "Shunt: {} raw, {} uV, {} mA",
+            difference, microvolt, milliampere
+        );
+
+        delay.delay_ms(500u32);
+    }
+}
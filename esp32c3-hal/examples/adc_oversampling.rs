@@ -0,0 +1,89 @@
+//! Compares the noise of single ADC readings with averaged readings
+//!
+//! Connect a slowly varying (or constant) voltage to PIN2, e.g. the wiper of a
+//! potentiometer. The variance of 64 single conversions and of 64 readings
+//! averaging 16 conversions each is printed, the latter should be clearly
+//! lower.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    adc::{AdcConfig, Attenuation, ADC, ADC1},
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // Create ADC instances
+    let analog = peripherals.APB_SARADC.split();
+
+    let mut adc1_config = AdcConfig::new();
+
+    let mut pin = adc1_config.enable_pin(io.pins.gpio2.into_analog(), Attenuation::Attenuation11dB);
+
+    let mut adc1 = ADC::<ADC1>::adc(
+        &mut system.peripheral_clock_control,
+        analog.adc1,
+        adc1_config,
+    )
+    .unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        adc1.set_oversampling(1);
+        let single = variance(|| nb::block!(adc1.read(&mut pin)).unwrap());
+
+        adc1.set_oversampling(16);
+        let averaged = variance(|| nb::block!(adc1.read(&mut pin)).unwrap());
+
+        println!(
+            "Variance single: {}, averaged over 16: {}",
+            single, averaged
+        );
+        delay.delay_ms(1500u32);
+    }
+}
+
+fn variance(mut read: impl FnMut() -> u16) -> u32 {
+    const READINGS: u32 = 64;
+
+    let mut sum = 0u32;
+    let mut sum_of_squares = 0u64;
+    for _ in 0..READINGS {
+        let value = read() as u32;
+        sum += value;
+        sum_of_squares += (value * value) as u64;
+    }
+
+    let mean = sum / READINGS;
+    (sum_of_squares / READINGS as u64 - (mean * mean) as u64) as u32
+}
@@ -0,0 +1,55 @@
+//! Runs a single async task without an executor crate
+//!
+//! The task toggles the LED on GPIO5 every time the boot button (GPIO9) is
+//! pressed, while `executor::block_on` puts the CPU to sleep in between.
+
+#![no_std]
+#![no_main]
+
+use embedded_hal_async::digital::Wait;
+use esp32c3_hal::{
+    clock::ClockControl,
+    executor::block_on,
+    gpio::IO,
+    interrupt,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut led = io.pins.gpio5.into_push_pull_output();
+    let mut button = io.pins.gpio9.into_pull_down_input();
+
+    // Async requires the GPIO interrupt to wake futures
+    interrupt::enable(peripherals::Interrupt::GPIO, interrupt::Priority::Priority1).unwrap();
+
+    block_on(async {
+        loop {
+            button.wait_for_falling_edge().await.unwrap();
+            esp_println::println!("Pressed!");
+            led.toggle().unwrap();
+        }
+    })
+}
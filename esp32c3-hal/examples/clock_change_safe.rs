@@ -0,0 +1,68 @@
+//! Changes the CPU clock while a UART is in use
+//!
+//! UART1 is sending a buffer at 9600 baud, so `change_frequency_safe` refuses
+//! to touch the clocks. Once the transmission is done, the change goes through
+//! and the baud rate of UART1 is derived again for the new clocks.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::{ClockControl, CpuClock, PeripheralBusy},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+    Uart,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let mut clocks = ClockControl::configure(system.clock_control, CpuClock::Clock160MHz).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut serial1 = Uart::new(peripherals.UART1);
+    serial1.change_baud(9600, &clocks);
+
+    // about 100 ms worth of data, it all fits into the FIFO
+    for _ in 0..96 {
+        block!(serial1.write(b'x')).unwrap();
+    }
+
+    let result = clocks.change_frequency_safe(CpuClock::Clock80MHz, &mut [&mut serial1]);
+    println!("While sending: {:?}", result);
+    assert_eq!(result, Err(PeripheralBusy));
+    assert_eq!(clocks.cpu_clock, 160u32.MHz());
+
+    block!(serial1.flush()).unwrap();
+
+    let result = clocks.change_frequency_safe(CpuClock::Clock80MHz, &mut [&mut serial1]);
+    println!("After sending: {:?}", result);
+    assert_eq!(result, Ok(()));
+    assert_eq!(clocks.cpu_clock, 80u32.MHz());
+
+    let error = serial1.baudrate_error(9600, &clocks);
+    println!(
+        "UART1 runs at {} baud ({:.3}%)",
+        serial1.baudrate(&clocks),
+        error
+    );
+    assert!(error > -0.5 && error < 0.5);
+
+    loop {}
+}
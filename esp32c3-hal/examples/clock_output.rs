@@ -0,0 +1,60 @@
+//! Outputs internal clocks on GPIO pins
+//!
+//! - GPIO2 outputs XTAL_CLK (40 MHz)
+//! - GPIO3 outputs APB_CLK (80 MHz, derived from the PLL)
+//!
+//! The ESP32-C3 has no divider in the clock output path, so the clocks are
+//! output at their full frequency. Use a scope with enough bandwidth to look
+//! at them.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{self, ClockOutputChannel, ClockOutputSource, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut xtal_pin = io.pins.gpio2;
+    let mut apb_pin = io.pins.gpio3;
+
+    gpio::set_clock_output(
+        &mut xtal_pin,
+        ClockOutputChannel::Channel1,
+        ClockOutputSource::Xtal,
+    );
+
+    // The CPU runs from the PLL, so APB_CLK is the 80 MHz PLL clock
+    gpio::set_clock_output(
+        &mut apb_pin,
+        ClockOutputChannel::Channel2,
+        ClockOutputSource::PllF80M,
+    );
+
+    loop {}
+}
@@ -0,0 +1,51 @@
+//! Checks that the PLL locks after the clocks were configured
+//!
+//! Switches the CPU to 160 MHz, which reconfigures the PLL, and waits for it
+//! to report the lock. Fails if that takes longer than 100 µs, otherwise
+//! prints how long it took.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::{ClockControl, CpuClock},
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::configure(system.clock_control, CpuClock::Clock160MHz).freeze();
+    let start = SystemTimer::now();
+
+    clocks
+        .wait_pll_lock(100)
+        .expect("PLL didn't lock within 100 µs");
+    let ticks = SystemTimer::now() - start;
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    println!(
+        "PLL locked after {} µs",
+        ticks * 1_000_000 / SystemTimer::TICKS_PER_SECOND
+    );
+
+    loop {}
+}
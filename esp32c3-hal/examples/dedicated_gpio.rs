@@ -0,0 +1,74 @@
+//! Bit-bangs a clock on GPIO5 using dedicated GPIO
+//!
+//! First toggles GPIO4 through the regular GPIO driver, then GPIO5 through
+//! dedicated GPIO, and prints the edge rate achieved by each. Connect a logic
+//! analyzer or scope to see the difference.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::{ClockControl, CpuClock},
+    dedicated_gpio::DedicatedGpio,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const EDGES: u32 = 1_000_000;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::configure(system.clock_control, CpuClock::Clock160MHz).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut regular = io.pins.gpio4.into_push_pull_output();
+
+    let mut dedicated = DedicatedGpio::new(&mut system.peripheral_clock_control);
+    dedicated.connect_output(0, io.pins.gpio5);
+
+    let start = SystemTimer::now();
+    for _ in 0..EDGES / 2 {
+        regular.set_high().unwrap();
+        regular.set_low().unwrap();
+    }
+    let regular_ticks = SystemTimer::now() - start;
+
+    let start = SystemTimer::now();
+    for _ in 0..EDGES / 2 {
+        dedicated.set(0b1);
+        dedicated.clear(0b1);
+    }
+    let dedicated_ticks = SystemTimer::now() - start;
+
+    println!(
+        "regular GPIO: {} edges/s",
+        EDGES as u64 * SystemTimer::TICKS_PER_SECOND / regular_ticks
+    );
+    println!(
+        "dedicated GPIO: {} edges/s",
+        EDGES as u64 * SystemTimer::TICKS_PER_SECOND / dedicated_ticks
+    );
+
+    loop {}
+}
@@ -0,0 +1,95 @@
+//! Samples the ADC from deep sleep with a minimal wake up path
+//!
+//! The chip wakes up every 5 seconds. Timer wake ups only initialize the clocks
+//! and the ADC, store one reading of GPIO2 in RTC fast memory and go straight
+//! back to sleep. After 12 readings (and on the first boot) the full
+//! initialization runs and the collected readings are printed.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    adc::{AdcConfig, Attenuation, ADC, ADC1},
+    clock::ClockControl,
+    gpio::IO,
+    macros::ram,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::{self, WakeupSource},
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const MAGIC: u32 = 0xc0ffee;
+const PERIOD_SECS: u64 = 5;
+const READINGS: usize = 12;
+
+#[ram(rtc_fast, uninitialized)]
+static mut VALID: u32 = 0;
+
+#[ram(rtc_fast, uninitialized)]
+static mut COUNT: usize = 0;
+
+#[ram(rtc_fast, uninitialized)]
+static mut SAMPLES: [u16; READINGS] = [0; READINGS];
+
+#[entry]
+fn main() -> ! {
+    // Decide on the path before doing anything else
+    let fast_path = rtc_cntl::is_timer_wake() && unsafe { VALID == MAGIC && COUNT < READINGS };
+
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    if fast_path {
+        let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+        let analog = peripherals.APB_SARADC.split();
+
+        let mut adc1_config = AdcConfig::new();
+        let mut pin =
+            adc1_config.enable_pin(io.pins.gpio2.into_analog(), Attenuation::Attenuation11dB);
+        let mut adc1 = ADC::<ADC1>::adc(
+            &mut system.peripheral_clock_control,
+            analog.adc1,
+            adc1_config,
+        )
+        .unwrap();
+
+        let value: u16 = nb::block!(adc1.read(&mut pin)).unwrap();
+        unsafe {
+            SAMPLES[COUNT] = value;
+            COUNT += 1;
+        }
+
+        rtc_cntl::quick_sleep(PERIOD_SECS.secs());
+    }
+
+    // Full initialization
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    unsafe {
+        // RTC memory content is random after power-on
+        if VALID == MAGIC {
+            println!("Readings: {:?}", &SAMPLES[..COUNT.min(READINGS)]);
+        } else {
+            VALID = MAGIC;
+        }
+
+        COUNT = 0;
+    }
+
+    let err = rtc.sleep_deep(&[WakeupSource::Timer(PERIOD_SECS.secs())]);
+    panic!("Can't enter deep sleep: {:?}", err);
+}
@@ -0,0 +1,86 @@
+//! Deep sleep with a timer and an RTC GPIO as wakeup sources
+//!
+//! Sleeps until 10 seconds passed or GPIO3 is pulled low, whichever happens
+//! first. Waking up resets the chip, the next boot prints what ended the sleep
+//! and how often the chip woke up, counted in RTC fast memory.
+//!
+//! The following wiring is assumed:
+//! - A button connecting GPIO3 to GND, with an external pull-up resistor to 3.3
+//!   V: the pad configuration of the digital domain isn't kept while sleeping
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    macros::ram,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::{get_wakeup_cause, WakeupCause, WakeupLevel, WakeupSource},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[ram(rtc_fast, uninitialized)]
+static mut WAKEUPS: u32 = 0;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let wakeups = unsafe {
+        match get_wakeup_cause() {
+            Some(cause) => {
+                WAKEUPS += 1;
+                match cause {
+                    WakeupCause::Timer => println!("Woken up by the timer"),
+                    WakeupCause::RtcGpio(pins) => println!("Woken up by GPIOs {:06b}", pins),
+                    WakeupCause::Other => println!("Woken up by something else"),
+                }
+            }
+            None => {
+                WAKEUPS = 0;
+                println!("Cold boot");
+            }
+        }
+        WAKEUPS
+    };
+    println!("{} wakeups so far", wakeups);
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let button = io.pins.gpio3.into_floating_input();
+
+    // a wakeup level that already holds would end the sleep right away
+    let mut delay = Delay::new(&clocks);
+    while button.is_low().unwrap() {
+        delay.delay_ms(10u32);
+    }
+
+    println!("Going to sleep");
+    let err = rtc.sleep_deep(&[
+        WakeupSource::Timer(10u64.secs()),
+        WakeupSource::RtcGpio {
+            pins: 1 << 3,
+            level: WakeupLevel::Low,
+        },
+    ]);
+    panic!("Can't enter deep sleep: {:?}", err);
+}
@@ -0,0 +1,95 @@
+//! Sleeps on a `SYSTIMER` alarm that is only borrowed by the delay
+//!
+//! The delays first sleep on alarm 0. The alarm is still owned by `main`
+//! afterwards, so it is then turned into a periodic 1 Hz interrupt, while the
+//! delays busy-wait, which doesn't need an alarm at all.
+
+#![no_std]
+#![no_main]
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    systimer::{Alarm, Periodic, SystemTimer},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const TICKS_PER_US: u64 = SystemTimer::TICKS_PER_SECOND / 1_000_000;
+
+static ALARM0: Mutex<RefCell<Option<Alarm<Periodic, 0>>>> = Mutex::new(RefCell::new(None));
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let syst = SystemTimer::new(peripherals.SYSTIMER);
+
+    interrupt::enable(
+        peripherals::Interrupt::SYSTIMER_TARGET0,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    let delay = Delay::new(&clocks);
+
+    for us in [100, 1_000, 100_000] {
+        let start = SystemTimer::now();
+        delay.delay_with_alarm(&syst.alarm0, us);
+        let elapsed = SystemTimer::ticks_between(start, SystemTimer::now()) / TICKS_PER_US;
+
+        println!("Slept {} us, measured {} us", us, elapsed);
+    }
+
+    // the delay doesn't hold on to the alarm, it can be used for the interrupt now
+    let alarm0 = syst.alarm0.into_periodic();
+    alarm0.set_period(1u32.Hz());
+    alarm0.clear_interrupt();
+    alarm0.interrupt_enable(true);
+
+    critical_section::with(|cs| ALARM0.borrow_ref_mut(cs).replace(alarm0));
+
+    loop {
+        delay.delay(500_000);
+        println!("Alarm fired {} times", TICKS.load(Ordering::Relaxed));
+    }
+}
+
+#[interrupt]
+fn SYSTIMER_TARGET0() {
+    critical_section::with(|cs| {
+        ALARM0
+            .borrow_ref_mut(cs)
+            .as_mut()
+            .unwrap()
+            .clear_interrupt()
+    });
+
+    TICKS.fetch_add(1, Ordering::Relaxed);
+}
@@ -0,0 +1,79 @@
+//! Compares busy-waiting and sleeping delays
+//!
+//! The same delays are run once with the default busy-waiting `Delay` and then
+//! repeatedly after switching it to sleep until a `SYSTIMER` alarm fires. For
+//! each the requested and the measured duration are printed, they should match
+//! to within a few microseconds in both modes.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const TICKS_PER_US: u64 = SystemTimer::TICKS_PER_SECOND / 1_000_000;
+
+const DELAYS_US: [u32; 4] = [10, 100, 1_000, 100_000];
+
+fn measure(delay: &Delay) {
+    for us in DELAYS_US {
+        let start = SystemTimer::now();
+        delay.delay(us);
+        let elapsed = SystemTimer::ticks_between(start, SystemTimer::now()) / TICKS_PER_US;
+
+        println!("  requested {} us, measured {} us", us, elapsed);
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let syst = SystemTimer::new(peripherals.SYSTIMER);
+
+    // the alarm interrupt only needs to reach the CPU to end `wfi`, the delay
+    // clears it before any handler would run
+    interrupt::enable(
+        peripherals::Interrupt::SYSTIMER_TARGET0,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    println!("Busy-waiting:");
+    measure(&delay);
+
+    delay.set_low_power(syst.alarm0);
+
+    loop {
+        println!("Sleeping:");
+        measure(&delay);
+
+        delay.delay(1_000_000);
+    }
+}
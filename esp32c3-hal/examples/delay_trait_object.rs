@@ -0,0 +1,53 @@
+//! Passes the `Delay` driver as a trait object
+//!
+//! Blinks an LED connected to GPIO5 from a function which only knows about
+//! `&mut dyn DelayUs<u32>`, like a driver avoiding monomorphization would.
+
+#![no_std]
+#![no_main]
+
+use embedded_hal::blocking::delay::DelayUs;
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{Gpio5, Output, PushPull, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+
+fn blink(led: &mut Gpio5<Output<PushPull>>, delay: &mut dyn DelayUs<u32>) {
+    led.toggle().unwrap();
+    delay.delay_us(500_000);
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut led = io.pins.gpio5.into_push_pull_output();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        blink(&mut led, &mut delay);
+    }
+}
@@ -0,0 +1,102 @@
+//! Copies a buffer with the GDMA and compares it to a CPU copy
+//!
+//! A 16 KiB buffer is copied by a DMA channel in memory-to-memory mode, the
+//! copy is checked to be byte-exact. The same copy is done with
+//! `copy_from_slice` and the durations of both are printed, for a few sizes
+//! to see where the DMA setup cost stops mattering. Expect the CPU to win for
+//! small copies and to be on par or faster for large ones: the DMA doesn't
+//! copy faster, its benefit is that the CPU is free in the meantime.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::{DmaPeripheral, DmaPriority},
+    gdma::{Gdma, Mem2Mem},
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const SIZE: usize = 16 * 1024;
+const TICKS_PER_US: u64 = SystemTimer::TICKS_PER_SECOND / 1_000_000;
+
+static mut SRC: [u8; SIZE] = [0u8; SIZE];
+static mut DST: [u8; SIZE] = [0u8; SIZE];
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut tx_descriptors = [0u32; 5 * 3];
+    let mut rx_descriptors = [0u32; 5 * 3];
+
+    // the ADC isn't used with DMA here, its ID only pairs the two sides
+    let mut mem2mem = Mem2Mem::new(
+        dma_channel.configure(
+            false,
+            &mut tx_descriptors,
+            &mut rx_descriptors,
+            DmaPriority::Priority0,
+        ),
+        DmaPeripheral::Adc,
+    );
+
+    let (src, dst) = unsafe { (&mut SRC, &mut DST) };
+    for (i, byte) in src.iter_mut().enumerate() {
+        *byte = (i % 251) as u8;
+    }
+
+    mem2mem.mem_copy(dst, src).unwrap();
+    assert_eq!(src, dst);
+    println!("Copied {} bytes, the copy is identical", SIZE);
+
+    let mut threshold = None;
+    for size in [64, 256, 1024, 4096, SIZE] {
+        dst.fill(0);
+
+        let start = SystemTimer::now();
+        mem2mem.mem_copy(&mut dst[..size], &src[..size]).unwrap();
+        let dma_us = SystemTimer::ticks_between(start, SystemTimer::now()) / TICKS_PER_US;
+        assert_eq!(&src[..size], &dst[..size]);
+
+        let start = SystemTimer::now();
+        dst[..size].copy_from_slice(&src[..size]);
+        let cpu_us = SystemTimer::ticks_between(start, SystemTimer::now()) / TICKS_PER_US;
+
+        println!("{:5} bytes: DMA {:4} us, CPU {:4} us", size, dma_us, cpu_us);
+
+        if dma_us <= cpu_us && threshold.is_none() {
+            threshold = Some(size);
+        }
+    }
+
+    match threshold {
+        Some(size) => println!("The DMA keeps up with the CPU from {} bytes", size),
+        None => println!("The CPU was faster for all sizes"),
+    }
+
+    loop {}
+}
@@ -0,0 +1,100 @@
+//! embassy alarm latency
+//!
+//! One task wakes up every 10 ms, while a second one regularly keeps
+//! interrupts masked for a while to simulate a long critical section. Once a
+//! second the alarm latency recorded by the time driver is printed, the
+//! maximum grows to roughly the length of the critical section.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use embassy_time::{Duration, Instant, Timer};
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy::{self, diagnostics},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use static_cell::StaticCell;
+
+#[embassy_executor::task]
+async fn ticker() {
+    let mut next = Instant::now();
+
+    loop {
+        next += Duration::from_millis(10);
+        Timer::at(next).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn load(delay: Delay) {
+    loop {
+        Timer::after(Duration::from_millis(37)).await;
+
+        critical_section::with(|_| delay.delay(500));
+    }
+}
+
+#[embassy_executor::task]
+async fn report() {
+    loop {
+        Timer::after(Duration::from_secs(1)).await;
+
+        if let Some(latency) = diagnostics::alarm_latency() {
+            println!(
+                "alarm latency: min {} us, max {} us, last {} us ({} alarms)",
+                latency.min.as_micros(),
+                latency.max.as_micros(),
+                latency.last.as_micros(),
+                latency.count
+            );
+        }
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    let delay = Delay::new(&clocks);
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(ticker()).ok();
+        spawner.spawn(load(delay)).ok();
+        spawner.spawn(report()).ok();
+    });
+}
@@ -0,0 +1,74 @@
+//! Ten tasks delaying concurrently
+//!
+//! Each task has its own async `Delay` and waits for a different period. All
+//! delays share the single alarm used by the embassy time driver, so there can
+//! be more of them than the SYSTIMER has comparators.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use embedded_hal_async::delay::DelayUs;
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use static_cell::StaticCell;
+
+const TASKS: u32 = 10;
+
+#[embassy_executor::task(pool_size = 10)]
+async fn worker(id: u32, mut delay: embassy::Delay) {
+    let period = 100 * (id + 1);
+    let mut count = 0u32;
+
+    loop {
+        delay.delay_ms(period).await.unwrap();
+        count += 1;
+        println!("Task {} woke up after {} ms, {} times", id, period, count);
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        for id in 0..TASKS {
+            spawner.spawn(worker(id, embassy::Delay)).unwrap();
+        }
+    });
+}
@@ -0,0 +1,101 @@
+//! embassy GPIO callback
+//!
+//! Mixes a pin awaited by an async task with a pin handled by a plain callback
+//! from the GPIO interrupt handler.
+//!
+//! Connect GPIO4 and GPIO9 (or the BOOT button) so that both pins fire
+//! together: the task is woken for the rising edge of GPIO9 while the callback
+//! counts the falling edges of GPIO4.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use core::cell::Cell;
+
+use critical_section::Mutex;
+use embassy_executor::Executor;
+use embedded_hal_async::digital::Wait;
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy,
+    gpio::{self, Event, Gpio9, Input, PullDown},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+    IO,
+};
+use esp_backtrace as _;
+use static_cell::StaticCell;
+
+static FALLING_EDGES: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+fn on_falling_edge() {
+    critical_section::with(|cs| {
+        let edges = FALLING_EDGES.borrow(cs);
+        edges.set(edges.get() + 1);
+    });
+}
+
+#[embassy_executor::task]
+async fn ping(mut pin: Gpio9<Input<PullDown>>) {
+    loop {
+        pin.wait_for_rising_edge().await.unwrap();
+
+        let edges = critical_section::with(|cs| FALLING_EDGES.borrow(cs).get());
+        esp_println::println!("Ping! GPIO4 fell {} times so far", edges);
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    esp_println::println!("Init!");
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    // GPIO 9 as input, awaited by the `ping` task
+    let input = io.pins.gpio9.into_pull_down_input();
+
+    // GPIO 4 as input, handled by `on_falling_edge`
+    let mut button = io.pins.gpio4.into_pull_up_input();
+    gpio::set_interrupt_callback(&mut button, on_falling_edge);
+    button.listen(Event::FallingEdge);
+
+    // The GPIO interrupt both wakes futures and calls the callbacks
+    esp32c3_hal::interrupt::enable(
+        esp32c3_hal::peripherals::Interrupt::GPIO,
+        esp32c3_hal::interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(ping(input)).ok();
+    });
+}
@@ -0,0 +1,76 @@
+//! embassy idle
+//!
+//! Blinks an LED on GPIO5 once a second. In between the executor has nothing
+//! to do and sleeps in `wfi` with the CPU clock gated, compare the current
+//! drawn by the board with a busy loop (e.g. the `blinky` example) to see the
+//! difference.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use embassy_time::{Duration, Instant, Timer};
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy,
+    gpio::{Gpio5, Output, PushPull},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+    IO,
+};
+use esp_backtrace as _;
+use static_cell::StaticCell;
+
+#[embassy_executor::task]
+async fn blink(mut led: Gpio5<Output<PushPull>>) {
+    let mut next = Instant::now();
+
+    loop {
+        led.toggle().unwrap();
+        next += Duration::from_secs(1);
+        Timer::at(next).await;
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // `Rtc::new` also allows the CPU clock to be gated while waiting for an
+    // interrupt
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let led = io.pins.gpio5.into_push_pull_output();
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(blink(led)).ok();
+    });
+}
@@ -0,0 +1,148 @@
+//! Runs embassy tasks from an interrupt handler
+//!
+//! An executor is polled in the handler of a software interrupt, which it
+//! pends whenever one of its tasks is woken. The TIMG1 timer interrupt spawns a
+//! new task on it every 500 ms, the main loop checks that every spawned task
+//! actually ran.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use critical_section::Mutex;
+use embassy_executor::{raw, SendSpawner};
+use embassy_time::{Duration, Timer as EmbassyTimer};
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy,
+    interrupt::{self, SoftwareInterrupt},
+    peripherals::{self, Peripherals, TIMG1},
+    prelude::*,
+    riscv,
+    timer::{Timer, Timer0, TimerGroup},
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+static mut EXECUTOR: Option<raw::Executor> = None;
+static SPAWNER: Mutex<RefCell<Option<SendSpawner>>> = Mutex::new(RefCell::new(None));
+static TIMER1: Mutex<RefCell<Option<Timer<Timer0<TIMG1>>>>> = Mutex::new(RefCell::new(None));
+
+static SPAWNED: AtomicU32 = AtomicU32::new(0);
+static RAN: AtomicU32 = AtomicU32::new(0);
+
+#[embassy_executor::task(pool_size = 4)]
+async fn job(number: u32) {
+    println!("Job {} started", number);
+    EmbassyTimer::after(Duration::from_millis(100)).await;
+    RAN.fetch_add(1, Ordering::Relaxed);
+    println!("Job {} done", number);
+}
+
+fn pend_executor(_: *mut ()) {
+    interrupt::pend(SoftwareInterrupt::SoftwareInterrupt0);
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+    let mut timer1 = timer_group1.timer0;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    let executor = unsafe {
+        EXECUTOR = Some(raw::Executor::new(pend_executor, core::ptr::null_mut()));
+        EXECUTOR.as_ref().unwrap()
+    };
+
+    critical_section::with(|cs| {
+        SPAWNER
+            .borrow_ref_mut(cs)
+            .replace(executor.spawner().make_send())
+    });
+
+    // the executor runs at a lower priority than the interrupt spawning tasks
+    interrupt::enable(
+        peripherals::Interrupt::FROM_CPU_INTR0,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+    interrupt::enable(
+        peripherals::Interrupt::TG1_T0_LEVEL,
+        interrupt::Priority::Priority2,
+    )
+    .unwrap();
+
+    timer1.start(500u64.millis());
+    timer1.listen();
+    critical_section::with(|cs| TIMER1.borrow_ref_mut(cs).replace(timer1));
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        delay.delay_ms(1000u32);
+
+        let spawned = SPAWNED.load(Ordering::Relaxed);
+        let ran = RAN.load(Ordering::Relaxed);
+        println!("{} jobs spawned, {} done", spawned, ran);
+
+        // a job spawned within the last 100 ms may still be waiting
+        assert!(spawned - ran <= 1);
+    }
+}
+
+#[interrupt]
+fn FROM_CPU_INTR0() {
+    interrupt::unpend(SoftwareInterrupt::SoftwareInterrupt0);
+
+    unsafe { EXECUTOR.as_ref().unwrap().poll() };
+}
+
+#[interrupt]
+fn TG1_T0_LEVEL() {
+    critical_section::with(|cs| {
+        let mut timer1 = TIMER1.borrow_ref_mut(cs);
+        let timer1 = timer1.as_mut().unwrap();
+
+        timer1.clear_interrupt();
+        timer1.start(500u64.millis());
+
+        let number = SPAWNED.load(Ordering::Relaxed);
+        let spawner = SPAWNER.borrow_ref(cs).unwrap();
+        if spawner.spawn(job(number)).is_ok() {
+            SPAWNED.store(number + 1, Ordering::Relaxed);
+        }
+    });
+}
@@ -0,0 +1,72 @@
+//! Awaits a peripheral interrupt without an async driver
+//!
+//! Timer 0 of TIMG1 fires an interrupt every 500 ms. There is no async API for
+//! it, so the task awaits the raw interrupt with `InterruptFuture` and clears
+//! the timer's interrupt itself afterwards.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy,
+    interrupt::InterruptFuture,
+    peripherals::{Interrupt, Peripherals, TIMG1},
+    prelude::*,
+    timer::{Timer, Timer0, TimerGroup},
+    Rtc,
+};
+use esp_backtrace as _;
+use static_cell::StaticCell;
+
+#[embassy_executor::task]
+async fn ticks(mut timer: Timer<Timer0<TIMG1>>) {
+    timer.listen();
+    timer.start(500u64.millis());
+
+    loop {
+        InterruptFuture::new(Interrupt::TG1_T0_LEVEL).await;
+        // the future only deals with the CPU interrupt, the timer keeps
+        // signalling it until cleared
+        timer.clear_interrupt();
+
+        esp_println::println!("Tick");
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(ticks(timer_group1.timer0)).ok();
+    });
+}
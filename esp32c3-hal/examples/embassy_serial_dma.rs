@@ -0,0 +1,124 @@
+//! Receives fixed-length frames on UART1 through DMA
+//!
+//! UHCI0 moves the received bytes to memory and ends the transfer after
+//! exactly 32 bytes, so the task is only woken once a complete frame has
+//! arrived.
+//!
+//! The following wiring is assumed:
+//! - RX => GPIO2, connected to a device sending 32 byte frames at 115200 baud
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::{DmaPriority, *},
+    embassy,
+    gdma::*,
+    peripherals::{Peripherals, UART1},
+    prelude::*,
+    timer::TimerGroup,
+    uart::{config::Config, dma::UartDma, TxRxPins},
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+use static_cell::StaticCell;
+
+macro_rules! singleton {
+    ($val:expr) => {{
+        type T = impl Sized;
+        static STATIC_CELL: StaticCell<T> = StaticCell::new();
+        let (x,) = STATIC_CELL.init(($val,));
+        x
+    }};
+}
+
+const FRAME_SIZE: usize = 32;
+
+pub type SerialType<'d> = UartDma<
+    'd,
+    UART1,
+    ChannelTx<'d, Channel0TxImpl, esp32c3_hal::gdma::Channel0>,
+    ChannelRx<'d, Channel0RxImpl, esp32c3_hal::gdma::Channel0>,
+    SuitablePeripheral0,
+>;
+
+#[embassy_executor::task]
+async fn reader(serial: &'static mut SerialType<'static>) {
+    let mut frame = [0u8; FRAME_SIZE];
+    loop {
+        serial.read_dma_exact(&mut frame).await.unwrap();
+        esp_println::println!("Frame received: {:02x?}", frame);
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    esp_println::println!("Init!");
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32c3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    esp32c3_hal::interrupt::enable(
+        esp32c3_hal::peripherals::Interrupt::DMA_CH0,
+        esp32c3_hal::interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    // a single descriptor covers up to 4092 bytes, plenty for one frame
+    let descriptors = singleton!([0u32; 3]);
+    let rx_descriptors = singleton!([0u32; 3]);
+
+    let serial = singleton!(Uart::new_with_config(
+        peripherals.UART1,
+        Some(Config::default().baudrate(115200)),
+        Some(pins),
+        &clocks,
+    )
+    .with_dma(
+        peripherals.UHCI0,
+        dma_channel.configure(false, descriptors, rx_descriptors, DmaPriority::Priority0),
+        &mut system.peripheral_clock_control,
+    ));
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(reader(serial)).ok();
+    });
+}
@@ -0,0 +1,94 @@
+//! Schedules an embassy timer across the wrap of the SYSTIMER counter
+//!
+//! The 52 bit counter wraps after about 8.9 years, so it is loaded with a value
+//! one second before the wrap. A two second timer started then has its alarm
+//! on the other side of the wrap, it has to fire after two seconds, not
+//! earlier, and time has to keep going forward past the wrap.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use embassy_time::{Duration, Instant, Timer};
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy,
+    peripherals::{Peripherals, SYSTIMER},
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use static_cell::StaticCell;
+
+const BEFORE_WRAP: u64 = SystemTimer::TICKS_PER_SECOND;
+
+#[embassy_executor::task]
+async fn run() {
+    let start = Instant::now();
+    println!("Counter at {:#x}, starting a 2 s timer", start.as_ticks());
+
+    // waking up early would mean the alarm matched before the wrap
+    Timer::after(Duration::from_secs(2)).await;
+
+    let elapsed = Instant::now() - start;
+    println!(
+        "Woke up after {} ms, counter at {:#x}, raw counter at {:#x}",
+        elapsed.as_millis(),
+        Instant::now().as_ticks(),
+        SystemTimer::now()
+    );
+    assert!(elapsed >= Duration::from_secs(2));
+    assert!(elapsed < Duration::from_millis(2100));
+    assert!(Instant::now().as_ticks() > SystemTimer::BIT_MASK);
+    println!("Alarm fired on time, time kept going across the wrap");
+
+    loop {
+        Timer::after(Duration::from_secs(1)).await;
+        println!("Still running at {:#x}", Instant::now().as_ticks());
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    // the HAL has no API to load the counter, go through the registers
+    let load = SystemTimer::BIT_MASK - BEFORE_WRAP;
+    let systimer = unsafe { &*SYSTIMER::PTR };
+    systimer
+        .unit0_load_hi
+        .write(|w| unsafe { w.timer_unit0_load_hi().bits((load >> 32) as u32) });
+    systimer
+        .unit0_load_lo
+        .write(|w| unsafe { w.timer_unit0_load_lo().bits(load as u32) });
+    systimer
+        .unit0_load
+        .write(|w| w.timer_unit0_load().set_bit());
+
+    embassy::init(&clocks, SystemTimer::new(peripherals.SYSTIMER));
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(run()).unwrap();
+    });
+}
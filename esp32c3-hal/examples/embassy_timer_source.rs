@@ -0,0 +1,72 @@
+//! embassy with the time driver selected at runtime
+//!
+//! Hold the BOOT button (GPIO9) while resetting to run the time driver on
+//! TIMG0, otherwise the SYSTIMER is used. The same firmware works with either
+//! timer, as both run at the same tick rate.
+
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use embassy_executor::Executor;
+use embassy_time::{Duration, Timer};
+use esp32c3_hal::{
+    clock::ClockControl,
+    embassy::{self, TimerSource},
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use static_cell::StaticCell;
+
+#[embassy_executor::task]
+async fn run(source: &'static str) {
+    loop {
+        esp_println::println!("Tick from {}", source);
+        Timer::after(Duration::from_millis(1_000)).await;
+    }
+}
+
+static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let button = io.pins.gpio9.into_pull_up_input();
+
+    let source = if button.is_low().unwrap() {
+        embassy::init(&clocks, TimerSource::Timg0(timer_group0.timer0));
+        "TIMG0"
+    } else {
+        embassy::init(
+            &clocks,
+            TimerSource::Systimer(SystemTimer::new(peripherals.SYSTIMER)),
+        );
+        "SYSTIMER"
+    };
+
+    let executor = EXECUTOR.init(Executor::new());
+    executor.run(|spawner| {
+        spawner.spawn(run(source)).ok();
+    });
+}
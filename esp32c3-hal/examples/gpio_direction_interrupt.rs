@@ -0,0 +1,108 @@
+//! Shows the interrupt behavior of a pin changing its direction
+//!
+//! GPIO5 is a bidirectional line listening for falling edges. It is flipped
+//! between input and output mode and it is checked that:
+//! - the edge detection configured once survives the direction changes
+//! - a push-pull output doesn't detect any edges
+//! - switching back to input doesn't report an edge that never happened
+//! - an open-drain output detects the edges it drives itself
+//!
+//! The following wiring is assumed:
+//! - GPIO4 => GPIO5
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{Event, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut delay = Delay::new(&clocks);
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // the other end of the line, released while high
+    let mut driver = io.pins.gpio4.into_open_drain_output();
+    driver.set_high().unwrap();
+
+    // the status is polled, the GPIO interrupt isn't enabled in the CPU
+    let mut line = io.pins.gpio5.into_pull_up_input();
+    line.listen(Event::FallingEdge);
+    line.clear_interrupt();
+
+    driver.set_low().unwrap();
+    delay.delay_us(10u32);
+    assert!(line.is_pcore_interrupt_set());
+    println!("Input: edge driven by the other end detected");
+    driver.set_high().unwrap();
+    delay.delay_us(10u32);
+    line.clear_interrupt();
+
+    let mut line = line.into_push_pull_output();
+    assert!(line.is_listening());
+    line.set_low().unwrap();
+    delay.delay_us(10u32);
+    line.set_high().unwrap();
+    delay.delay_us(10u32);
+    assert!(!line.is_pcore_interrupt_set());
+    println!("Push-pull output: still listening, own edge not detected");
+
+    let mut line = line.into_pull_up_input();
+    delay.delay_us(10u32);
+    assert!(!line.is_pcore_interrupt_set());
+    println!("Input again: no edge reported for the direction change");
+
+    driver.set_low().unwrap();
+    delay.delay_us(10u32);
+    assert!(line.is_pcore_interrupt_set());
+    println!("Input again: edge driven by the other end detected");
+    driver.set_high().unwrap();
+    delay.delay_us(10u32);
+    line.clear_interrupt();
+
+    // the conversion disables the pull-up, nothing else pulls the line high
+    let mut line = line.into_open_drain_output();
+    line.internal_pull_up(true);
+    line.set_high().unwrap();
+    delay.delay_us(10u32);
+    line.clear_interrupt();
+
+    line.set_low().unwrap();
+    delay.delay_us(10u32);
+    assert!(line.is_pcore_interrupt_set());
+    println!("Open-drain output: own edge detected");
+
+    line.unlisten();
+    line.clear_interrupt();
+    assert!(!line.is_listening());
+
+    println!("Done");
+
+    loop {}
+}
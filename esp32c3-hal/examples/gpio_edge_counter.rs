@@ -0,0 +1,70 @@
+//! Counts presses of the boot button (GPIO9) using the GPIO interrupt
+//!
+//! The ESP32-C3 has no PCNT peripheral, so edges are counted by the GPIO
+//! interrupt handler instead. On chips with a PCNT peripheral (see the `pcnt`
+//! examples of the ESP32, ESP32-S2 and ESP32-S3) the counting is done in
+//! hardware, without an interrupt per edge.
+//!
+//! The button isn't debounced, so a single press might be counted more than
+//! once.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{self, Event, IO},
+    interrupt,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let counter = io
+        .pins
+        .gpio9
+        .into_pull_up_input()
+        .into_counter(Event::FallingEdge);
+
+    interrupt::enable(peripherals::Interrupt::GPIO, interrupt::Priority::Priority1).unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+    loop {
+        println!("Button pressed {} times", counter.count());
+        delay.delay_ms(1000u32);
+    }
+}
+
+#[interrupt]
+fn GPIO() {
+    gpio::count_edges();
+}
@@ -0,0 +1,65 @@
+//! Configures the input synchronizers of a GPIO
+//!
+//! Every combination of the two synchronizer stages is set on GPIO9 and read
+//! back from the pin register, then the pin is left with both stages sampling
+//! on the rising edge and its level is printed.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{InputSync, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const SETTINGS: [InputSync; 3] = [
+    InputSync::Bypass,
+    InputSync::FallingEdge,
+    InputSync::RisingEdge,
+];
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut button = io.pins.gpio9.into_pull_up_input();
+
+    for first in SETTINGS {
+        for second in SETTINGS {
+            button.set_input_sync(first, second);
+            assert_eq!(button.input_sync(), (first, second));
+        }
+    }
+    println!("All synchronizer settings read back as configured");
+
+    button.set_input_sync(InputSync::RisingEdge, InputSync::RisingEdge);
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        println!("GPIO9 is high: {}", button.is_high().unwrap());
+        delay.delay_ms(500u32);
+    }
+}
@@ -0,0 +1,95 @@
+//! GPIO interrupt with a handler registered at runtime
+//!
+//! Like the `gpio_interrupt` example, but the handler is an ordinary function
+//! registered with `interrupt::set_handler` instead of an `#[interrupt]`
+//! function. After the boot button was pressed five times the handler is
+//! removed again and further presses are ignored.
+
+#![no_std]
+#![no_main]
+
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{Event, Gpio9, Input, PullDown, IO},
+    interrupt,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const PRESSES: u32 = 5;
+
+static BUTTON: Mutex<RefCell<Option<Gpio9<Input<PullDown>>>>> = Mutex::new(RefCell::new(None));
+static COUNT: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+fn on_button() {
+    critical_section::with(|cs| {
+        BUTTON
+            .borrow_ref_mut(cs)
+            .as_mut()
+            .unwrap()
+            .clear_interrupt();
+
+        let count = COUNT.borrow(cs).get() + 1;
+        COUNT.borrow(cs).set(count);
+        println!("Button pressed {} times", count);
+    });
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut led = io.pins.gpio5.into_push_pull_output();
+
+    let mut button = io.pins.gpio9.into_pull_down_input();
+    button.listen(Event::FallingEdge);
+    critical_section::with(|cs| BUTTON.borrow_ref_mut(cs).replace(button));
+
+    interrupt::set_handler(
+        peripherals::Interrupt::GPIO,
+        on_button,
+        interrupt::Priority::Priority3,
+    )
+    .unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+    let mut registered = true;
+    loop {
+        led.toggle().unwrap();
+        delay.delay_ms(500u32);
+
+        if registered && critical_section::with(|cs| COUNT.borrow(cs).get()) >= PRESSES {
+            interrupt::free(peripherals::Interrupt::GPIO);
+            registered = false;
+            println!("Handler removed");
+        }
+    }
+}
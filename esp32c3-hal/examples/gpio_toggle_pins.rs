@@ -0,0 +1,69 @@
+//! Drives a differential pair by toggling two pins at once
+//!
+//! GPIO4 and GPIO5 carry complementary square waves. Watch them with a logic
+//! analyzer or scope: in the first burst both are toggled by a single call to
+//! `toggle_pins` and transition on the same edge, in the second burst they
+//! are toggled one after the other, and GPIO5 visibly lags behind GPIO4.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{self, Pin, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut positive = io.pins.gpio4.into_push_pull_output();
+    let mut negative = io.pins.gpio5.into_push_pull_output();
+
+    positive.set_high().unwrap();
+    negative.set_low().unwrap();
+
+    let mask = 1 << positive.number() | 1 << negative.number();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        // both edges in the same clock cycle
+        for _ in 0..10 {
+            gpio::toggle_pins(mask);
+            delay.delay_us(10u32);
+        }
+
+        delay.delay_us(100u32);
+
+        // one edge after the other
+        for _ in 0..10 {
+            positive.toggle().unwrap();
+            negative.toggle().unwrap();
+            delay.delay_us(10u32);
+        }
+
+        delay.delay_ms(1u32);
+    }
+}
@@ -0,0 +1,75 @@
+//! Toggles GPIO4 as fast as possible with the regular and the unchecked
+//! output methods
+//!
+//! Prints the edge rate and the CPU cycles per edge achieved by
+//! `set_high`/`set_low` and by `set_high_unchecked`/`set_low_unchecked`.
+//! Connect a logic analyzer or scope to GPIO4 to see the resulting frequency.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::{ClockControl, CpuClock},
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const EDGES: u32 = 1_000_000;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::configure(system.clock_control, CpuClock::Clock160MHz).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut pin = io.pins.gpio4.into_push_pull_output();
+
+    let start = SystemTimer::now();
+    for _ in 0..EDGES / 2 {
+        pin.set_high().unwrap();
+        pin.set_low().unwrap();
+    }
+    let regular_ticks = SystemTimer::now() - start;
+
+    let start = SystemTimer::now();
+    for _ in 0..EDGES / 2 {
+        pin.set_high_unchecked();
+        pin.set_low_unchecked();
+    }
+    let unchecked_ticks = SystemTimer::now() - start;
+
+    for (name, ticks) in [
+        ("set_high/set_low", regular_ticks),
+        ("unchecked", unchecked_ticks),
+    ] {
+        let edges_per_second = EDGES as u64 * SystemTimer::TICKS_PER_SECOND / ticks;
+        println!(
+            "{}: {} edges/s, {} Hz, {} CPU cycles per edge",
+            name,
+            edges_per_second,
+            edges_per_second / 2,
+            clocks.cpu_clock.raw() as u64 / edges_per_second
+        );
+    }
+
+    loop {}
+}
@@ -0,0 +1,88 @@
+//! Detects an output that doesn't reach its commanded level
+//!
+//! GPIO4 is an output whose level is read back after every change. GPIO5 plays
+//! a fault on the line: while it pulls the line low, driving GPIO4 high has to
+//! be reported as a mismatch.
+//!
+//! The following wiring is assumed:
+//! - GPIO4 => GPIO5
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{VerifyError, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+// enough for the internal pull-up to charge the line
+const SETTLE_US: u32 = 10;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut delay = Delay::new(&clocks);
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // the fault, released for now
+    let mut fault = io.pins.gpio5.into_open_drain_output();
+    fault.set_high().unwrap();
+
+    // a push-pull output can't be read back until its input is enabled
+    let mut output = io.pins.gpio4.into_push_pull_output();
+    output.set_high().unwrap();
+    assert_eq!(
+        output.verify_output(&mut delay, SETTLE_US),
+        Err(VerifyError::InputDisabled)
+    );
+    output.enable_input(true);
+    assert_eq!(output.verify_output(&mut delay, SETTLE_US), Ok(()));
+    println!("Push-pull output verified");
+
+    // an open-drain output only goes high if nothing holds the line low
+    let mut output = output.into_open_drain_output();
+    output.internal_pull_up(true);
+
+    output.set_high().unwrap();
+    assert_eq!(output.verify_output(&mut delay, SETTLE_US), Ok(()));
+    output.set_low().unwrap();
+    assert_eq!(output.verify_output(&mut delay, SETTLE_US), Ok(()));
+    println!("Open-drain output verified");
+
+    fault.set_low().unwrap();
+    output.set_high().unwrap();
+    assert_eq!(
+        output.verify_output(&mut delay, SETTLE_US),
+        Err(VerifyError::Mismatch { driven_high: true })
+    );
+    println!("Line held low: mismatch detected");
+
+    fault.set_high().unwrap();
+    assert_eq!(output.verify_output(&mut delay, SETTLE_US), Ok(()));
+    println!("Fault released: output verified again");
+
+    loop {}
+}
@@ -2,6 +2,10 @@
 //!
 //! This example dumps the calibration data from a BMP180 sensor
 //!
+//! The register address is written and the data read back in a single
+//! transaction, using a repeated START between both phases. Watching SDA and
+//! SCL with a logic analyzer shows no STOP condition before the read.
+//!
 //! The following wiring is assumed:
 //! - SDA => GPIO1
 //! - SCL => GPIO2
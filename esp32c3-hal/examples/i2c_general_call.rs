@@ -0,0 +1,97 @@
+//! Sends and receives I2C general calls
+//!
+//! This needs two boards: flash one with `MASTER` set to `true` and the other
+//! one with it set to `false`.
+//!
+//! - The master sends a general call with a counter every second and reports
+//!   whether any device acknowledged it.
+//! - The slave has the address 0x55, but also responds to general calls. It
+//!   prints the bytes it receives.
+//!
+//! The following wiring is assumed, on both boards:
+//! - SDA => GPIO1
+//! - SCL => GPIO2
+//! - GND connected between the boards
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::{I2cSlave, I2C},
+    peripherals::{Peripherals, I2C0},
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const MASTER: bool = true;
+const SLAVE_ADDRESS: u8 = 0x55;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    if MASTER {
+        let mut i2c = I2C::new(
+            peripherals.I2C0,
+            io.pins.gpio1,
+            io.pins.gpio2,
+            100u32.kHz(),
+            &mut system.peripheral_clock_control,
+            &clocks,
+        );
+        let mut delay = Delay::new(&clocks);
+
+        let mut counter = 0u8;
+        loop {
+            // the first byte must not be one of the values reserved by the
+            // specification, e.g. 0x06 resets devices
+            match i2c.write_general_call(&[0xa5, counter]) {
+                Ok(()) => println!("General call {} acknowledged", counter),
+                Err(err) => println!("General call {} failed: {:?}", counter, err),
+            }
+
+            counter = counter.wrapping_add(1);
+            delay.delay_ms(1000u32);
+        }
+    } else {
+        let mut i2c = I2cSlave::new(
+            peripherals.I2C0,
+            io.pins.gpio1,
+            io.pins.gpio2,
+            SLAVE_ADDRESS,
+            &mut system.peripheral_clock_control,
+        );
+        i2c.set_general_call(true);
+
+        let mut buffer = [0u8; I2cSlave::<I2C0>::FIFO_SIZE];
+        loop {
+            if i2c.is_transfer_complete() {
+                let count = i2c.read(&mut buffer);
+                println!("Received {:02x?}", &buffer[..count]);
+                i2c.clear_interrupts();
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Uses the partial data of an I2C read aborted by the timeout
+//!
+//! Reads 32 bytes from an EEPROM in a loop. When a read times out, the number
+//! of bytes received before the timeout is printed together with that data.
+//!
+//! The following wiring is assumed:
+//! - SDA => GPIO1
+//! - SCL => GPIO2, with a button to GND simulating a device stretching the
+//!   clock: pressing it while a read is in progress aborts that read
+//! - An EEPROM like the 24C32 at address 0x50
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::{Error, I2C},
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // a slow bus makes it easy to hit a read in progress, 32 bytes take about
+    // 30 ms at 10 kHz
+    let mut i2c = I2C::new_with_timeout(
+        peripherals.I2C0,
+        io.pins.gpio1,
+        io.pins.gpio2,
+        10u32.kHz(),
+        &mut system.peripheral_clock_control,
+        &clocks,
+        Some(100),
+    );
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let mut data = [0u8; 32];
+
+        match i2c.write_read(0x50, &[0x00, 0x00], &mut data) {
+            Ok(()) => println!("Complete: {:02x?}", data),
+            Err(Error::TimeOut(received)) => {
+                println!(
+                    "Timed out after {} bytes: {:02x?}",
+                    received,
+                    &data[..received]
+                )
+            }
+            Err(err) => println!("Error: {:?}", err),
+        }
+
+        delay.delay_ms(100u32);
+    }
+}
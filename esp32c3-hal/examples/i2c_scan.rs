@@ -0,0 +1,73 @@
+//! Scans the I2C bus and prints the addresses of the devices found
+//!
+//! The scan is repeated every 5 seconds, so devices can be connected while
+//! the example runs. Note that some devices don't respond to the zero-length
+//! writes used for probing.
+//!
+//! The following wiring is assumed:
+//! - SDA => GPIO1
+//! - SCL => GPIO2
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::I2C,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let mut i2c = I2C::new(
+        peripherals.I2C0,
+        io.pins.gpio1,
+        io.pins.gpio2,
+        100u32.kHz(),
+        &mut system.peripheral_clock_control,
+        &clocks,
+    );
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        println!("Scanning...");
+
+        let mut found = 0;
+        for address in i2c.scan() {
+            println!("Found a device at 0x{:02x}", address);
+            found += 1;
+        }
+        println!("{} device(s) found", found);
+
+        if i2c.is_bus_busy() {
+            println!("The bus is stuck, check the wiring and the pull-ups");
+        }
+
+        delay.delay_ms(5000u32);
+    }
+}
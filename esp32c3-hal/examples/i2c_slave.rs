@@ -0,0 +1,140 @@
+//! I2C slave example
+//!
+//! The ESP32-C3 presents itself as an I2C device with address 0x55 exposing
+//! 16 byte-wide registers, like many sensors do:
+//!
+//! - a write of `[reg, data...]` stores `data` starting at register `reg`
+//! - a write of `[reg]` followed by a read returns the registers starting at
+//!   `reg`
+//!
+//! The register pointer auto-increments in both cases. Register 0 is
+//! read-only and holds a counter of completed transfers.
+//!
+//! The following wiring is assumed:
+//! - SDA => GPIO1
+//! - SCL => GPIO2
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::I2cSlave,
+    interrupt,
+    peripherals::{self, Peripherals, I2C0},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+
+const ADDRESS: u8 = 0x55;
+const REGISTER_COUNT: usize = 16;
+
+struct Device {
+    i2c: I2cSlave<'static, I2C0>,
+    registers: [u8; REGISTER_COUNT],
+    pointer: usize,
+    expecting_pointer: bool,
+}
+
+static DEVICE: Mutex<RefCell<Option<Device>>> = Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let mut i2c = I2cSlave::new(
+        peripherals.I2C0,
+        io.pins.gpio1,
+        io.pins.gpio2,
+        ADDRESS,
+        &mut system.peripheral_clock_control,
+    );
+    i2c.listen();
+
+    critical_section::with(|cs| {
+        DEVICE.borrow_ref_mut(cs).replace(Device {
+            i2c,
+            registers: [0u8; REGISTER_COUNT],
+            pointer: 0,
+            expecting_pointer: true,
+        })
+    });
+
+    interrupt::enable(
+        peripherals::Interrupt::I2C_EXT0,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    loop {}
+}
+
+#[interrupt]
+fn I2C_EXT0() {
+    critical_section::with(|cs| {
+        let mut device = DEVICE.borrow_ref_mut(cs);
+        let device = device.as_mut().unwrap();
+
+        // Bytes written by the master: register pointer first, then data
+        let mut buffer = [0u8; I2cSlave::<I2C0>::FIFO_SIZE];
+        let count = device.i2c.read(&mut buffer);
+        for byte in &buffer[..count] {
+            if device.expecting_pointer {
+                device.pointer = *byte as usize % REGISTER_COUNT;
+                device.expecting_pointer = false;
+            } else {
+                if device.pointer != 0 {
+                    device.registers[device.pointer] = *byte;
+                }
+                device.pointer = (device.pointer + 1) % REGISTER_COUNT;
+            }
+        }
+
+        // The master wants to read but nothing is queued, SCL is held low until
+        // the response is ready
+        if device.i2c.is_clock_stretched() {
+            let pointer = device.pointer;
+            device.i2c.write(&device.registers[pointer..]);
+            device.i2c.release_clock_stretch();
+        }
+
+        if device.i2c.is_transfer_complete() {
+            if device.i2c.is_master_read() {
+                // Drop whatever the master didn't read
+                device.i2c.reset_fifo();
+            }
+
+            device.registers[0] = device.registers[0].wrapping_add(1);
+            device.expecting_pointer = true;
+        }
+
+        device.i2c.clear_interrupts();
+    });
+}
@@ -0,0 +1,79 @@
+//! Shows how an I2C transfer on a stalled bus is aborted by the timeout
+//!
+//! The timeout is shortened at runtime after the first attempt, and the bus
+//! state is reported before each transfer.
+//!
+//! The following wiring is assumed:
+//! - SDA => GPIO1
+//! - SCL => GPIO2, shorted to GND to simulate a device holding the clock low
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::I2C,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // Give up if SCL doesn't change for 1000 bus clock cycles (10 ms at 100 kHz)
+    let mut i2c = I2C::new_with_timeout(
+        peripherals.I2C0,
+        io.pins.gpio1,
+        io.pins.gpio2,
+        100u32.kHz(),
+        &mut system.peripheral_clock_control,
+        &clocks,
+        Some(1000),
+    );
+
+    let mut delay = Delay::new(&clocks);
+
+    let mut first = true;
+    loop {
+        println!("Bus busy: {}", i2c.is_bus_busy());
+
+        let start = SystemTimer::now();
+        let result = i2c.write(0x77, &[0xaa]);
+        let elapsed = SystemTimer::ticks_between(start, SystemTimer::now())
+            / (SystemTimer::TICKS_PER_SECOND / 1_000_000);
+
+        println!("{:?} after {} us", result, elapsed);
+
+        if first {
+            // 100 bus clock cycles, i.e. 1 ms at 100 kHz
+            i2c.set_timeout(Some(100));
+            first = false;
+        }
+
+        delay.delay_ms(1000u32);
+    }
+}
@@ -0,0 +1,74 @@
+//! Queries the state of CPU interrupts
+//!
+//! The software interrupt 0 is enabled at priority 2. With interrupt
+//! vectoring, each priority has a CPU interrupt of its own, here CPU interrupt
+//! 2. Its priority and enable bit are read back, then the software interrupt
+//! is raised inside a critical section, where it stays pending until the
+//! critical section ends and the handler runs.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt::{self, CpuInterrupt, Priority, SoftwareInterrupt},
+    peripherals::{self, Peripherals},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    interrupt::enable(peripherals::Interrupt::FROM_CPU_INTR0, Priority::Priority2).unwrap();
+
+    let cpu_interrupt = CpuInterrupt::Interrupt2;
+    println!(
+        "CPU interrupt 2: priority {:?}, enabled {}",
+        interrupt::priority(cpu_interrupt),
+        interrupt::is_enabled(cpu_interrupt)
+    );
+    assert_eq!(interrupt::priority(cpu_interrupt), Priority::Priority2);
+    assert!(interrupt::is_enabled(cpu_interrupt));
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    critical_section::with(|_| {
+        assert!(!interrupt::is_pending(cpu_interrupt));
+        interrupt::pend(SoftwareInterrupt::SoftwareInterrupt0);
+        assert!(interrupt::is_pending(cpu_interrupt));
+        println!("Pending while interrupts are masked");
+    });
+
+    assert!(!interrupt::is_pending(cpu_interrupt));
+    println!("Handled after the critical section");
+
+    loop {}
+}
+
+#[interrupt]
+fn FROM_CPU_INTR0() {
+    interrupt::unpend(SoftwareInterrupt::SoftwareInterrupt0);
+    println!("Software interrupt handled");
+}
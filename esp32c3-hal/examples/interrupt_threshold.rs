@@ -0,0 +1,129 @@
+//! Masks low priority interrupts while letting high priority ones through
+//!
+//! Two timers interrupt every 10 ms, TIMG0 at priority 1 and TIMG1 at priority
+//! 3. While a `PriorityGuard` raises the threshold to priority 2, only the
+//! TIMG1 handler keeps running. The TIMG0 interrupt stays pending and is
+//! serviced as soon as the guard is dropped.
+
+#![no_std]
+#![no_main]
+
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt::{self, Priority, PriorityGuard},
+    peripherals::{self, Peripherals, TIMG0, TIMG1},
+    prelude::*,
+    riscv,
+    timer::{Timer, Timer0, TimerGroup},
+    Cpu,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+static TIMER0: Mutex<RefCell<Option<Timer<Timer0<TIMG0>>>>> = Mutex::new(RefCell::new(None));
+static TIMER1: Mutex<RefCell<Option<Timer<Timer0<TIMG1>>>>> = Mutex::new(RefCell::new(None));
+static LOW: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+static HIGH: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+fn counts() -> (u32, u32) {
+    critical_section::with(|cs| (LOW.borrow(cs).get(), HIGH.borrow(cs).get()))
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut timer0 = timer_group0.timer0;
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut timer1 = timer_group1.timer0;
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    interrupt::enable(peripherals::Interrupt::TG0_T0_LEVEL, Priority::Priority1).unwrap();
+    timer0.start(10u64.millis());
+    timer0.listen();
+
+    interrupt::enable(peripherals::Interrupt::TG1_T0_LEVEL, Priority::Priority3).unwrap();
+    timer1.start(10u64.millis());
+    timer1.listen();
+
+    critical_section::with(|cs| {
+        TIMER0.borrow_ref_mut(cs).replace(timer0);
+        TIMER1.borrow_ref_mut(cs).replace(timer1);
+    });
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let (low, high) = counts();
+        {
+            let _guard = PriorityGuard::new(Cpu::ProCpu, Priority::Priority2);
+            delay.delay_ms(200u32);
+
+            let (masked_low, masked_high) = counts();
+            assert_eq!(masked_low, low);
+            assert!(masked_high > high);
+            println!(
+                "Masked: priority 1 ran {} times, priority 3 ran {} times",
+                masked_low - low,
+                masked_high - high
+            );
+        }
+
+        let (low, high) = counts();
+        delay.delay_ms(200u32);
+
+        let (unmasked_low, unmasked_high) = counts();
+        assert!(unmasked_low > low);
+        println!(
+            "Unmasked: priority 1 ran {} times, priority 3 ran {} times",
+            unmasked_low - low,
+            unmasked_high - high
+        );
+    }
+}
+
+#[interrupt]
+fn TG0_T0_LEVEL() {
+    critical_section::with(|cs| {
+        let mut timer0 = TIMER0.borrow_ref_mut(cs);
+        let timer0 = timer0.as_mut().unwrap();
+
+        timer0.clear_interrupt();
+        timer0.start(10u64.millis());
+
+        LOW.borrow(cs).set(LOW.borrow(cs).get() + 1);
+    });
+}
+
+#[interrupt]
+fn TG1_T0_LEVEL() {
+    critical_section::with(|cs| {
+        let mut timer1 = TIMER1.borrow_ref_mut(cs);
+        let timer1 = timer1.as_mut().unwrap();
+
+        timer1.clear_interrupt();
+        timer1.start(10u64.millis());
+
+        HIGH.borrow(cs).set(HIGH.borrow(cs).get() + 1);
+    });
+}
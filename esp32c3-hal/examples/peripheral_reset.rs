@@ -0,0 +1,74 @@
+//! Resets a configured peripheral to its power-on defaults
+//!
+//! Configures I2C0 for 100 kHz, which writes the SCL timing registers, then
+//! resets the peripheral and checks that the low period register went back to
+//! its reset value of 0.
+//!
+//! No wiring is needed, the pins are only configured.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::I2C,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    system::Peripheral,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+fn scl_low_period() -> u16 {
+    let i2c0 = unsafe { &*peripherals::I2C0::PTR };
+    i2c0.scl_low_period.read().scl_low_period().bits()
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let i2c = I2C::new(
+        peripherals.I2C0,
+        io.pins.gpio1,
+        io.pins.gpio2,
+        100u32.kHz(),
+        &mut system.peripheral_clock_control,
+        &clocks,
+    );
+
+    let configured = scl_low_period();
+    println!("SCL low period after configuration: {}", configured);
+    assert_ne!(configured, 0);
+
+    // the driver would have to configure the peripheral again, get rid of it
+    drop(i2c);
+    system.peripheral_clock_control.reset(Peripheral::I2cExt0);
+
+    let reset = scl_low_period();
+    println!("SCL low period after reset: {}", reset);
+    assert_eq!(reset, 0);
+
+    println!("Peripheral reset to its defaults");
+
+    loop {}
+}
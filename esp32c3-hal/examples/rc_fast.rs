@@ -0,0 +1,58 @@
+//! Measures the internal RC_FAST oscillator against the XTAL
+//!
+//! Afterwards RTC_SLOW_CLK is switched to RC_FAST / 256 and the RTC watchdog,
+//! which now runs from it, is fed for a while before it's allowed to reset the
+//! chip.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::RtcSlowClock,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    rtc.enable_rc_fast(true);
+    println!("RC_FAST runs at {} Hz", rtc.rc_fast_frequency().raw());
+
+    rtc.set_slow_clock(RtcSlowClock::RtcSlowClock8mD256)
+        .unwrap();
+    rtc.rwdt.start(2000u64.millis());
+
+    let mut delay = Delay::new(&clocks);
+
+    for _ in 0..5 {
+        delay.delay_ms(1000u32);
+        rtc.rwdt.feed();
+        println!("Fed the RTC watchdog");
+    }
+
+    println!("Waiting for the RTC watchdog to reset the chip");
+    loop {}
+}
@@ -0,0 +1,60 @@
+//! Reports why the chip was reset
+//!
+//! Prints the reason of the last reset, then lets the TIMG0 watchdog reset the
+//! chip after 3 seconds, so the next boot reports `CoreMwdt0`. Press the reset
+//! button or power cycle the board to see the other reasons.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::SocResetReason,
+    timer::TimerGroup,
+    Cpu,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    match rtc.reset_reason(Cpu::ProCpu) {
+        Some(SocResetReason::ChipPowerOn) => println!("Power on, or the reset button"),
+        Some(SocResetReason::SysBrownOut) => println!("Brownout, check the supply"),
+        Some(SocResetReason::CoreMwdt0 | SocResetReason::Cpu0Mwdt0) => {
+            println!("TIMG0 watchdog")
+        }
+        Some(SocResetReason::CoreRtcWdt | SocResetReason::SysRtcWdt) => {
+            println!("RTC watchdog")
+        }
+        Some(SocResetReason::CoreDeepSleep) => {
+            println!("Woken from deep sleep by {:?}", rtc.wakeup_cause())
+        }
+        Some(reason) => println!("{:?}", reason),
+        None => println!("Unknown reset reason"),
+    }
+
+    println!("Resetting in 3 s...");
+    wdt0.start(3u64.secs());
+
+    loop {}
+}
@@ -0,0 +1,103 @@
+//! Selects the RMT channel divider from a tick length
+//!
+//! Requests 100 ns per tick, which is what WS2812 timings are usually given
+//! in, and checks the divider chosen for the 80 MHz APB clock. A resolution
+//! that isn't a whole number of clock cycles gets the nearest divider. The
+//! channel then sends 300 ns high / 900 ns low pulses on GPIO4 forever.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    pulse_control::{
+        divider_for_resolution,
+        ClockSource,
+        ConfiguredChannel,
+        OutputChannel,
+        PulseCode,
+        RepeatMode,
+    },
+    timer::TimerGroup,
+    PulseControl,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // The channels are clocked with the undivided APB clock
+    let pulse = PulseControl::new(
+        peripherals.RMT,
+        &mut system.peripheral_clock_control,
+        ClockSource::APB,
+        0,
+        0,
+        0,
+    )
+    .unwrap();
+
+    // 100 ns are 8 cycles of the 80 MHz APB clock
+    assert_eq!(
+        divider_for_resolution(80u32.MHz(), 100u32.nanos()),
+        (8, 10u32.MHz())
+    );
+    // 37 ns aren't a whole number of cycles, 3 cycles (37.5 ns) are nearest
+    assert_eq!(divider_for_resolution(80u32.MHz(), 37u32.nanos()).0, 3);
+    // the largest divider (256) gives 3.2 µs, anything longer is clamped to it
+    assert_eq!(divider_for_resolution(80u32.MHz(), 3200u32.nanos()).0, 256);
+    assert_eq!(divider_for_resolution(80u32.MHz(), 10u32.micros()).0, 256);
+
+    let mut rmt_channel0 = pulse.channel0;
+    let tick_frequency = rmt_channel0.set_resolution(clocks.apb_clock, 100u32.nanos());
+    rmt_channel0
+        .set_idle_output_level(false)
+        .set_carrier_modulation(false)
+        .set_idle_output(true);
+    println!("Tick frequency: {} Hz", tick_frequency.raw());
+
+    let mut rmt_channel0 = rmt_channel0.assign_pin(io.pins.gpio4);
+
+    // lengths are in ticks of 100 ns
+    let seq = [
+        PulseCode {
+            level1: true,
+            length1: 3u32.nanos(),
+            level2: false,
+            length2: 9u32.nanos(),
+        },
+        PulseCode {
+            level1: false,
+            length1: 0u32.nanos(),
+            level2: false,
+            length2: 0u32.nanos(),
+        },
+    ];
+    rmt_channel0
+        .send_pulse_sequence(RepeatMode::Forever, &seq)
+        .unwrap();
+
+    loop {}
+}
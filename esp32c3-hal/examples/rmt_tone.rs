@@ -0,0 +1,90 @@
+//! Plays a short melody on a passive buzzer connected to GPIO4 using the RMT
+//! tone generator.
+//!
+//! The achieved frequency of every note is printed.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    pulse_control::{ClockSource, OutputChannel, ToneGenerator},
+    timer::TimerGroup,
+    Delay,
+    PulseControl,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // Configure RMT peripheral globally, the channels are clocked with the
+    // undivided APB clock
+    let pulse = PulseControl::new(
+        peripherals.RMT,
+        &mut system.peripheral_clock_control,
+        ClockSource::APB,
+        0,
+        0,
+        0,
+    )
+    .unwrap();
+
+    let mut rmt_channel0 = pulse.channel0;
+    rmt_channel0
+        .set_idle_output_level(false)
+        .set_carrier_modulation(false)
+        .set_idle_output(true);
+
+    let mut buzzer = ToneGenerator::new(
+        rmt_channel0.assign_pin(io.pins.gpio4),
+        clocks.apb_clock,
+        440u32.Hz(),
+    )
+    .unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    // C5, E5, G5, C6
+    let notes = [523u32, 659, 784, 1047];
+
+    loop {
+        for note in notes {
+            let achieved = buzzer.set_frequency(note.Hz()).unwrap();
+            println!(
+                "{} Hz -> {} Hz (divider {})",
+                note,
+                achieved.raw(),
+                buzzer.divider()
+            );
+
+            buzzer.play(200_000u32.micros()).unwrap();
+            delay.delay_ms(50u32);
+        }
+
+        delay.delay_ms(1000u32);
+    }
+}
@@ -0,0 +1,61 @@
+//! Keeps an output pin latched through a reset using the RTC pad hold
+//!
+//! GPIO5 stands in for the power-enable line of an external load. On the first
+//! boot it is driven high and held, then the TIMG0 watchdog is used to reset
+//! the digital core (the same happens when waking up from deep sleep). The RTC
+//! domain is not reset, so the pin stays high the whole time. After the
+//! reset, the pin is reconfigured to the same level and the hold is released.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut load_enable = io.pins.gpio5.into_push_pull_output();
+
+    // The hold survives the reset, so it tells us whether this is the first boot
+    if rtc.is_held(&load_enable) {
+        // Restore the level the pin was held at before releasing the hold, so
+        // there is no glitch on the load
+        load_enable.set_high().unwrap();
+        rtc.release_hold(&mut load_enable);
+        println!("Load stayed powered, hold released");
+
+        loop {}
+    }
+
+    load_enable.set_high().unwrap();
+    rtc.hold_output(&mut load_enable);
+    println!("Load powered and held, resetting...");
+
+    wdt0.start(1u64.secs());
+
+    loop {}
+}
@@ -0,0 +1,67 @@
+//! Checks for pins left latched by the RTC pad hold at boot
+//!
+//! On the first boot GPIO2 and GPIO3 are driven high and held, then the TIMG0
+//! watchdog resets the digital core. After the reset the held pins are queried
+//! before touching any GPIO, and all holds are released so the pins can be
+//! used normally again.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let held = rtc.held_pins();
+    if held != 0 {
+        for gpio in 0..32 {
+            if held & (1 << gpio) != 0 {
+                println!("GPIO{} is still held", gpio);
+            }
+        }
+
+        rtc.clear_all_holds();
+        assert_eq!(rtc.held_pins(), 0);
+        println!("All holds released");
+
+        loop {}
+    }
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut led1 = io.pins.gpio2.into_push_pull_output();
+    let mut led2 = io.pins.gpio3.into_push_pull_output();
+
+    led1.set_high().unwrap();
+    led2.set_high().unwrap();
+    rtc.hold_output(&mut led1);
+    rtc.hold_output(&mut led2);
+    println!("Holding GPIO2 and GPIO3, resetting...");
+
+    wdt0.start(1u64.secs());
+
+    loop {}
+}
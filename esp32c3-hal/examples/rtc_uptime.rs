@@ -0,0 +1,56 @@
+//! Shows that the RTC uptime keeps counting across resets
+//!
+//! The uptime since power-on is printed every second. After 5 seconds the
+//! TIMG0 watchdog resets the chip, the uptime printed after the reset continues
+//! from where it was instead of starting over. Only a power cycle resets it.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::get_reset_reason,
+    timer::TimerGroup,
+    Cpu,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    println!(
+        "Reset reason: {:?}, uptime {} ms",
+        get_reset_reason(Cpu::ProCpu),
+        rtc.uptime_since_power_on().to_millis()
+    );
+
+    let mut delay = Delay::new(&clocks);
+    for _ in 0..5 {
+        delay.delay_ms(1000u32);
+        println!("Uptime {} ms", rtc.uptime_since_power_on().to_millis());
+    }
+
+    println!("Resetting...");
+    wdt0.start(100u64.millis());
+
+    loop {}
+}
@@ -0,0 +1,55 @@
+//! Shows the baud rates the UART divider actually generates
+//!
+//! UART1 is configured for a few common rates and the achieved rate and its
+//! error are printed. Thanks to the fractional part of the divider the error
+//! stays well below 0.5% even at high rates.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+    Uart,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut serial1 = Uart::new(peripherals.UART1);
+
+    for requested in [9600, 115200, 921600, 3_000_000] {
+        serial1.change_baud(requested, &clocks);
+
+        let achieved = serial1.baudrate(&clocks);
+        let error = serial1.baudrate_error(requested, &clocks);
+        println!(
+            "requested {} baud, achieved {} baud ({:.3}%)",
+            requested, achieved, error
+        );
+
+        assert!(error > -0.5 && error < 0.5);
+    }
+
+    loop {}
+}
@@ -5,7 +5,7 @@
 #![no_std]
 #![no_main]
 
-use core::{cell::RefCell, fmt::Write};
+use core::cell::RefCell;
 
 use critical_section::Mutex;
 use esp32c3_hal::{
@@ -21,7 +21,6 @@ use esp32c3_hal::{
     Uart,
 };
 use esp_backtrace as _;
-use nb::block;
 
 static SERIAL: Mutex<RefCell<Option<Uart<UART0>>>> = Mutex::new(RefCell::new(None));
 
@@ -0,0 +1,135 @@
+//! Addresses nodes on a multidrop bus with the 9th bit
+//!
+//! This needs at least two boards: flash one with `NODE_ADDRESS` set to `None`
+//! to make it the master, and the others with the address they should respond
+//! to.
+//!
+//! - The master sends a message to each of the nodes 1 to 3 in turn, every
+//!   second. The message starts with the address of the node, sent with the 9th
+//!   bit set, followed by the data bytes with the 9th bit clear.
+//! - A node filters for its own address, the UART1 interrupt handler only gets
+//!   and prints the data that follows it. The filter runs in software, the
+//!   interrupt is still raised for every byte on the bus.
+//!
+//! The 9th bit is carried by the parity bit, the UART runs at 9600 baud with 8
+//! data bits and even parity.
+//!
+//! The following wiring is assumed, on all boards:
+//! - TX => GPIO1
+//! - RX => GPIO2
+//! - GND connected between the boards
+//!
+//! The TX of the master is connected to the RX of all nodes. On a real RS-485
+//! bus each board needs a transceiver instead, with the driver only enabled
+//! while sending.
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{Peripherals, UART1},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    uart::{
+        config::{Config, DataBits, Parity, StopBits},
+        TxRxPins,
+    },
+    Delay,
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const NODE_ADDRESS: Option<u8> = None;
+
+static SERIAL: Mutex<RefCell<Option<Uart<UART1>>>> = Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let config = Config {
+        baudrate: 9600,
+        data_bits: DataBits::DataBits8,
+        parity: Parity::ParityEven,
+        stop_bits: StopBits::STOP1,
+    };
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let mut serial1 = Uart::new_with_config(peripherals.UART1, Some(config), Some(pins), &clocks);
+
+    if NODE_ADDRESS.is_none() {
+        let mut delay = Delay::new(&clocks);
+        let mut counter = 0u8;
+
+        loop {
+            for node in 1..=3 {
+                serial1.write_with_ninth_bit(node, true).unwrap();
+                for byte in [b'#', counter] {
+                    serial1.write_with_ninth_bit(byte, false).unwrap();
+                }
+                println!("Sent {} to node {}", counter, node);
+            }
+
+            counter = counter.wrapping_add(1);
+            delay.delay_ms(1000u32);
+        }
+    }
+
+    // the 9th bit of a byte is only known if it is read before the next one
+    // arrives
+    serial1.set_rx_fifo_full_threshold(1);
+    serial1.listen_rx_fifo_full();
+    serial1.set_address_filter(NODE_ADDRESS);
+
+    interrupt::enable(serial1.interrupt(), interrupt::Priority::Priority1).unwrap();
+
+    critical_section::with(|cs| SERIAL.borrow_ref_mut(cs).replace(serial1));
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    loop {}
+}
+
+#[interrupt]
+fn UART1() {
+    critical_section::with(|cs| {
+        let mut serial = SERIAL.borrow_ref_mut(cs);
+        let serial = serial.as_mut().unwrap();
+
+        while let Ok(byte) = serial.read_addressed() {
+            println!("Received {:02x}", byte);
+        }
+
+        serial.reset_rx_fifo_full_interrupt();
+    });
+}
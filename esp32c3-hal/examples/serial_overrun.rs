@@ -0,0 +1,112 @@
+//! Detects data lost to RX FIFO overruns
+//!
+//! UART1 sends bursts to itself without reading in between, the overrun count
+//! shows when the 128 byte RX FIFO couldn't take all of a burst.
+//!
+//! The following wiring is assumed:
+//! - TX => GPIO1
+//! - RX => GPIO2
+//! - GPIO1 connected to GPIO2
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::{Peripherals, UART1},
+    prelude::*,
+    timer::TimerGroup,
+    uart::TxRxPins,
+    Delay,
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+fn send_burst(serial: &mut Uart<UART1>, delay: &mut Delay, len: usize) {
+    for i in 0..len {
+        block!(serial.write(i as u8)).unwrap();
+    }
+    block!(serial.flush()).unwrap();
+
+    // the last byte is still being received once the TX is done
+    delay.delay_ms(1u32);
+}
+
+fn drain(serial: &mut Uart<UART1>) -> usize {
+    let mut received = 0;
+    while serial.read().is_ok() {
+        received += 1;
+    }
+    received
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let mut serial1 = Uart::new_with_config(peripherals.UART1, None, Some(pins), &clocks);
+    let mut delay = Delay::new(&clocks);
+
+    // exactly fills the FIFO, nothing is lost
+    send_burst(&mut serial1, &mut delay, 128);
+    let received = drain(&mut serial1);
+    println!(
+        "128 sent, {} received, {} overruns",
+        received,
+        serial1.overrun_count()
+    );
+    assert_eq!(received, 128);
+    assert_eq!(serial1.overrun_count(), 0);
+
+    // 72 bytes dropped, counted as one overrun as they were lost between two reads
+    send_burst(&mut serial1, &mut delay, 200);
+    let received = drain(&mut serial1);
+    println!(
+        "200 sent, {} received, {} overruns",
+        received,
+        serial1.overrun_count()
+    );
+    assert_eq!(received, 128);
+    assert_eq!(serial1.overrun_count(), 1);
+
+    // a single byte too many is one more overrun
+    send_burst(&mut serial1, &mut delay, 129);
+    let received = drain(&mut serial1);
+    println!(
+        "129 sent, {} received, {} overruns",
+        received,
+        serial1.overrun_count()
+    );
+    assert_eq!(received, 128);
+    assert_eq!(serial1.overrun_count(), 2);
+
+    serial1.reset_overrun_count();
+    assert_eq!(serial1.overrun_count(), 0);
+
+    println!("Overruns counted as expected");
+
+    loop {}
+}
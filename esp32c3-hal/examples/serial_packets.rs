@@ -0,0 +1,80 @@
+//! Receives length-prefixed command packets on UART1
+//!
+//! Every packet is a single length byte followed by that many bytes of
+//! payload, the first payload byte being the command. Packets longer than the
+//! 64 byte buffer are rejected and a pause of more than 10 ms within a packet
+//! drops it, so the receiver gets back in sync after a lost byte.
+//!
+//! The following wiring is assumed:
+//! - TX => GPIO1
+//! - RX => GPIO2
+//!
+//! A packet can be sent from a host with e.g.
+//! `printf '\x03\x01\x02\x03' > /dev/ttyUSB0`
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    uart::{
+        config::Config,
+        packet::{Error, LengthPrefix, PacketReader},
+        TxRxPins,
+    },
+    Rtc,
+    Uart,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+    let mut timer = timer_group1.timer0;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let mut serial1 = Uart::new_with_config(
+        peripherals.UART1,
+        Some(Config::default().baudrate(115200)),
+        Some(pins),
+        &clocks,
+    );
+
+    let mut buffer = [0u8; 64];
+    let mut reader = PacketReader::new(&mut buffer, LengthPrefix::U8, 10u64.millis());
+
+    loop {
+        match reader.read_packet(&mut serial1, &mut timer) {
+            Ok([]) => println!("Empty packet"),
+            Ok([command, args @ ..]) => println!("Command {:02x}: {:02x?}", command, args),
+            Err(Error::Timeout { received }) => {
+                println!("Packet dropped after {} bytes", received)
+            }
+            Err(Error::TooLong { length }) => println!("Packet of {} bytes rejected", length),
+        }
+    }
+}
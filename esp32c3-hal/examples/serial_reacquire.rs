@@ -0,0 +1,73 @@
+//! Constructs and releases a UART driver repeatedly
+//!
+//! UART1, GPIO1 and GPIO2 are only borrowed by the driver, so after
+//! `Uart::free` they are available again for the next round. Each round uses a
+//! different baud rate and checks that a byte sent on TX comes back on RX.
+//!
+//! The following wiring is assumed:
+//! - TX (GPIO1) => RX (GPIO2)
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    uart::{config::Config, TxRxPins},
+    Delay,
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut delay = Delay::new(&clocks);
+
+    for (round, baudrate) in [9600, 115200, 921600].into_iter().cycle().enumerate() {
+        let pins = TxRxPins::new_tx_rx(&mut io.pins.gpio1, &mut io.pins.gpio2);
+        let mut serial1 = Uart::new_with_config(
+            &mut peripherals.UART1,
+            Some(Config::default().baudrate(baudrate)),
+            Some(pins),
+            &clocks,
+        );
+
+        let byte = round as u8;
+        block!(serial1.write(byte)).unwrap();
+        let received = block!(serial1.read()).unwrap();
+
+        println!(
+            "round {} at {} baud: sent {}, received {}",
+            round, baudrate, byte, received
+        );
+        assert_eq!(byte, received);
+
+        serial1.free();
+
+        delay.delay_ms(500u32);
+    }
+
+    unreachable!()
+}
@@ -0,0 +1,171 @@
+//! Receives parity errors and breaks inline with the data
+//!
+//! UART1 receives at 9600 baud with even parity. The frames are bit-banged on
+//! GPIO4, so the parity bit of one of them can be flipped on purpose and a
+//! break can be sent. The UART interrupt reads every byte with its status, it
+//! is checked that exactly the corrupted byte and the break are flagged.
+//!
+//! The following wiring is assumed:
+//! - GPIO4 => GPIO2 (RX of UART1)
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{Gpio4, Output, PushPull},
+    interrupt,
+    peripherals::{self, Peripherals, UART1},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    uart::{
+        config::{Config, DataBits, Parity, StopBits},
+        RxStatus,
+        TxRxPins,
+    },
+    Delay,
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const BIT_US: u32 = 104;
+
+static SERIAL: Mutex<RefCell<Option<Uart<UART1>>>> = Mutex::new(RefCell::new(None));
+static RECEIVED: Mutex<RefCell<([(u8, RxStatus); 8], usize)>> =
+    Mutex::new(RefCell::new(([(0, RxStatus::default()); 8], 0)));
+
+// Sends a 8E1 frame, with the wrong parity bit if `corrupt` is set
+fn send_frame(pin: &mut Gpio4<Output<PushPull>>, delay: &Delay, byte: u8, corrupt: bool) {
+    let parity = (byte.count_ones() % 2 == 1) != corrupt;
+
+    // keep the bit timing free of interrupts
+    critical_section::with(|_| {
+        pin.set_low().unwrap();
+        delay.delay(BIT_US);
+        for bit in 0..8 {
+            pin.set_state((byte & (1 << bit) != 0).into()).unwrap();
+            delay.delay(BIT_US);
+        }
+        pin.set_state(parity.into()).unwrap();
+        delay.delay(BIT_US);
+        pin.set_high().unwrap();
+        delay.delay(BIT_US);
+    });
+
+    // leave time for the interrupt to read the byte
+    delay.delay(2 * BIT_US);
+}
+
+fn send_break(pin: &mut Gpio4<Output<PushPull>>, delay: &Delay) {
+    pin.set_low().unwrap();
+    delay.delay(30 * BIT_US);
+    pin.set_high().unwrap();
+    delay.delay(2 * BIT_US);
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let config = Config {
+        baudrate: 9600,
+        data_bits: DataBits::DataBits8,
+        parity: Parity::ParityEven,
+        stop_bits: StopBits::STOP1,
+    };
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    // TX isn't used
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let mut serial1 = Uart::new_with_config(peripherals.UART1, Some(config), Some(pins), &clocks);
+    serial1.set_rx_fifo_full_threshold(1);
+    serial1.listen_rx_fifo_full();
+
+    let mut line = io.pins.gpio4.into_push_pull_output();
+    line.set_high().unwrap();
+
+    let delay = Delay::new(&clocks);
+    delay.delay(10 * BIT_US);
+
+    // drop anything caused by the line going idle
+    while serial1.read_with_status().is_ok() {}
+
+    critical_section::with(|cs| SERIAL.borrow_ref_mut(cs).replace(serial1));
+
+    interrupt::enable(
+        peripherals::Interrupt::UART1,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    send_frame(&mut line, &delay, 0x41, false);
+    send_frame(&mut line, &delay, 0x42, true);
+    send_frame(&mut line, &delay, 0x43, false);
+    send_break(&mut line, &delay);
+    send_frame(&mut line, &delay, 0x44, false);
+
+    let (received, count) = critical_section::with(|cs| *RECEIVED.borrow_ref(cs));
+    for (byte, status) in &received[..count] {
+        println!("{:02x} {:?}", byte, status);
+    }
+
+    assert_eq!(count, 5);
+    assert_eq!(received[0], (0x41, RxStatus::default()));
+    assert!(received[1].1.parity_error);
+    assert!(!received[1].1.break_detected);
+    assert_eq!(received[2], (0x43, RxStatus::default()));
+    assert_eq!(received[3].0, 0x00);
+    assert!(received[3].1.break_detected);
+    assert_eq!(received[4], (0x44, RxStatus::default()));
+
+    println!("Only the corrupted byte and the break were flagged");
+
+    loop {}
+}
+
+#[interrupt]
+fn UART1() {
+    critical_section::with(|cs| {
+        let mut serial = SERIAL.borrow_ref_mut(cs);
+        let serial = serial.as_mut().unwrap();
+        let mut received = RECEIVED.borrow_ref_mut(cs);
+        let (buffer, count) = &mut *received;
+
+        while let Ok(entry) = serial.read_with_status() {
+            if *count < buffer.len() {
+                buffer[*count] = entry;
+                *count += 1;
+            }
+        }
+
+        serial.reset_rx_fifo_full_interrupt();
+    });
+}
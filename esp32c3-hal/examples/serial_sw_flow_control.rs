@@ -0,0 +1,74 @@
+//! Text transfer with software (XON/XOFF) flow control
+//!
+//! Connect a USB-serial adapter to GPIO1 (TX) and GPIO2 (RX) and open it in a
+//! terminal with XON/XOFF flow control enabled (e.g. `picocom -f s -b 115200`).
+//! Paste a long text: every received line is echoed back in upper case, but
+//! processing each line takes a while, so the UART sends XOFF to pause the
+//! terminal whenever the RX-FIFO fills up and XON once it has drained.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    uart::{config::Config, TxRxPins},
+    Delay,
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let mut serial1 = Uart::new_with_config(
+        peripherals.UART1,
+        Some(Config::default().baudrate(115200)),
+        Some(pins),
+        &clocks,
+    );
+
+    // The FIFO holds at most 128 bytes, so XOFF would never be sent
+    assert!(serial1.enable_sw_flow_control(32, 128).is_err());
+
+    // Pause the terminal at 96 bytes, leaving room for the bytes it sends before
+    // reacting, and let it continue once fewer than 32 bytes are left
+    serial1.enable_sw_flow_control(32, 96).unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let byte = block!(serial1.read()).unwrap();
+        block!(serial1.write(byte.to_ascii_uppercase())).unwrap();
+
+        if byte == b'\n' {
+            // simulate slow processing of every line
+            delay.delay_ms(100u32);
+        }
+    }
+}
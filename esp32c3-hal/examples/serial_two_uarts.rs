@@ -0,0 +1,107 @@
+//! Uses two UARTs side by side
+//!
+//! UART0 is the console, UART1 is connected to GPIO1 (TX) and GPIO2 (RX).
+//! Everything received on UART1 is forwarded to the console from the UART1
+//! interrupt handler. Both drivers are stored in statics, their types tell the
+//! instances apart.
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{Peripherals, UART0, UART1},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    uart::{config::Config, TxRxPins},
+    Rtc,
+    Uart,
+    IO,
+};
+use esp_backtrace as _;
+
+static SERIAL0: Mutex<RefCell<Option<Uart<UART0>>>> = Mutex::new(RefCell::new(None));
+static SERIAL1: Mutex<RefCell<Option<Uart<UART1>>>> = Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut timer0 = timer_group0.timer0;
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let serial0 = Uart::new(peripherals.UART0);
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let mut serial1 = Uart::new_with_config(
+        peripherals.UART1,
+        Some(Config::default().baudrate(115200)),
+        Some(pins),
+        &clocks,
+    );
+    serial1.set_rx_fifo_full_threshold(1);
+    serial1.listen_rx_fifo_full();
+
+    interrupt::enable(serial1.interrupt(), interrupt::Priority::Priority1).unwrap();
+
+    critical_section::with(|cs| {
+        SERIAL0.borrow_ref_mut(cs).replace(serial0);
+        SERIAL1.borrow_ref_mut(cs).replace(serial1);
+    });
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    timer0.start(1u64.secs());
+
+    loop {
+        critical_section::with(|cs| {
+            writeln!(
+                SERIAL1.borrow_ref_mut(cs).as_mut().unwrap(),
+                "Hello from UART1, whatever you type here shows up on UART0"
+            )
+            .ok();
+        });
+
+        block!(timer0.wait()).unwrap();
+    }
+}
+
+#[interrupt]
+fn UART1() {
+    critical_section::with(|cs| {
+        let mut serial0 = SERIAL0.borrow_ref_mut(cs);
+        let serial0 = serial0.as_mut().unwrap();
+        let mut serial1 = SERIAL1.borrow_ref_mut(cs);
+        let serial1 = serial1.as_mut().unwrap();
+
+        while let nb::Result::Ok(byte) = serial1.read() {
+            block!(serial0.write(byte)).ok();
+        }
+
+        serial1.reset_rx_fifo_full_interrupt();
+    });
+}
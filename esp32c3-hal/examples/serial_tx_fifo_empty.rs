@@ -0,0 +1,122 @@
+//! Interrupt-driven transmission of a large buffer using the TX-FIFO-EMPTY
+//! interrupt.
+//!
+//! The interrupt handler refills the TX-FIFO in bulk every time it drops below
+//! the configured threshold, and counts how often it had to do so. With a
+//! threshold of 16 bytes, sending 4 KiB takes roughly 4096 / (128 - 16) = 37
+//! interrupts instead of one per byte.
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{self, Peripherals, UART0},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+    Uart,
+};
+use esp_backtrace as _;
+
+const TX_THRESHOLD: u16 = 16;
+const BUFFER: [u8; 4096] = [b'*'; 4096];
+
+static SERIAL: Mutex<RefCell<Option<Uart<UART0>>>> = Mutex::new(RefCell::new(None));
+static SENT: Mutex<RefCell<usize>> = Mutex::new(RefCell::new(0));
+static INTERRUPTS: Mutex<RefCell<usize>> = Mutex::new(RefCell::new(0));
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let mut serial0 = Uart::new(peripherals.UART0);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    serial0.set_tx_fifo_empty_threshold(TX_THRESHOLD).unwrap();
+
+    critical_section::with(|cs| SERIAL.borrow_ref_mut(cs).replace(serial0));
+
+    interrupt::enable(
+        peripherals::Interrupt::UART0,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        critical_section::with(|cs| {
+            *SENT.borrow_ref_mut(cs) = 0;
+            *INTERRUPTS.borrow_ref_mut(cs) = 0;
+            SERIAL
+                .borrow_ref_mut(cs)
+                .as_mut()
+                .unwrap()
+                .listen_tx_fifo_empty();
+        });
+
+        // wait for the interrupt handler to send the whole buffer
+        while critical_section::with(|cs| *SENT.borrow_ref(cs)) < BUFFER.len() {}
+
+        critical_section::with(|cs| {
+            let mut serial = SERIAL.borrow_ref_mut(cs);
+            let serial = serial.as_mut().unwrap();
+            writeln!(
+                serial,
+                "\r\nSent {} bytes using {} TX-FIFO-EMPTY interrupts",
+                BUFFER.len(),
+                INTERRUPTS.borrow_ref(cs)
+            )
+            .ok();
+        });
+
+        delay.delay_ms(1000u32);
+    }
+}
+
+#[interrupt]
+fn UART0() {
+    critical_section::with(|cs| {
+        let mut serial = SERIAL.borrow_ref_mut(cs);
+        let serial = serial.as_mut().unwrap();
+        let mut sent = SENT.borrow_ref_mut(cs);
+
+        *INTERRUPTS.borrow_ref_mut(cs) += 1;
+
+        while *sent < BUFFER.len() {
+            match serial.write(BUFFER[*sent]) {
+                Ok(()) => *sent += 1,
+                Err(_) => break, // FIFO is full again
+            }
+        }
+
+        if *sent == BUFFER.len() {
+            serial.unlisten_tx_fifo_empty();
+        }
+
+        serial.reset_tx_fifo_empty_interrupt();
+    });
+}
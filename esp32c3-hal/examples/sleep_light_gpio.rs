@@ -0,0 +1,77 @@
+//! Light sleep with several GPIO wakeup sources
+//!
+//! Sleeps until one of the buttons is pressed, then prints which GPIO woke the
+//! chip. Any GPIO can wake the chip from light sleep, not just the RTC GPIOs
+//! usable in deep sleep.
+//!
+//! The following wiring is assumed:
+//! - Buttons connecting GPIO2, GPIO3 and GPIO9 (the BOOT button on most boards)
+//!   to GND
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{Event, Pin, IO},
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::{get_gpio_wakeup_status, WakeupSource},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut button2 = io.pins.gpio2.into_pull_up_input();
+    let mut button3 = io.pins.gpio3.into_pull_up_input();
+    let mut button9 = io.pins.gpio9.into_pull_up_input();
+
+    // only the wakeup is enabled, not the CPU interrupt
+    button2.listen_with_options(Event::LowLevel, false, false, true);
+    button3.listen_with_options(Event::LowLevel, false, false, true);
+    button9.listen_with_options(Event::LowLevel, false, false, true);
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        println!("Going to sleep");
+        rtc.sleep_light(&[WakeupSource::Gpio]).unwrap();
+
+        let status = get_gpio_wakeup_status();
+        for gpio in 0..22 {
+            if status & (1 << gpio) != 0 {
+                println!("Woken up by GPIO{}", gpio);
+            }
+        }
+
+        // wait for the buttons to be released, a held level would end the next
+        // sleep right away
+        while button2.is_low().unwrap() || button3.is_low().unwrap() || button9.is_low().unwrap() {
+            delay.delay_ms(10u32);
+        }
+
+        button2.clear_interrupt();
+        button3.clear_interrupt();
+        button9.clear_interrupt();
+    }
+}
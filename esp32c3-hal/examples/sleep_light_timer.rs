@@ -0,0 +1,55 @@
+//! Light sleep between UART prints
+//!
+//! Prints a line every 2 seconds and light-sleeps in between, woken by the
+//! RTC timer. The current drawn while sleeping is a fraction of the one with
+//! the CPU busy-waiting, measure it in series with the supply of the board.
+//! The printed SYSTIMER time keeps advancing by 2 s per line although the
+//! counter may stop while sleeping, `sleep_light` accounts for the time slept.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::WakeupSource,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+    Uart,
+};
+use esp_backtrace as _;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let mut uart0 = Uart::new(peripherals.UART0);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    for line in 0u32.. {
+        let millis = SystemTimer::now() / (SystemTimer::TICKS_PER_SECOND / 1000);
+        writeln!(uart0, "Line {} at {} ms", line, millis).unwrap();
+
+        // the UART clock is gated while sleeping, let it finish sending first
+        block!(uart0.flush()).unwrap();
+
+        rtc.sleep_light(&[WakeupSource::Timer(2u64.secs())])
+            .unwrap();
+    }
+
+    unreachable!()
+}
@@ -0,0 +1,98 @@
+//! Sweeps a servo connected to GPIO4 back and forth using software PWM.
+//!
+//! The PWM signal is generated by the TIMG0 timer interrupt, so this works on
+//! any output pin, even when all LEDC channels are in use.
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{AnyPin, Output, PushPull, IO},
+    interrupt,
+    peripherals::{self, Peripherals, TIMG0},
+    prelude::*,
+    riscv,
+    soft_pwm::SoftPwm,
+    timer::{Timer0, TimerGroup},
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+
+static PWM: Mutex<RefCell<Option<SoftPwm<Timer0<TIMG0>, AnyPin<Output<PushPull>>, 1>>>> =
+    Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let timer0 = timer_group0.timer0;
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let servo = io.pins.gpio4.into_push_pull_output();
+
+    // Servos expect a pulse of 1 to 2 ms every 20 ms
+    let mut pwm = SoftPwm::new(timer0, [servo.degrade()], 20u64.millis());
+    pwm.set_pulse_width(0, 1500u64.micros()).unwrap();
+    pwm.start();
+
+    critical_section::with(|cs| PWM.borrow_ref_mut(cs).replace(pwm));
+
+    interrupt::enable(
+        peripherals::Interrupt::TG0_T0_LEVEL,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+    let mut width = 1000u64;
+    let mut step = 10i64;
+
+    loop {
+        critical_section::with(|cs| {
+            PWM.borrow_ref_mut(cs)
+                .as_mut()
+                .unwrap()
+                .set_pulse_width(0, width.micros())
+                .unwrap()
+        });
+
+        if width <= 1000 {
+            step = 10;
+        } else if width >= 2000 {
+            step = -10;
+        }
+        width = (width as i64 + step) as u64;
+
+        delay.delay_ms(20u32);
+    }
+}
+
+#[interrupt]
+fn TG0_T0_LEVEL() {
+    critical_section::with(|cs| {
+        PWM.borrow_ref_mut(cs).as_mut().unwrap().on_interrupt();
+    });
+}
@@ -0,0 +1,111 @@
+//! Streams samples from an external SPI ADC using a DMA ring buffer
+//!
+//! Folowing pins are used:
+//! SCLK    GPIO6
+//! MISO    GPIO2
+//! MOSI    GPIO7 (not used by the ADC, carries dummy data)
+//! CS      GPIO10
+//!
+//! Depending on your target and the board you are using you have to change the
+//! pins.
+//!
+//! The ADC is expected to shift out big endian 16 bit samples continuously
+//! while clocked. At 1 MHz this gives 62500 samples per second.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::DmaPriority,
+    gdma::Gdma,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    spi::{Spi, SpiMode},
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let sclk = io.pins.gpio6;
+    let miso = io.pins.gpio2;
+    let mosi = io.pins.gpio7;
+    let cs = io.pins.gpio10;
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let spi = Spi::new(
+        peripherals.SPI2,
+        sclk,
+        mosi,
+        miso,
+        cs,
+        1u32.MHz(),
+        SpiMode::Mode0,
+        &mut system.peripheral_clock_control,
+        &clocks,
+    )
+    .with_dma(dma_channel.configure(
+        false,
+        &mut descriptors,
+        &mut rx_descriptors,
+        DmaPriority::Priority0,
+    ));
+
+    // DMA buffer require a static life-time
+    let mut stream = spi.dma_read_circular(ring_buffer()).unwrap();
+
+    let mut chunk = [0u8; 4092];
+    let mut samples = 0u32;
+
+    loop {
+        let (len, overflow) = stream.read_stream(&mut chunk).unwrap();
+
+        if overflow {
+            println!("Overflow, samples got lost");
+        }
+
+        if len == 0 {
+            continue;
+        }
+
+        let first = u16::from_be_bytes([chunk[0], chunk[1]]);
+        samples += len as u32 / 2;
+
+        println!(
+            "{} samples received, latest chunk starts with {}",
+            samples, first
+        );
+    }
+}
+
+fn ring_buffer() -> &'static mut [u8; 16368] {
+    static mut BUFFER: [u8; 16368] = [0u8; 16368];
+    unsafe { &mut BUFFER }
+}
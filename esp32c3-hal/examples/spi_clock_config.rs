@@ -0,0 +1,105 @@
+//! SPI clock configuration
+//!
+//! Folowing pins are used:
+//! SCLK    GPIO6
+//! MISO    GPIO2
+//! MOSI    GPIO7
+//! CS      GPIO10
+//!
+//! Applies every standard SPI mode and checks the resulting `ck_idle_edge` and
+//! `ck_out_edge` bits, then configures SCLK directly to idle high and shift
+//! data out on the falling edge (equivalent to Mode3). Connect MISO and MOSI
+//! to see the outgoing data read back as incoming data.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::{Peripherals, SPI2},
+    prelude::*,
+    spi::{Spi, SpiClockConfig, SpiMode},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+fn clock_bits() -> (bool, bool) {
+    let spi = unsafe { &*SPI2::PTR };
+
+    (
+        spi.misc.read().ck_idle_edge().bit(),
+        spi.user.read().ck_out_edge().bit(),
+    )
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let sclk = io.pins.gpio6;
+    let miso = io.pins.gpio2;
+    let mosi = io.pins.gpio7;
+    let cs = io.pins.gpio10;
+
+    let mut spi = Spi::new(
+        peripherals.SPI2,
+        sclk,
+        mosi,
+        miso,
+        cs,
+        100u32.kHz(),
+        SpiMode::Mode0,
+        &mut system.peripheral_clock_control,
+        &clocks,
+    );
+
+    for (mode, expected) in [
+        (SpiMode::Mode0, (false, false)),
+        (SpiMode::Mode1, (false, true)),
+        (SpiMode::Mode2, (true, true)),
+        (SpiMode::Mode3, (true, false)),
+    ] {
+        spi.change_data_mode(mode);
+        assert_eq!(clock_bits(), expected);
+
+        let config = SpiClockConfig::from(mode);
+        assert_eq!((config.idle_high, config.out_on_rising_edge), expected);
+        println!("{:?}: {:?}", mode, config);
+    }
+
+    spi.set_clock_config(SpiClockConfig {
+        idle_high: true,
+        out_on_rising_edge: false,
+    });
+    assert_eq!(clock_bits(), (true, false));
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let mut data = [0xde, 0xca, 0xfb, 0xad];
+        spi.transfer(&mut data).unwrap();
+        println!("{:x?}", data);
+
+        delay.delay_ms(250u32);
+    }
+}
@@ -0,0 +1,160 @@
+//! Reads an external SPI flash in quad mode
+//!
+//! Folowing pins are used:
+//! SCLK        GPIO6
+//! SIO0 (DI)   GPIO7
+//! SIO1 (DO)   GPIO2
+//! SIO2 (WP)   GPIO5
+//! SIO3 (HD)   GPIO4
+//! CS          GPIO10
+//!
+//! Connect a quad SPI NOR flash (e.g. a W25Q32) to the pins, with pull-ups on
+//! WP and HD. The quad enable bit of the flash is set first, then the first
+//! 256 bytes are read twice: with the standard read command `0x03` on a single
+//! line, and with the "Fast Read Quad Output" command `0x6b`, which returns
+//! the data on all four lines. Both reads have to match.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::DmaPriority,
+    gdma::Gdma,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    spi::{
+        dma::{LineWidth, Transaction},
+        Spi,
+        SpiMode,
+    },
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const CMD_WRITE_ENABLE: u8 = 0x06;
+const CMD_READ_STATUS_1: u16 = 0x05;
+const CMD_WRITE_STATUS_2: u16 = 0x31;
+const CMD_READ_DATA: u16 = 0x03;
+const CMD_FAST_READ_QUAD_OUTPUT: u16 = 0x6b;
+
+// status register 1: write in progress, status register 2: quad enable
+const STATUS_BUSY: u8 = 1 << 0;
+const STATUS_QE: u8 = 1 << 1;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let sclk = io.pins.gpio6;
+    let sio0 = io.pins.gpio7;
+    let sio1 = io.pins.gpio2;
+    let sio2 = io.pins.gpio5;
+    let sio3 = io.pins.gpio4;
+    let cs = io.pins.gpio10;
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let mut spi = Spi::new_quad(
+        peripherals.SPI2,
+        sclk,
+        sio0,
+        sio1,
+        sio2,
+        sio3,
+        cs,
+        10u32.MHz(),
+        SpiMode::Mode0,
+        &mut system.peripheral_clock_control,
+        &clocks,
+    )
+    .with_dma(dma_channel.configure(
+        false,
+        &mut descriptors,
+        &mut rx_descriptors,
+        DmaPriority::Priority0,
+    ));
+
+    let mut delay = Delay::new(&clocks);
+
+    // DMA buffer require a static life-time
+    let mut status = status_buffer();
+    let mut single = data_buffer();
+    let mut quad = quad_buffer();
+
+    // the flash only uses WP and HD as data lines with quad enable set
+    spi.write(&[CMD_WRITE_ENABLE]).unwrap();
+    status[0] = STATUS_QE;
+    let write_status = Transaction::new().command(8, CMD_WRITE_STATUS_2);
+    let transfer = spi.dma_transaction_write(write_status, status).unwrap();
+    (status, spi) = transfer.wait();
+
+    loop {
+        let read_status = Transaction::new().command(8, CMD_READ_STATUS_1);
+        let transfer = spi.dma_transaction_read(read_status, status).unwrap();
+        (status, spi) = transfer.wait();
+
+        if status[0] & STATUS_BUSY == 0 {
+            break;
+        }
+    }
+
+    loop {
+        let read = Transaction::new()
+            .command(8, CMD_READ_DATA)
+            .address(24, 0x000000);
+        let transfer = spi.dma_transaction_read(read, single).unwrap();
+        (single, spi) = transfer.wait();
+
+        let read_quad = Transaction::new()
+            .command(8, CMD_FAST_READ_QUAD_OUTPUT)
+            .address(24, 0x000000)
+            .dummy_cycles(8)
+            .data_width(LineWidth::Quad);
+        let transfer = spi.dma_transaction_read(read_quad, quad).unwrap();
+        (quad, spi) = transfer.wait();
+
+        println!("{:02x?} .. {:02x?}", &quad[..16], &quad[quad.len() - 16..]);
+        assert_eq!(single, quad);
+        println!("Quad read matches the single line read");
+
+        delay.delay_ms(1000u32);
+    }
+}
+
+fn status_buffer() -> &'static mut [u8; 1] {
+    static mut BUFFER: [u8; 1] = [0u8; 1];
+    unsafe { &mut BUFFER }
+}
+
+fn data_buffer() -> &'static mut [u8; 256] {
+    static mut BUFFER: [u8; 256] = [0u8; 256];
+    unsafe { &mut BUFFER }
+}
+
+fn quad_buffer() -> &'static mut [u8; 256] {
+    static mut BUFFER: [u8; 256] = [0u8; 256];
+    unsafe { &mut BUFFER }
+}
@@ -0,0 +1,114 @@
+//! Reads an external SPI flash with single-transaction command sequences
+//!
+//! Folowing pins are used:
+//! SCLK    GPIO6
+//! MISO    GPIO2
+//! MOSI    GPIO7
+//! CS      GPIO10
+//!
+//! Connect a SPI NOR flash (e.g. a W25Q32) to the pins. The JEDEC ID is read
+//! with the command `0x9f`, then the first 256 bytes with the read command
+//! `0x03` followed by a 24 bit address. Each is a single SPI transaction: the
+//! command and address phases are followed by the DMA-driven data phase
+//! without any CPU involvement in between.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::DmaPriority,
+    gdma::Gdma,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    spi::{dma::Transaction, Spi, SpiMode},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const CMD_READ_JEDEC_ID: u16 = 0x9f;
+const CMD_READ_DATA: u16 = 0x03;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let sclk = io.pins.gpio6;
+    let miso = io.pins.gpio2;
+    let mosi = io.pins.gpio7;
+    let cs = io.pins.gpio10;
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let mut spi = Spi::new(
+        peripherals.SPI2,
+        sclk,
+        mosi,
+        miso,
+        cs,
+        1u32.MHz(),
+        SpiMode::Mode0,
+        &mut system.peripheral_clock_control,
+        &clocks,
+    )
+    .with_dma(dma_channel.configure(
+        false,
+        &mut descriptors,
+        &mut rx_descriptors,
+        DmaPriority::Priority0,
+    ));
+
+    let mut delay = Delay::new(&clocks);
+
+    // DMA buffer require a static life-time
+    let mut jedec_id = id_buffer();
+    let mut data = data_buffer();
+
+    loop {
+        let read_id = Transaction::new().command(8, CMD_READ_JEDEC_ID);
+        let transfer = spi.dma_transaction_read(read_id, jedec_id).unwrap();
+        (jedec_id, spi) = transfer.wait();
+        println!("JEDEC ID: {:02x?}", jedec_id);
+
+        let read = Transaction::new()
+            .command(8, CMD_READ_DATA)
+            .address(24, 0x000000);
+        let transfer = spi.dma_transaction_read(read, data).unwrap();
+        (data, spi) = transfer.wait();
+        println!("{:02x?} .. {:02x?}", &data[..16], &data[data.len() - 16..]);
+
+        delay.delay_ms(1000u32);
+    }
+}
+
+fn id_buffer() -> &'static mut [u8; 3] {
+    static mut BUFFER: [u8; 3] = [0u8; 3];
+    unsafe { &mut BUFFER }
+}
+
+fn data_buffer() -> &'static mut [u8; 256] {
+    static mut BUFFER: [u8; 256] = [0u8; 256];
+    unsafe { &mut BUFFER }
+}
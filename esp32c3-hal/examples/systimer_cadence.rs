@@ -0,0 +1,73 @@
+//! Runs a loop at a fixed 10 ms cadence with `SystemTimer::wait_until`
+//!
+//! Every iteration does a varying amount of work and then waits for the next
+//! 10 ms boundary. Every 100 iterations the accumulated drift against the ideal
+//! schedule and the latest wake-up are printed, both should stay in the order
+//! of a few microseconds.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const TICKS_PER_US: u64 = SystemTimer::TICKS_PER_SECOND / 1_000_000;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut delay = Delay::new(&clocks);
+
+    let start = SystemTimer::now_instant();
+    let mut next = start;
+    let mut max_late = 0;
+    let mut iteration = 0u64;
+
+    loop {
+        iteration += 1;
+
+        // simulate work taking between 0 and 8.1 ms
+        delay.delay_us((iteration % 10) as u32 * 900);
+
+        next += 10u64.millis();
+        SystemTimer::wait_until_instant(next);
+        let late = SystemTimer::ticks_between(next.ticks(), SystemTimer::now()) / TICKS_PER_US;
+        max_late = max_late.max(late);
+
+        if iteration % 100 == 0 {
+            let elapsed = SystemTimer::ticks_between(start.ticks(), SystemTimer::now());
+            let ideal = iteration * 10_000 * TICKS_PER_US;
+            let drift = elapsed.abs_diff(ideal) / TICKS_PER_US;
+
+            println!(
+                "{} iterations: drift {} us, latest wake-up {} us",
+                iteration, drift, max_late
+            );
+            max_late = 0;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Freezes the SYSTIMER while the CPU is halted by a debugger
+//!
+//! This is a manual test, run it under a debugger (e.g. with probe-rs or
+//! OpenOCD and GDB):
+//! - set a breakpoint on `halt_here`
+//! - once it is hit, wait a few seconds before continuing
+//! - the printed time across the breakpoint stays at a few microseconds
+//!
+//! Change `STALL_ON_DEBUG` to `false` to see the time spent halted counted.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const STALL_ON_DEBUG: bool = true;
+
+#[inline(never)]
+fn halt_here() {
+    core::hint::black_box(());
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut systimer = SystemTimer::new(peripherals.SYSTIMER);
+    systimer.stall_on_debug(STALL_ON_DEBUG);
+
+    loop {
+        let before = SystemTimer::now();
+        halt_here();
+        let after = SystemTimer::now();
+
+        println!(
+            "{} us across the breakpoint",
+            SystemTimer::ticks_between(before, after) * 1_000_000 / SystemTimer::TICKS_PER_SECOND
+        );
+
+        SystemTimer::wait_until(after + SystemTimer::TICKS_PER_SECOND);
+    }
+}
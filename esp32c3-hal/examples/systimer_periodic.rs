@@ -0,0 +1,87 @@
+//! Runs a 4 kHz tick from a periodic `SYSTIMER` alarm
+//!
+//! The alarm reloads itself in hardware, the interrupt handler only counts the
+//! ticks. The period of 250 µs isn't a whole number of milliseconds, so it is
+//! set in counter ticks. Once a second the number of ticks is printed, it
+//! should go up by 4000 each time.
+
+#![no_std]
+#![no_main]
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{self, Peripherals},
+    prelude::*,
+    systimer::{Alarm, Periodic, SystemTimer},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const PERIOD_TICKS: u32 = (SystemTimer::TICKS_PER_SECOND / 4000) as u32;
+
+static ALARM0: Mutex<RefCell<Option<Alarm<Periodic, 0>>>> = Mutex::new(RefCell::new(None));
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let syst = SystemTimer::new(peripherals.SYSTIMER);
+
+    let alarm0 = syst.alarm0.into_periodic();
+    alarm0.set_period_ticks(PERIOD_TICKS);
+    alarm0.clear_interrupt();
+    alarm0.interrupt_enable(true);
+
+    critical_section::with(|cs| ALARM0.borrow_ref_mut(cs).replace(alarm0));
+
+    interrupt::enable(
+        peripherals::Interrupt::SYSTIMER_TARGET0,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        delay.delay_ms(1000u32);
+        println!("{} ticks", TICKS.load(Ordering::Relaxed));
+    }
+}
+
+#[interrupt]
+fn SYSTIMER_TARGET0() {
+    critical_section::with(|cs| {
+        ALARM0
+            .borrow_ref_mut(cs)
+            .as_mut()
+            .unwrap()
+            .clear_interrupt()
+    });
+
+    TICKS.fetch_add(1, Ordering::Relaxed);
+}
@@ -0,0 +1,76 @@
+//! Runs a 1 kHz loop with `Scheduler` and checks it doesn't drift
+//!
+//! Every iteration does a varying amount of work and then waits for the next
+//! deadline. After 5 seconds the time elapsed is compared against 5000 periods:
+//! the loop ends within a few microseconds of the ideal time, however long the
+//! individual iterations took.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::{Scheduler, SystemTimer},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const TICKS_PER_US: u64 = SystemTimer::TICKS_PER_SECOND / 1_000_000;
+const ITERATIONS: u64 = 5000;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let delay = Delay::new(&clocks);
+
+    loop {
+        let mut scheduler = Scheduler::new(1u64.millis());
+        let start = scheduler.last_deadline();
+
+        for iteration in 0..ITERATIONS {
+            // simulate work taking between 0 and 900 us
+            delay.delay((iteration % 10) as u32 * 100);
+
+            if let Err(overrun) = scheduler.wait_next() {
+                println!(
+                    "Iteration {} missed {} deadlines",
+                    iteration, overrun.missed
+                );
+            }
+        }
+
+        let elapsed = SystemTimer::ticks_between(start, SystemTimer::now());
+        let ideal = ITERATIONS * 1000 * TICKS_PER_US;
+        let drift = elapsed.abs_diff(ideal) / TICKS_PER_US;
+
+        println!(
+            "{} iterations in {} us, drift {} us, {} overruns",
+            ITERATIONS,
+            elapsed / TICKS_PER_US,
+            drift,
+            scheduler.overruns()
+        );
+        assert_eq!(scheduler.overruns(), 0);
+        assert!(drift < 10);
+    }
+}
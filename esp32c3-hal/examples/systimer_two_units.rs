@@ -0,0 +1,74 @@
+//! Counts running time and time halted by a debugger with the two SYSTIMER
+//! units
+//!
+//! Unit 0 keeps counting while the CPU is halted, unit 1 is frozen. The
+//! difference between them is the time spent halted.
+//!
+//! This is a manual test, run it under a debugger (e.g. with probe-rs or
+//! OpenOCD and GDB):
+//! - set a breakpoint on `halt_here`
+//! - once it is hit, wait a few seconds before continuing
+//! - the printed halted time goes up by the time waited
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const TICKS_PER_MS: u64 = SystemTimer::TICKS_PER_SECOND / 1000;
+
+#[inline(never)]
+fn halt_here() {
+    core::hint::black_box(());
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut systimer = SystemTimer::new(peripherals.SYSTIMER);
+    systimer.stall_on_debug(false);
+    systimer.stall_unit1_on_debug(true);
+    systimer.enable_unit1(true);
+
+    let start0 = SystemTimer::now();
+    let start1 = SystemTimer::now_unit1();
+
+    loop {
+        halt_here();
+
+        let total = SystemTimer::ticks_between(start0, SystemTimer::now());
+        let running = SystemTimer::ticks_between(start1, SystemTimer::now_unit1());
+
+        println!(
+            "{} ms since start, {} ms of it halted",
+            total / TICKS_PER_MS,
+            total.saturating_sub(running) / TICKS_PER_MS
+        );
+
+        SystemTimer::wait_until(SystemTimer::now() + SystemTimer::TICKS_PER_SECOND);
+    }
+}
@@ -0,0 +1,83 @@
+//! Periodic tick from a TIMG timer with a handler registered at runtime
+//!
+//! Timer 0 of TIMG0 reloads itself every 100 ms and raises `TG0_T0_LEVEL`,
+//! which is handled by a plain function registered with
+//! `interrupt::set_handler`.
+
+#![no_std]
+#![no_main]
+
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{self, Peripherals, TIMG0},
+    prelude::*,
+    riscv,
+    timer::{Timer, Timer0, TimerGroup},
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+static TIMER0: Mutex<RefCell<Option<Timer<Timer0<TIMG0>>>>> = Mutex::new(RefCell::new(None));
+static TICKS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+fn on_tick() {
+    critical_section::with(|cs| {
+        let mut timer0 = TIMER0.borrow_ref_mut(cs);
+        let timer0 = timer0.as_mut().unwrap();
+
+        // the counter reloads itself, the alarm has to be re-enabled
+        timer0.clear_interrupt();
+        timer0.set_alarm_active(true);
+
+        TICKS.borrow(cs).set(TICKS.borrow(cs).get() + 1);
+    });
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut timer0 = timer_group0.timer0;
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    timer0.start(100u64.millis());
+    timer0.listen();
+    critical_section::with(|cs| TIMER0.borrow_ref_mut(cs).replace(timer0));
+
+    interrupt::set_handler(
+        peripherals::Interrupt::TG0_T0_LEVEL,
+        on_tick,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut last = 0;
+    loop {
+        let ticks = critical_section::with(|cs| TICKS.borrow(cs).get());
+        if ticks != last && ticks % 10 == 0 {
+            println!("{} ticks, {} s", ticks, ticks / 10);
+        }
+        last = ticks;
+    }
+}
@@ -0,0 +1,57 @@
+//! Measures elapsed time with the counter of a TIMG timer
+//!
+//! Timer 0 of TIMG0 free runs at 1 MHz and is read with `now()` before and
+//! after a piece of code, without using an alarm or an interrupt.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::{Instance, TimerGroup},
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut timer0 = timer_group0.timer0;
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    // one tick per microsecond
+    timer0.set_divider(clocks.apb_clock.to_MHz()).unwrap();
+    println!("Counting at {} Hz", timer0.tick_frequency().raw());
+    timer0.reset_counter();
+    timer0.set_counter_decrementing(false);
+    timer0.set_counter_active(true);
+
+    loop {
+        for n in [1_000u32, 10_000, 100_000] {
+            let start = timer0.now();
+            let mut sum = 0u32;
+            for i in 0..n {
+                sum = core::hint::black_box(sum.wrapping_add(i));
+            }
+            let elapsed = timer0.now() - start;
+
+            println!("{} additions (sum {}): {} us", n, sum, elapsed);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Turns an LED off 500 ms after turning it on
+//!
+//! The LED is switched off by a callback the TIMG1 timer runs from its
+//! interrupt, the main code neither waits for it nor runs an executor. It
+//! sleeps in `wfi` until an interrupt arrives.
+//!
+//! The following wiring is assumed:
+//! - LED => GPIO5
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::{Gpio5, Output, PushPull, IO},
+    interrupt,
+    peripherals::{self, Peripherals, TIMG1},
+    prelude::*,
+    riscv,
+    timer::{Timer, Timer0, TimerGroup},
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+static LED: Mutex<RefCell<Option<Gpio5<Output<PushPull>>>>> = Mutex::new(RefCell::new(None));
+static TIMER1: Mutex<RefCell<Option<Timer<Timer0<TIMG1>>>>> = Mutex::new(RefCell::new(None));
+
+fn led_off() {
+    critical_section::with(|cs| {
+        LED.borrow_ref_mut(cs).as_mut().unwrap().set_low().unwrap();
+    });
+    println!("LED off");
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+    let mut timer1 = timer_group1.timer0;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut led = io.pins.gpio5.into_push_pull_output();
+
+    interrupt::enable(
+        peripherals::Interrupt::TG1_T0_LEVEL,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    led.set_high().unwrap();
+    println!("LED on");
+    timer1.schedule_once(500u64.millis(), led_off);
+
+    critical_section::with(|cs| {
+        LED.borrow_ref_mut(cs).replace(led);
+        TIMER1.borrow_ref_mut(cs).replace(timer1);
+    });
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    loop {
+        unsafe { riscv::asm::wfi() };
+    }
+}
+
+#[interrupt]
+fn TG1_T0_LEVEL() {
+    critical_section::with(|cs| {
+        TIMER1.borrow_ref_mut(cs).as_mut().unwrap().on_interrupt();
+    });
+}
@@ -0,0 +1,77 @@
+//! Uses a deep sleep wake stub to count wake ups without booting.
+//!
+//! The chip sleeps for one second at a time. On every wake up the stub in RTC
+//! fast memory increments a counter and goes straight back to sleep, only
+//! every tenth wake up continues to boot the application which prints the
+//! counter.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    macros::ram,
+    peripherals::Peripherals,
+    prelude::*,
+    rtc_cntl::{wake_stub, WakeupSource},
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+const MAGIC: u32 = 0xc0ffee;
+
+#[ram(rtc_fast, uninitialized)]
+static mut VALID: u32 = 0;
+
+#[ram(rtc_fast, uninitialized)]
+static mut WAKE_COUNT: u32 = 0;
+
+#[ram(rtc_fast, uninitialized)]
+static mut SLEEP_TICKS: u64 = 0;
+
+#[ram(rtc_fast)]
+unsafe extern "C" fn wake_stub() {
+    WAKE_COUNT += 1;
+
+    if WAKE_COUNT % 10 != 0 {
+        wake_stub::sleep(SLEEP_TICKS);
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    unsafe {
+        // RTC memory content is random after power-on
+        if VALID != MAGIC {
+            VALID = MAGIC;
+            WAKE_COUNT = 0;
+        }
+
+        println!("Woke up {} times", WAKE_COUNT);
+
+        SLEEP_TICKS = rtc.duration_to_ticks(1u64.secs());
+    }
+
+    rtc.set_wake_stub(Some(wake_stub));
+    let err = rtc.sleep_deep(&[WakeupSource::Timer(1u64.secs())]);
+    panic!("Can't enter deep sleep: {:?}", err);
+}
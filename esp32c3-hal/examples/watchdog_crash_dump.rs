@@ -0,0 +1,94 @@
+//! Logs a hang diagnostic before the watchdog resets the system
+//!
+//! The TIMG0 watchdog is configured to raise an interrupt when it expires and
+//! to reset the system one timeout period later. The main loop feeds the
+//! watchdog a few times and then simulates a hang. The interrupt handler steals
+//! UART0 to print where the application got stuck, then lets the reset
+//! happen.
+
+#![no_std]
+#![no_main]
+
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    interrupt,
+    peripherals::{self, Peripherals, UART0},
+    prelude::*,
+    riscv,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+    Uart,
+};
+use esp_backtrace as _;
+
+// Updated by the application as it makes progress, read by the crash handler
+static CHECKPOINT: AtomicU32 = AtomicU32::new(0);
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let mut serial0 = Uart::new(peripherals.UART0);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt1.disable();
+
+    wdt0.listen();
+    wdt0.start(2u64.secs());
+
+    interrupt::enable(
+        peripherals::Interrupt::TG0_WDT_LEVEL,
+        interrupt::Priority::Priority1,
+    )
+    .unwrap();
+
+    unsafe {
+        riscv::interrupt::enable();
+    }
+
+    let mut delay = Delay::new(&clocks);
+
+    for checkpoint in 1..=5 {
+        CHECKPOINT.store(checkpoint, Ordering::Relaxed);
+        writeln!(serial0, "Checkpoint {}", checkpoint).ok();
+
+        delay.delay_ms(500u32);
+        wdt0.feed();
+    }
+
+    writeln!(serial0, "Hanging...").ok();
+    loop {}
+}
+
+#[interrupt]
+fn TG0_WDT_LEVEL() {
+    // The application may be holding its serial driver, so don't wait for it.
+    // Creating a new driver from the stolen peripheral keeps the baud rate.
+    let mut serial = Uart::new(unsafe { UART0::steal() });
+
+    writeln!(
+        serial,
+        "Watchdog expired after checkpoint {}, resetting",
+        CHECKPOINT.load(Ordering::Relaxed)
+    )
+    .ok();
+
+    // Not feeding the watchdog, the second stage resets the system. Clear the
+    // interrupt so the handler doesn't run again in the meantime.
+    let mut wdt = esp32c3_hal::timer::Wdt::<peripherals::TIMG0>::new();
+    wdt.clear_interrupt();
+}
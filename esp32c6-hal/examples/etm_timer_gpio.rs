@@ -0,0 +1,74 @@
+//! Toggles GPIOs from a timer through the ETM, without the CPU
+//!
+//! SYSTIMER alarm 0 fires every millisecond and toggles GPIO4 through ETM
+//! channel 0, which gives a 500 Hz square wave. Every rising edge of GPIO4 in
+//! turn toggles GPIO5 through ETM channel 1. The CPU only sets this up and
+//! then idles, the delay between the edges of GPIO4 and GPIO5 seen on a scope
+//! is the latency of the ETM.
+//!
+//! The following wiring is assumed:
+//! - GPIO4 => scope channel 1
+//! - GPIO5 => scope channel 2
+
+#![no_std]
+#![no_main]
+
+use esp32c6_hal::{
+    clock::ClockControl,
+    etm::{Etm, Event, Task},
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.PCR.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.LP_CLKRST);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut square = io.pins.gpio4.into_push_pull_output();
+    // the rising edges of GPIO4 are an event as well
+    square.enable_input(true);
+    let follower = io.pins.gpio5.into_push_pull_output();
+
+    let mut etm = Etm::new(peripherals.SOC_ETM, &mut system.peripheral_clock_control);
+    etm.add_gpio_to_task_channel(0, &square);
+    etm.set_gpio_event_channel(0, &square);
+    etm.add_gpio_to_task_channel(1, &follower);
+
+    let mut timer_to_square = etm.get_channel(0);
+    timer_to_square.bind(Event::SystimerAlarm0, Task::GpioToggle(0));
+    timer_to_square.enable();
+
+    let mut square_to_follower = etm.get_channel(1);
+    square_to_follower.bind(Event::GpioRisingEdge(0), Task::GpioToggle(1));
+    square_to_follower.enable();
+
+    let mut syst = SystemTimer::new(peripherals.SYSTIMER);
+    syst.enable_etm(true);
+    let alarm0 = syst.alarm0.into_periodic();
+    alarm0.set_period(1u32.kHz());
+
+    println!("GPIO4 and GPIO5 are toggled by the ETM now");
+
+    loop {}
+}
@@ -4,8 +4,6 @@
 #![no_std]
 #![no_main]
 
-use core::fmt::Write;
-
 use esp32c6_hal::{
     clock::ClockControl,
     peripherals::Peripherals,
@@ -15,7 +13,6 @@ use esp32c6_hal::{
     Uart,
 };
 use esp_backtrace as _;
-use nb::block;
 
 #[entry]
 fn main() -> ! {
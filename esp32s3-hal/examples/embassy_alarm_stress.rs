@@ -0,0 +1,128 @@
+//! Sets the same embassy alarm from both cores
+//!
+//! Both cores keep moving a single alarm of the time driver to a random time
+//! shortly ahead. The callback checks that it never runs before the latest
+//! timestamp that was set, and both cores check that an armed alarm is never
+//! lost.
+//!
+//! Build with either `embassy-time-systick` or `embassy-time-timg0`.
+
+#![no_std]
+#![no_main]
+
+use core::cell::Cell;
+
+use critical_section::Mutex;
+use embassy_time::{
+    driver::{self, AlarmHandle},
+    Duration,
+    Instant,
+};
+use esp32s3_hal::{
+    clock::ClockControl,
+    cpu_control::CpuControl,
+    embassy,
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+// the alarm is due, `u64::MAX` while disarmed
+static TARGET: Mutex<Cell<u64>> = Mutex::new(Cell::new(u64::MAX));
+static FIRED: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+fn on_alarm(_ctx: *mut ()) {
+    critical_section::with(|cs| {
+        let now = Instant::now().as_ticks();
+        let target = TARGET.borrow(cs).get();
+
+        assert!(target != u64::MAX, "Alarm fired while disarmed");
+        assert!(now >= target, "Alarm fired early: {} < {}", now, target);
+
+        TARGET.borrow(cs).set(u64::MAX);
+        FIRED.borrow(cs).set(FIRED.borrow(cs).get() + 1);
+    });
+}
+
+fn stress(alarm: AlarmHandle, core: u32) -> ! {
+    let mut seed = core + 1;
+    let slack = Duration::from_millis(1).as_ticks();
+    let mut rounds = 0u32;
+
+    loop {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        let offset = Duration::from_micros((seed >> 16) as u64 % 200).as_ticks();
+
+        critical_section::with(|cs| {
+            let now = Instant::now().as_ticks();
+            let target = TARGET.borrow(cs).get();
+            assert!(
+                target == u64::MAX || now < target + slack,
+                "Alarm for {} lost at {}",
+                target,
+                now
+            );
+
+            let timestamp = now + offset;
+            let armed = driver::set_alarm(alarm, timestamp);
+            TARGET
+                .borrow(cs)
+                .set(if armed { timestamp } else { u64::MAX });
+        });
+
+        // give the alarm a chance to fire now and then
+        let until = Instant::now().as_ticks() + offset / 2;
+        while Instant::now().as_ticks() < until {}
+
+        rounds += 1;
+        if rounds % 100_000 == 0 {
+            let fired = critical_section::with(|cs| FIRED.borrow(cs).get());
+            println!(
+                "Core {}: {} rounds, alarm fired {} times",
+                core, rounds, fired
+            );
+        }
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt0.disable();
+    wdt1.disable();
+    rtc.rwdt.disable();
+
+    #[cfg(feature = "embassy-time-systick")]
+    embassy::init(
+        &clocks,
+        esp32s3_hal::systimer::SystemTimer::new(peripherals.SYSTIMER),
+    );
+
+    #[cfg(feature = "embassy-time-timg0")]
+    embassy::init(&clocks, timer_group0.timer0);
+
+    // the alarm interrupt is handled by this core, the app core only sets it
+    let alarm = unsafe { driver::allocate_alarm() }.unwrap();
+    driver::set_alarm_callback(alarm, on_alarm, core::ptr::null_mut());
+
+    let mut cpu_control = CpuControl::new(system.cpu_control);
+    let mut cpu1_fnctn = || {
+        stress(alarm, 1);
+    };
+    let _guard = cpu_control.start_app_core(&mut cpu1_fnctn).unwrap();
+
+    stress(alarm, 0)
+}
@@ -0,0 +1,96 @@
+//! Timestamps pulses with the MCPWM capture channels
+//!
+//! Operator0 outputs a 1 kHz signal with a 25% duty cycle on GPIO4. Capture
+//! channel 0 latches the capture timer on both edges of it at GPIO5, which
+//! gives the period and the pulse width with a resolution of one APB clock
+//! cycle (12.5 ns), independent of when the timestamps are read.
+//!
+//! The following wiring is assumed:
+//! - GPIO4 => GPIO5
+
+#![no_std]
+#![no_main]
+
+use esp32s3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    mcpwm::{
+        capture::{CaptureEdge, Edge},
+        operator::PwmPinConfig,
+        timer::PwmWorkingMode,
+        PeripheralClockConfig,
+        MCPWM,
+    },
+    peripherals::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable watchdog timer
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let clock_cfg = PeripheralClockConfig::with_frequency(&clocks, 40u32.MHz()).unwrap();
+    let mut mcpwm = MCPWM::new(
+        peripherals.PWM0,
+        clock_cfg,
+        &mut system.peripheral_clock_control,
+    );
+
+    mcpwm.operator0.set_timer(&mcpwm.timer0);
+    let mut pwm_pin = mcpwm
+        .operator0
+        .with_pin_a(io.pins.gpio4, PwmPinConfig::UP_ACTIVE_HIGH);
+    let timer_clock_cfg = clock_cfg
+        .timer_clock_with_frequency(99, PwmWorkingMode::Increase, 1u32.kHz())
+        .unwrap();
+    mcpwm.timer0.start(timer_clock_cfg);
+    pwm_pin.set_timestamp(25);
+
+    let mut capture = mcpwm
+        .capture0
+        .capture_timestamp_on_edge(io.pins.gpio5, CaptureEdge::Both);
+
+    let ticks_to_ns = |ticks: u32| ticks as u64 * 1_000_000_000 / clocks.apb_clock.to_Hz() as u64;
+
+    loop {
+        // collect the timestamps first, printing is slower than the signal
+        let mut timestamps = [None; 16];
+        for timestamp in timestamps.iter_mut() {
+            *timestamp = Some(nb::block!(capture.timestamp()).unwrap());
+        }
+
+        let mut last_rise = None;
+        for timestamp in timestamps.into_iter().flatten() {
+            match (timestamp.edge, last_rise) {
+                (Edge::Rising, Some(last_rise)) => {
+                    let period = timestamp.ticks.wrapping_sub(last_rise);
+                    println!("Period: {} ns", ticks_to_ns(period));
+                }
+                (Edge::Falling, Some(last_rise)) => {
+                    let width = timestamp.ticks.wrapping_sub(last_rise);
+                    println!("Pulse width: {} ns", ticks_to_ns(width));
+                }
+                _ => {}
+            }
+            if timestamp.edge == Edge::Rising {
+                last_rise = Some(timestamp.ticks);
+            }
+        }
+    }
+}
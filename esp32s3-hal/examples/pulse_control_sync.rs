@@ -0,0 +1,130 @@
+//! Refreshes four LED strips in sync
+//!
+//! Each RMT channel drives a strip of eight SK68XX/WS2812 LEDs, the channels
+//! are started together with `sync_transmit` so all strips latch their new
+//! colors at the same time. A dot runs along the strips, check with a logic
+//! analyzer that the data on all four pins starts on the same edge.
+//!
+//! The following wiring is assumed:
+//! - GPIO4 => DIN of strip 0
+//! - GPIO5 => DIN of strip 1
+//! - GPIO6 => DIN of strip 2
+//! - GPIO7 => DIN of strip 3
+//!
+//! The correct output is only achieved when running in release mode.
+
+#![no_std]
+#![no_main]
+
+use esp32s3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    peripherals::Peripherals,
+    prelude::*,
+    pulse_control::{self, ClockSource, OutputChannel, PulseCode},
+    timer::TimerGroup,
+    Delay,
+    PulseControl,
+    Rtc,
+};
+use esp_backtrace as _;
+
+const LEDS: usize = 8;
+// 24 bits per LED and the end marker
+const CODES: usize = LEDS * 24 + 1;
+
+// RMT ticks at 80 MHz
+const T0H: u32 = 25;
+const T0L: u32 = 70;
+const T1H: u32 = 51;
+const T1L: u32 = 44;
+
+// Encode GRB colors into the raw pulse codes of one strip
+fn encode(colors: &[[u8; 3]; LEDS], codes: &mut [u32; CODES]) {
+    let bits = colors
+        .iter()
+        .flat_map(|color| color.iter())
+        .flat_map(|byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0));
+
+    for (code, bit) in codes.iter_mut().zip(bits) {
+        let (high, low) = if bit { (T1H, T1L) } else { (T0H, T0L) };
+        *code = PulseCode {
+            level1: true,
+            length1: high.nanos(),
+            level2: false,
+            length2: low.nanos(),
+        }
+        .into();
+    }
+
+    codes[CODES - 1] = 0;
+}
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // Configure RMT peripheral globally
+    let pulse = PulseControl::new(
+        peripherals.RMT,
+        &mut system.peripheral_clock_control,
+        ClockSource::APB,
+        0,
+        0,
+        0,
+    )
+    .unwrap();
+
+    // All channels need the same divider to stay aligned
+    let mut rmt_channel0 = pulse.channel0;
+    let mut rmt_channel1 = pulse.channel1;
+    let mut rmt_channel2 = pulse.channel2;
+    let mut rmt_channel3 = pulse.channel3;
+    rmt_channel0.set_channel_divider(1).set_idle_output(true);
+    rmt_channel1.set_channel_divider(1).set_idle_output(true);
+    rmt_channel2.set_channel_divider(1).set_idle_output(true);
+    rmt_channel3.set_channel_divider(1).set_idle_output(true);
+
+    let mut rmt_channel0 = rmt_channel0.assign_pin(io.pins.gpio4);
+    let mut rmt_channel1 = rmt_channel1.assign_pin(io.pins.gpio5);
+    let mut rmt_channel2 = rmt_channel2.assign_pin(io.pins.gpio6);
+    let mut rmt_channel3 = rmt_channel3.assign_pin(io.pins.gpio7);
+
+    // dim green, red, blue and white for the four strips
+    let dots = [[16, 0, 0], [0, 16, 0], [0, 0, 16], [16, 16, 16]];
+    let mut strips = [[0u32; CODES]; 4];
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        for position in 0..LEDS {
+            for (strip, dot) in strips.iter_mut().zip(dots) {
+                let mut colors = [[0; 3]; LEDS];
+                colors[position] = dot;
+                encode(&colors, strip);
+            }
+
+            pulse_control::sync_transmit(&mut [
+                (&mut rmt_channel0, &strips[0][..]),
+                (&mut rmt_channel1, &strips[1][..]),
+                (&mut rmt_channel2, &strips[2][..]),
+                (&mut rmt_channel3, &strips[3][..]),
+            ])
+            .unwrap();
+
+            delay.delay_ms(100u32);
+        }
+    }
+}
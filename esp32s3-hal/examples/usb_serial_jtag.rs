@@ -7,7 +7,7 @@
 #![no_std]
 #![no_main]
 
-use core::{cell::RefCell, fmt::Write};
+use core::cell::RefCell;
 
 use critical_section::Mutex;
 use esp32s3_hal::{
@@ -20,7 +20,6 @@ use esp32s3_hal::{
     UsbSerialJtag,
 };
 use esp_backtrace as _;
-use nb::block;
 
 static USB_SERIAL: Mutex<RefCell<Option<UsbSerialJtag<USB_DEVICE>>>> =
     Mutex::new(RefCell::new(None));